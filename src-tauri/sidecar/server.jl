@@ -0,0 +1,92 @@
+#!/usr/bin/env julia
+#
+# Long-lived Friedman CLI session.
+# Started once by the Tauri backend in dev mode so that Julia startup and JIT
+# compilation are paid only once per app session instead of once per command.
+#
+# Usage: julia --project=src-tauri/sidecar --startup-file=no server.jl
+#
+# Protocol (stdin/stdout, one request at a time):
+#
+#   -> FRIEDMAN-REQ <nargs> [<nenv>]\n
+#      then, for each argument and then each KEY=VALUE environment entry:
+#      <byte length>\n<bytes>\n
+#   <- FRIEDMAN-RESP <exit code> <byte length>\n<captured stdout bytes>
+#
+# The session writes FRIEDMAN-READY on stdout once Friedman is loaded.
+# stderr is passed through untouched; after each request a line consisting of
+# the record separator (0x1e) followed by FRIEDMAN-END marks the end of that
+# request's stderr. Environment entries (the job's scratch TMPDIR) are set
+# only while that request runs.
+
+using Friedman
+
+const PROTOCOL_OUT = stdout
+
+function read_field(io::IO)
+    len = parse(Int, readline(io))
+    field = String(read(io, len))
+    read(io, UInt8)  # trailing newline
+    return field
+end
+
+function read_request(io::IO)
+    header = readline(io)
+    isempty(header) && return nothing
+    parts = split(header)
+    parts[1] == "FRIEDMAN-REQ" || error("unexpected frame header: $header")
+    nargs = parse(Int, parts[2])
+    nenv = length(parts) >= 3 ? parse(Int, parts[3]) : 0
+
+    args = String[read_field(io) for _ in 1:nargs]
+    env = Pair{String,String}[]
+    for _ in 1:nenv
+        key, value = split(read_field(io), '='; limit=2)
+        push!(env, String(key) => String(value))
+    end
+    return args, env
+end
+
+function run_request(args::Vector{String}, env::Vector{Pair{String,String}})
+    status = 0
+    # Capture stdout via a temp file (redirect_stdout doesn't accept IOBuffer in Julia 1.12+)
+    output = mktemp() do path, io
+        try
+            withenv(env...) do
+                redirect_stdout(io) do
+                    Friedman.main(args)
+                end
+            end
+        catch e
+            e isa InterruptException && rethrow()
+            status = 1
+            showerror(stderr, e, catch_backtrace())
+            println(stderr)
+        end
+        flush(io)
+        read(path)
+    end
+    return status, output
+end
+
+function serve()
+    println(PROTOCOL_OUT, "FRIEDMAN-READY")
+    flush(PROTOCOL_OUT)
+
+    while true
+        request = read_request(stdin)
+        request === nothing && break
+
+        status, output = run_request(request...)
+
+        flush(stderr)
+        print(stderr, "\x1eFRIEDMAN-END\n")
+        flush(stderr)
+
+        write(PROTOCOL_OUT, "FRIEDMAN-RESP $status $(length(output))\n")
+        write(PROTOCOL_OUT, output)
+        flush(PROTOCOL_OUT)
+    end
+end
+
+serve()
@@ -1,8 +1,9 @@
 use serde::Deserialize;
 use tauri::AppHandle;
 
+use crate::dataset::input::DataInput;
 use crate::error::FriedmanError;
-use crate::sidecar::run_friedman_command;
+use crate::sidecar::{run_friedman_command, CommandOutput};
 
 // ---------------------------------------------------------------------------
 // Parameter structs — matching actual CLI v0.1.3
@@ -10,7 +11,8 @@ use crate::sidecar::run_friedman_command;
 
 #[derive(Debug, Deserialize)]
 pub struct ArimaEstimateParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default = "default_column")]
     pub column: u32,
     #[serde(default)]
@@ -34,7 +36,8 @@ pub struct ArimaEstimateParams {
 
 #[derive(Debug, Deserialize)]
 pub struct ArimaForecastParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default = "default_column")]
     pub column: u32,
     #[serde(default)]
@@ -66,13 +69,14 @@ fn default_confidence() -> f64 { 0.95 }
 pub async fn arima_estimate(
     app: AppHandle,
     params: ArimaEstimateParams,
-) -> Result<serde_json::Value, FriedmanError> {
-    let column = params.column.to_string();
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
+    let column = data.column(params.column)?;
     let d = params.d.to_string();
     let q = params.q.to_string();
 
     let mut args = vec![
-        "arima", "estimate", &params.data,
+        "arima", "estimate", data.path(),
         "--column", &column,
         "--d", &d,
         "--q", &q,
@@ -110,7 +114,9 @@ pub async fn arima_estimate(
         }
     }
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman arima forecast <data> [--column N] [--p N] [--d N] [--q N] [--horizons N] [--confidence F] [--method css_mle]`
@@ -118,15 +124,16 @@ pub async fn arima_estimate(
 pub async fn arima_forecast(
     app: AppHandle,
     params: ArimaForecastParams,
-) -> Result<serde_json::Value, FriedmanError> {
-    let column = params.column.to_string();
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
+    let column = data.column(params.column)?;
     let d = params.d.to_string();
     let q = params.q.to_string();
     let horizons = params.horizons.to_string();
     let confidence = params.confidence.to_string();
 
     let mut args = vec![
-        "arima", "forecast", &params.data,
+        "arima", "forecast", data.path(),
         "--column", &column,
         "--d", &d,
         "--q", &q,
@@ -142,5 +149,7 @@ pub async fn arima_forecast(
         args.push(&p_str);
     }
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
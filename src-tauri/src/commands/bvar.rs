@@ -1,12 +1,14 @@
 use serde::Deserialize;
 use tauri::AppHandle;
 
+use crate::dataset::input::DataInput;
 use crate::error::FriedmanError;
-use crate::sidecar::run_friedman_command;
+use crate::sidecar::{job_id_or_new, run_friedman_command_with_progress, CommandOutput};
 
 #[derive(Debug, Deserialize)]
 pub struct BvarEstimateParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default = "default_lags")]
     pub lags: u32,
     #[serde(default = "default_prior")]
@@ -21,7 +23,8 @@ pub struct BvarEstimateParams {
 
 #[derive(Debug, Deserialize)]
 pub struct BvarPosteriorParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default = "default_lags")]
     pub lags: u32,
     #[serde(default = "default_draws")]
@@ -36,7 +39,8 @@ pub struct BvarPosteriorParams {
 
 #[derive(Debug, Deserialize)]
 pub struct BvarIrfParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default)]
     pub lags: Option<u32>,
     #[serde(default = "default_shock")]
@@ -55,7 +59,8 @@ pub struct BvarIrfParams {
 
 #[derive(Debug, Deserialize)]
 pub struct BvarFevdParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default)]
     pub lags: Option<u32>,
     #[serde(default = "default_horizons")]
@@ -72,7 +77,8 @@ pub struct BvarFevdParams {
 
 #[derive(Debug, Deserialize)]
 pub struct BvarHdParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default)]
     pub lags: Option<u32>,
     #[serde(default = "default_id")]
@@ -87,7 +93,8 @@ pub struct BvarHdParams {
 
 #[derive(Debug, Deserialize)]
 pub struct BvarForecastParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default)]
     pub lags: Option<u32>,
     #[serde(default = "default_horizons")]
@@ -110,16 +117,20 @@ fn default_horizons() -> u32 { 20 }
 fn default_id() -> String { "cholesky".into() }
 
 /// `friedman bvar estimate <data> [--lags N] [--prior minnesota] [--draws N] [--sampler nuts] [--config path]`
+///
+/// Sampler progress goes to `friedman://progress/{job_id}`; pass `job_id` to subscribe first.
 #[tauri::command]
 pub async fn bvar_estimate(
     app: AppHandle,
     params: BvarEstimateParams,
-) -> Result<serde_json::Value, FriedmanError> {
+    job_id: Option<String>,
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
     let lags = params.lags.to_string();
     let draws = params.draws.to_string();
 
     let mut args = vec![
-        "bvar", "estimate", &params.data,
+        "bvar", "estimate", data.path(),
         "--lags", &lags,
         "--prior", &params.prior,
         "--draws", &draws,
@@ -131,20 +142,27 @@ pub async fn bvar_estimate(
         args.push(&params.config);
     }
 
-    run_friedman_command(&app, args).await
+    let job_id = job_id_or_new(job_id);
+    run_friedman_command_with_progress(&app, &job_id, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman bvar posterior <data> [--lags N] [--draws N] [--sampler nuts] [--method mean]`
+///
+/// Sampler progress goes to `friedman://progress/{job_id}`; pass `job_id` to subscribe first.
 #[tauri::command]
 pub async fn bvar_posterior(
     app: AppHandle,
     params: BvarPosteriorParams,
-) -> Result<serde_json::Value, FriedmanError> {
+    job_id: Option<String>,
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
     let lags = params.lags.to_string();
     let draws = params.draws.to_string();
 
     let mut args = vec![
-        "bvar", "posterior", &params.data,
+        "bvar", "posterior", data.path(),
         "--lags", &lags,
         "--draws", &draws,
         "--sampler", &params.sampler,
@@ -156,21 +174,28 @@ pub async fn bvar_posterior(
         args.push(&params.config);
     }
 
-    run_friedman_command(&app, args).await
+    let job_id = job_id_or_new(job_id);
+    run_friedman_command_with_progress(&app, &job_id, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman bvar irf <data> [--lags N] [--shock N] [--horizons N] [--id cholesky] [--draws N] [--sampler nuts] [--config path]`
+///
+/// Sampler progress goes to `friedman://progress/{job_id}`; pass `job_id` to subscribe first.
 #[tauri::command]
 pub async fn bvar_irf(
     app: AppHandle,
     params: BvarIrfParams,
-) -> Result<serde_json::Value, FriedmanError> {
+    job_id: Option<String>,
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
     let shock = params.shock.to_string();
     let horizons = params.horizons.to_string();
     let draws = params.draws.to_string();
 
     let mut args = vec![
-        "bvar", "irf", &params.data,
+        "bvar", "irf", data.path(),
         "--shock", &shock,
         "--horizons", &horizons,
         "--id", &params.id,
@@ -190,20 +215,27 @@ pub async fn bvar_irf(
         args.push(&params.config);
     }
 
-    run_friedman_command(&app, args).await
+    let job_id = job_id_or_new(job_id);
+    run_friedman_command_with_progress(&app, &job_id, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman bvar fevd <data> [--lags N] [--horizons N] [--id cholesky] [--draws N] [--sampler nuts] [--config path]`
+///
+/// Sampler progress goes to `friedman://progress/{job_id}`; pass `job_id` to subscribe first.
 #[tauri::command]
 pub async fn bvar_fevd(
     app: AppHandle,
     params: BvarFevdParams,
-) -> Result<serde_json::Value, FriedmanError> {
+    job_id: Option<String>,
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
     let horizons = params.horizons.to_string();
     let draws = params.draws.to_string();
 
     let mut args = vec![
-        "bvar", "fevd", &params.data,
+        "bvar", "fevd", data.path(),
         "--horizons", &horizons,
         "--id", &params.id,
         "--draws", &draws,
@@ -222,19 +254,26 @@ pub async fn bvar_fevd(
         args.push(&params.config);
     }
 
-    run_friedman_command(&app, args).await
+    let job_id = job_id_or_new(job_id);
+    run_friedman_command_with_progress(&app, &job_id, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman bvar hd <data> [--lags N] [--id cholesky] [--draws N] [--sampler nuts] [--config path]`
+///
+/// Sampler progress goes to `friedman://progress/{job_id}`; pass `job_id` to subscribe first.
 #[tauri::command]
 pub async fn bvar_hd(
     app: AppHandle,
     params: BvarHdParams,
-) -> Result<serde_json::Value, FriedmanError> {
+    job_id: Option<String>,
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
     let draws = params.draws.to_string();
 
     let mut args = vec![
-        "bvar", "hd", &params.data,
+        "bvar", "hd", data.path(),
         "--id", &params.id,
         "--draws", &draws,
         "--sampler", &params.sampler,
@@ -252,20 +291,27 @@ pub async fn bvar_hd(
         args.push(&params.config);
     }
 
-    run_friedman_command(&app, args).await
+    let job_id = job_id_or_new(job_id);
+    run_friedman_command_with_progress(&app, &job_id, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman bvar forecast <data> [--lags N] [--horizons N] [--draws N] [--sampler nuts] [--config path]`
+///
+/// Sampler progress goes to `friedman://progress/{job_id}`; pass `job_id` to subscribe first.
 #[tauri::command]
 pub async fn bvar_forecast(
     app: AppHandle,
     params: BvarForecastParams,
-) -> Result<serde_json::Value, FriedmanError> {
+    job_id: Option<String>,
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
     let horizons = params.horizons.to_string();
     let draws = params.draws.to_string();
 
     let mut args = vec![
-        "bvar", "forecast", &params.data,
+        "bvar", "forecast", data.path(),
         "--horizons", &horizons,
         "--draws", &draws,
         "--sampler", &params.sampler,
@@ -283,5 +329,8 @@ pub async fn bvar_forecast(
         args.push(&params.config);
     }
 
-    run_friedman_command(&app, args).await
+    let job_id = job_id_or_new(job_id);
+    run_friedman_command_with_progress(&app, &job_id, args)
+        .await
+        .map(|output| data.record_input(output))
 }
@@ -1,114 +1,251 @@
-use std::fs;
-use std::io::BufRead;
+use std::path::Path;
 
 use tauri::{AppHandle, Manager};
 
+use crate::dataset::columnar::{self, ColumnarFormat};
+use crate::dataset::spreadsheet::{self, SheetInfo, SheetSelection};
+use crate::dataset::preview::{PreviewPage, PreviewQuery};
+use crate::dataset::transform::{self, Lineage, TransformSpec};
+use crate::dataset::{blocking, dataset_dir, delimited, file_name, period, profile, spss, stata, Table};
 use crate::error::FriedmanError;
-use crate::sidecar::run_friedman_command;
 use crate::state::{AppState, DatasetInfo};
 
-/// Load a CSV file: read header for column names, count rows, store in state.
+/// Load a delimited text file, detecting its delimiter, quoting, encoding and
+/// decimal separator, and store it in state. Files friedman-cli cannot read
+/// as-is are normalized to a plain CSV copy.
 #[tauri::command]
 pub async fn load_csv(
     app: AppHandle,
     path: String,
 ) -> Result<DatasetInfo, FriedmanError> {
-    let file = fs::File::open(&path)?;
-    let reader = std::io::BufReader::new(file);
-
-    let mut lines = reader.lines();
-
-    // First line is the header
-    let header_line = lines
-        .next()
-        .ok_or_else(|| FriedmanError::InvalidParams("CSV file is empty".into()))?
-        .map_err(|e| FriedmanError::Io(e))?;
-
-    let columns: Vec<String> = header_line
-        .split(',')
-        .map(|s| s.trim().trim_matches('"').to_string())
-        .collect();
-
-    // Count remaining lines (data rows)
-    let mut row_count: usize = 0;
-    for line in lines {
-        let _ = line?;
-        row_count += 1;
-    }
+    blocking(move || load_delimited(&app, path)).await
+}
+
+fn load_delimited(app: &AppHandle, path: String) -> Result<DatasetInfo, FriedmanError> {
+    let (table, dialect) = delimited::read_csv(Path::new(&path))?;
 
     let id = uuid::Uuid::new_v4().to_string();
-    let name = std::path::Path::new(&path)
-        .file_name()
-        .map(|n| n.to_string_lossy().to_string())
-        .unwrap_or_else(|| "unknown".into());
+    let (data_path, source) = if dialect.is_plain() {
+        (path.clone(), None)
+    } else {
+        let normalized = dataset_dir(app).join(format!("{id}.csv"));
+        table.write_csv(&normalized)?;
+        (normalized.display().to_string(), Some(path.clone()))
+    };
 
+    let column_profiles = profile::profile_table(&table);
+    let time_index = period::detect_time_index(&table);
     let info = DatasetInfo {
-        id: id.clone(),
-        name,
-        path: path.clone(),
-        columns,
-        row_count,
+        id,
+        name: file_name(&path),
+        path: data_path,
+        columns: table.columns.clone(),
+        row_count: table.rows.len(),
+        source,
+        dialect: Some(dialect),
+        sheet: None,
+        schema: None,
+        column_meta: Vec::new(),
+        column_profiles,
+        time_index,
+        lineage: None,
     };
 
-    let state = app.state::<AppState>();
-    state
-        .datasets
-        .lock()
-        .expect("datasets lock poisoned")
-        .insert(id, info.clone());
-
+    insert_dataset(app, info.clone(), table);
     Ok(info)
 }
 
-/// Load an XLSX file via the sidecar `data import` subcommand, store in state.
+/// List the sheets of a spreadsheet file with their used ranges.
+#[tauri::command]
+pub async fn list_sheets(path: String) -> Result<Vec<SheetInfo>, FriedmanError> {
+    blocking(move || spreadsheet::list_sheets(Path::new(&path))).await
+}
+
+/// Load a sheet of an XLSX, XLS or ODS file, optionally limited to a cell
+/// range with a given header row, and store it in state as a normalized CSV.
 #[tauri::command]
 pub async fn load_xlsx(
     app: AppHandle,
     path: String,
+    sheet: Option<String>,
+    range: Option<String>,
+    header_row: Option<u32>,
 ) -> Result<DatasetInfo, FriedmanError> {
-    let result = run_friedman_command(
-        &app,
-        vec!["data", "import", "--path", &path],
-    )
-    .await?;
-
-    let columns: Vec<String> = result
-        .get("columns")
-        .and_then(|v| v.as_array())
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|v| v.as_str().map(String::from))
-                .collect()
-        })
-        .unwrap_or_default();
-
-    let row_count = result
-        .get("row_count")
-        .and_then(|v| v.as_u64())
-        .unwrap_or(0) as usize;
+    let selection = SheetSelection { sheet, range, header_row };
+    blocking(move || {
+        let (table, selection) = spreadsheet::read_sheet(Path::new(&path), &selection)?;
 
+        let mut info = normalized_dataset(&app, &path, &table)?;
+        info.sheet = Some(selection);
+        insert_dataset(&app, info.clone(), table);
+        Ok(info)
+    })
+    .await
+}
+
+/// Load a Parquet file, keeping its numeric columns and time index.
+#[tauri::command]
+pub async fn load_parquet(
+    app: AppHandle,
+    path: String,
+) -> Result<DatasetInfo, FriedmanError> {
+    blocking(move || load_columnar(&app, path, ColumnarFormat::Parquet)).await
+}
+
+/// Load an Arrow IPC file or stream, keeping its numeric columns and time
+/// index.
+#[tauri::command]
+pub async fn load_arrow(
+    app: AppHandle,
+    path: String,
+) -> Result<DatasetInfo, FriedmanError> {
+    blocking(move || load_columnar(&app, path, ColumnarFormat::Arrow)).await
+}
+
+/// Export a loaded or derived dataset to Parquet or Arrow, by the extension
+/// of `path` unless `format` is given.
+#[tauri::command]
+pub async fn export_dataset(
+    app: AppHandle,
+    id: String,
+    path: String,
+    format: Option<ColumnarFormat>,
+) -> Result<(), FriedmanError> {
+    let format = format
+        .or_else(|| ColumnarFormat::from_path(Path::new(&path)))
+        .ok_or_else(|| {
+            FriedmanError::InvalidParams(format!(
+                "Cannot tell the export format of {}: use .parquet or .arrow",
+                file_name(&path)
+            ))
+        })?;
+    let dataset = get_dataset(app.clone(), id).await?;
+    blocking(move || {
+        let table = app.state::<AppState>().tables.get_or_load(&dataset.id, Path::new(&dataset.path))?;
+        columnar::write_columnar(&table, Path::new(&path), format)
+    })
+    .await
+}
+
+/// Load a Stata `.dta` file with its variable and value labels.
+#[tauri::command]
+pub async fn load_stata(
+    app: AppHandle,
+    path: String,
+) -> Result<DatasetInfo, FriedmanError> {
+    blocking(move || {
+        let (table, meta) = stata::read_dta(Path::new(&path))?;
+
+        let mut info = normalized_dataset(&app, &path, &table)?;
+        info.column_meta = meta;
+        insert_dataset(&app, info.clone(), table);
+        Ok(info)
+    })
+    .await
+}
+
+/// Load an SPSS `.sav` file with its variable and value labels.
+#[tauri::command]
+pub async fn load_spss(
+    app: AppHandle,
+    path: String,
+) -> Result<DatasetInfo, FriedmanError> {
+    blocking(move || {
+        let (table, meta) = spss::read_sav(Path::new(&path))?;
+
+        let mut info = normalized_dataset(&app, &path, &table)?;
+        info.column_meta = meta;
+        insert_dataset(&app, info.clone(), table);
+        Ok(info)
+    })
+    .await
+}
+
+/// Apply `transforms` in order to a dataset and register the result as a
+/// new dataset, with a lineage record pointing back to its parent.
+#[tauri::command]
+pub async fn transform_dataset(
+    app: AppHandle,
+    id: String,
+    transforms: Vec<TransformSpec>,
+    name: Option<String>,
+) -> Result<DatasetInfo, FriedmanError> {
+    let parent = get_dataset(app.clone(), id).await?;
+    if let Some(index) = parent.time_index.as_ref().filter(|index| !index.sorted) {
+        return Err(FriedmanError::InvalidParams(format!(
+            "Rows of {} are not in time order by `{}`; sort them before transforming",
+            parent.name, index.column
+        )));
+    }
+    blocking(move || {
+        let table = app.state::<AppState>().tables.get_or_load(&parent.id, Path::new(&parent.path))?;
+        let frequency = parent.time_index.as_ref().map(|index| index.frequency);
+        let derived = transform::apply_transforms(&table, &transforms, frequency)?;
+
+        let mut info = normalized_dataset(&app, &parent.path, &derived)?;
+        info.name = name.unwrap_or_else(|| format!("{} (transformed)", parent.name));
+        info.source = None;
+        info.column_meta = transform::derived_meta(&parent.column_meta, &derived.columns);
+        info.lineage = Some(Lineage {
+            parent_id: parent.id,
+            parent_name: parent.name,
+            transforms,
+        });
+        insert_dataset(&app, info.clone(), derived);
+        Ok(info)
+    })
+    .await
+}
+
+fn load_columnar(
+    app: &AppHandle,
+    path: String,
+    format: ColumnarFormat,
+) -> Result<DatasetInfo, FriedmanError> {
+    let (table, schema) = columnar::read_columnar(Path::new(&path), format)?;
+
+    let mut info = normalized_dataset(app, &path, &table)?;
+    info.schema = Some(schema);
+    insert_dataset(app, info.clone(), table);
+    Ok(info)
+}
+
+/// Write `table` as the dataset's plain CSV copy and describe it.
+fn normalized_dataset(
+    app: &AppHandle,
+    path: &str,
+    table: &Table,
+) -> Result<DatasetInfo, FriedmanError> {
     let id = uuid::Uuid::new_v4().to_string();
-    let name = std::path::Path::new(&path)
-        .file_name()
-        .map(|n| n.to_string_lossy().to_string())
-        .unwrap_or_else(|| "unknown".into());
+    let normalized = dataset_dir(app).join(format!("{id}.csv"));
+    table.write_csv(&normalized)?;
 
-    let info = DatasetInfo {
-        id: id.clone(),
-        name,
-        path: path.clone(),
-        columns,
-        row_count,
-    };
+    Ok(DatasetInfo {
+        id,
+        name: file_name(path),
+        path: normalized.display().to_string(),
+        row_count: table.rows.len(),
+        source: Some(path.to_string()),
+        dialect: None,
+        sheet: None,
+        schema: None,
+        column_meta: Vec::new(),
+        columns: table.columns.clone(),
+        column_profiles: profile::profile_table(table),
+        time_index: period::detect_time_index(table),
+        lineage: None,
+    })
+}
 
+/// Keep `info` in state, and `table` in the table cache for previews.
+fn insert_dataset(app: &AppHandle, info: DatasetInfo, table: Table) {
     let state = app.state::<AppState>();
+    state.tables.insert(&info.id, table);
     state
         .datasets
         .lock()
         .expect("datasets lock poisoned")
-        .insert(id, info.clone());
-
-    Ok(info)
+        .insert(info.id.clone(), info);
 }
 
 /// Retrieve a previously loaded dataset by its ID.
@@ -135,17 +272,21 @@ pub async fn list_datasets(
     Ok(datasets.values().cloned().collect())
 }
 
-/// Preview the first N rows of a data file via the sidecar.
+/// A page of a loaded dataset: `query` selects the rows (offset and limit,
+/// after an optional filter and sort) and columns to return. Parsed rows are
+/// cached, so paging through large files does not re-read them.
 #[tauri::command]
 pub async fn preview_data(
     app: AppHandle,
-    path: String,
-    rows: Option<usize>,
-) -> Result<serde_json::Value, FriedmanError> {
-    let rows_str = rows.unwrap_or(100).to_string();
-    run_friedman_command(
-        &app,
-        vec!["data", "preview", "--path", &path, "--rows", &rows_str],
-    )
+    dataset_id: String,
+    query: Option<PreviewQuery>,
+) -> Result<PreviewPage, FriedmanError> {
+    let dataset = get_dataset(app.clone(), dataset_id).await?;
+    blocking(move || {
+        let state = app.state::<AppState>();
+        state
+            .tables
+            .preview(&dataset.id, Path::new(&dataset.path), &query.unwrap_or_default())
+    })
     .await
 }
@@ -1,8 +1,9 @@
 use serde::Deserialize;
 use tauri::AppHandle;
 
+use crate::dataset::input::DataInput;
 use crate::error::FriedmanError;
-use crate::sidecar::run_friedman_command;
+use crate::sidecar::{run_friedman_command, CommandOutput};
 
 // ---------------------------------------------------------------------------
 // Parameter structs — matching actual CLI v0.1.3
@@ -10,7 +11,8 @@ use crate::sidecar::run_friedman_command;
 
 #[derive(Debug, Deserialize)]
 pub struct FactorEstimateParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     pub model_type: String, // static|dynamic|gdfm
     #[serde(default)]
     pub nfactors: Option<u32>,
@@ -29,7 +31,8 @@ pub struct FactorEstimateParams {
 
 #[derive(Debug, Deserialize)]
 pub struct FactorForecastParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default)]
     pub nfactors: Option<u32>,
     #[serde(default = "default_horizon")]
@@ -61,8 +64,9 @@ fn default_conf_level() -> f64 { 0.95 }
 pub async fn factor_estimate(
     app: AppHandle,
     params: FactorEstimateParams,
-) -> Result<serde_json::Value, FriedmanError> {
-    let mut args = vec!["factor", "estimate", &params.model_type, &params.data];
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
+    let mut args = vec!["factor", "estimate", &params.model_type, data.path()];
 
     let nf_str;
     if let Some(nf) = params.nfactors {
@@ -95,7 +99,9 @@ pub async fn factor_estimate(
         args.push(&dr_str);
     }
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman factor forecast <data> [--nfactors N] [--horizon N] [--ci-method none] [--conf-level F] [--model static|dynamic|gdfm] [model-specific opts]`
@@ -103,11 +109,12 @@ pub async fn factor_estimate(
 pub async fn factor_forecast(
     app: AppHandle,
     params: FactorForecastParams,
-) -> Result<serde_json::Value, FriedmanError> {
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
     let horizon = params.horizon.to_string();
     let conf_level = params.conf_level.to_string();
 
-    let mut args = vec!["factor", "forecast", &params.data];
+    let mut args = vec!["factor", "forecast", data.path()];
 
     let nf_str;
     if let Some(nf) = params.nfactors {
@@ -147,5 +154,7 @@ pub async fn factor_forecast(
         args.push(&dr_str);
     }
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
@@ -1,12 +1,14 @@
 use serde::Deserialize;
 use tauri::AppHandle;
 
+use crate::dataset::input::DataInput;
 use crate::error::FriedmanError;
-use crate::sidecar::run_friedman_command;
+use crate::sidecar::{run_friedman_command, CommandOutput};
 
 #[derive(Debug, Deserialize)]
 pub struct GmmEstimateParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default)]
     pub config: String,
     #[serde(default = "default_weighting")]
@@ -20,9 +22,10 @@ fn default_weighting() -> String { "twostep".into() }
 pub async fn gmm_estimate(
     app: AppHandle,
     params: GmmEstimateParams,
-) -> Result<serde_json::Value, FriedmanError> {
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
     let mut args = vec![
-        "gmm", "estimate", &params.data,
+        "gmm", "estimate", data.path(),
         "--weighting", &params.weighting,
     ];
 
@@ -31,5 +34,7 @@ pub async fn gmm_estimate(
         args.push(&params.config);
     }
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
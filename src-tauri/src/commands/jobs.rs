@@ -0,0 +1,66 @@
+use tauri::{AppHandle, Manager};
+
+use crate::error::FriedmanError;
+use crate::sidecar::jobs::JobInfo;
+use crate::sidecar::watchdog::TimeoutSettings;
+use crate::state::AppState;
+
+/// List queued, running and recently finished sidecar jobs, oldest first.
+#[tauri::command]
+pub async fn list_jobs(
+    app: AppHandle,
+) -> Result<Vec<JobInfo>, FriedmanError> {
+    let state = app.state::<AppState>();
+    Ok(state.jobs.list())
+}
+
+/// Cancel a running job: its process tree is killed and the command that
+/// started it fails with a "Job cancelled" error.
+#[tauri::command]
+pub async fn cancel_job(
+    app: AppHandle,
+    job_id: String,
+) -> Result<JobInfo, FriedmanError> {
+    let state = app.state::<AppState>();
+    state.jobs.cancel(&job_id)
+}
+
+/// Current limit on concurrently running sidecar processes.
+#[tauri::command]
+pub async fn get_max_concurrency(
+    app: AppHandle,
+) -> Result<usize, FriedmanError> {
+    let state = app.state::<AppState>();
+    Ok(state.scheduler.max_concurrency())
+}
+
+/// Change how many sidecar processes may run at once (at least 1).
+#[tauri::command]
+pub async fn set_max_concurrency(
+    app: AppHandle,
+    max: usize,
+) -> Result<usize, FriedmanError> {
+    let state = app.state::<AppState>();
+    state.scheduler.set_max_concurrency(&app, max)
+}
+
+/// Current per-command-family time limits and idle watchdog, in seconds.
+#[tauri::command]
+pub async fn get_timeouts(
+    app: AppHandle,
+) -> Result<TimeoutSettings, FriedmanError> {
+    let state = app.state::<AppState>();
+    let timeouts = state.timeouts.lock().expect("timeouts lock poisoned");
+    Ok(timeouts.clone())
+}
+
+/// Replace the time limits; applies to jobs started afterwards.
+#[tauri::command]
+pub async fn set_timeouts(
+    app: AppHandle,
+    timeouts: TimeoutSettings,
+) -> Result<TimeoutSettings, FriedmanError> {
+    let state = app.state::<AppState>();
+    *state.timeouts.lock().expect("timeouts lock poisoned") = timeouts.clone();
+    Ok(timeouts)
+}
@@ -1,8 +1,11 @@
 use serde::Deserialize;
 use tauri::AppHandle;
 
+use crate::dataset::input::DataInput;
 use crate::error::FriedmanError;
-use crate::sidecar::run_friedman_command;
+use crate::sidecar::{
+    job_id_or_new, run_friedman_command, run_friedman_command_with_progress, CommandOutput,
+};
 
 // ---------------------------------------------------------------------------
 // Parameter structs — matching actual CLI v0.1.3
@@ -10,7 +13,8 @@ use crate::sidecar::run_friedman_command;
 
 #[derive(Debug, Deserialize)]
 pub struct LpEstimateParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default = "default_method")]
     pub method: String, // standard|iv|smooth|state|propensity|robust
     #[serde(default = "default_shock")]
@@ -45,7 +49,8 @@ pub struct LpEstimateParams {
 
 #[derive(Debug, Deserialize)]
 pub struct LpIrfParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default)]
     pub shock: Option<u32>,
     #[serde(default)]
@@ -72,7 +77,8 @@ pub struct LpIrfParams {
 
 #[derive(Debug, Deserialize)]
 pub struct LpFevdParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default = "default_horizons")]
     pub horizons: u32,
     #[serde(default)]
@@ -89,7 +95,8 @@ pub struct LpFevdParams {
 
 #[derive(Debug, Deserialize)]
 pub struct LpHdParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default)]
     pub lags: Option<u32>,
     #[serde(default)]
@@ -104,7 +111,8 @@ pub struct LpHdParams {
 
 #[derive(Debug, Deserialize)]
 pub struct LpForecastParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default)]
     pub shock: Option<u32>,
     #[serde(default = "default_horizons")]
@@ -139,13 +147,14 @@ fn default_id() -> String { "cholesky".into() }
 pub async fn lp_estimate(
     app: AppHandle,
     params: LpEstimateParams,
-) -> Result<serde_json::Value, FriedmanError> {
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
     let shock = params.shock.to_string();
     let horizons = params.horizons.to_string();
     let control_lags = params.control_lags.to_string();
 
     let mut args = vec![
-        "lp", "estimate", &params.data,
+        "lp", "estimate", data.path(),
         "--method", &params.method,
         "--shock", &shock,
         "--horizons", &horizons,
@@ -204,7 +213,9 @@ pub async fn lp_estimate(
         args.push(sm);
     }
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman lp irf <data> [--shock N] [--shocks 1,2,3] [--horizons N] [--lags N] [--var-lags N] [--id cholesky] [--ci bootstrap] [--replications N] [--vcov ...]`
@@ -212,11 +223,12 @@ pub async fn lp_estimate(
 pub async fn lp_irf(
     app: AppHandle,
     params: LpIrfParams,
-) -> Result<serde_json::Value, FriedmanError> {
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
     let horizons = params.horizons.to_string();
 
     let mut args = vec![
-        "lp", "irf", &params.data,
+        "lp", "irf", data.path(),
         "--horizons", &horizons,
         "--id", &params.id,
     ];
@@ -276,7 +288,9 @@ pub async fn lp_irf(
         args.push(&params.config);
     }
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman lp fevd <data> [--horizons N] [--lags N] [--var-lags N] [--id cholesky] [--vcov ...]`
@@ -284,11 +298,12 @@ pub async fn lp_irf(
 pub async fn lp_fevd(
     app: AppHandle,
     params: LpFevdParams,
-) -> Result<serde_json::Value, FriedmanError> {
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
     let horizons = params.horizons.to_string();
 
     let mut args = vec![
-        "lp", "fevd", &params.data,
+        "lp", "fevd", data.path(),
         "--horizons", &horizons,
         "--id", &params.id,
     ];
@@ -317,7 +332,9 @@ pub async fn lp_fevd(
         args.push(&params.config);
     }
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman lp hd <data> [--lags N] [--var-lags N] [--id cholesky] [--vcov ...]`
@@ -325,9 +342,10 @@ pub async fn lp_fevd(
 pub async fn lp_hd(
     app: AppHandle,
     params: LpHdParams,
-) -> Result<serde_json::Value, FriedmanError> {
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
     let mut args = vec![
-        "lp", "hd", &params.data,
+        "lp", "hd", data.path(),
         "--id", &params.id,
     ];
 
@@ -355,19 +373,25 @@ pub async fn lp_hd(
         args.push(&params.config);
     }
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman lp forecast <data> [--shock N] [--horizons N] [--shock-size F] [--lags N] [--vcov ...] [--ci-method ...] [--conf-level F] [--n-boot N]`
+///
+/// Bootstrap progress goes to `friedman://progress/{job_id}`; pass `job_id` to subscribe first.
 #[tauri::command]
 pub async fn lp_forecast(
     app: AppHandle,
     params: LpForecastParams,
-) -> Result<serde_json::Value, FriedmanError> {
+    job_id: Option<String>,
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
     let horizons = params.horizons.to_string();
 
     let mut args = vec![
-        "lp", "forecast", &params.data,
+        "lp", "forecast", data.path(),
         "--horizons", &horizons,
     ];
 
@@ -416,5 +440,8 @@ pub async fn lp_forecast(
         args.push(&nb_str);
     }
 
-    run_friedman_command(&app, args).await
+    let job_id = job_id_or_new(job_id);
+    run_friedman_command_with_progress(&app, &job_id, args)
+        .await
+        .map(|output| data.record_input(output))
 }
@@ -7,3 +7,6 @@ pub mod test;
 pub mod gmm;
 pub mod arima;
 pub mod nongaussian;
+pub mod jobs;
+pub mod results;
+pub mod sidecar;
@@ -1,8 +1,9 @@
 use serde::Deserialize;
 use tauri::AppHandle;
 
+use crate::dataset::input::DataInput;
 use crate::error::FriedmanError;
-use crate::sidecar::run_friedman_command;
+use crate::sidecar::{run_friedman_command, CommandOutput};
 
 // ---------------------------------------------------------------------------
 // Parameter structs — matching actual CLI
@@ -10,7 +11,8 @@ use crate::sidecar::run_friedman_command;
 
 #[derive(Debug, Deserialize)]
 pub struct NongaussianFasticaParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default)]
     pub lags: Option<u32>,
     #[serde(default = "default_fastica_method")]
@@ -21,7 +23,8 @@ pub struct NongaussianFasticaParams {
 
 #[derive(Debug, Deserialize)]
 pub struct NongaussianMlParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default)]
     pub lags: Option<u32>,
     #[serde(default = "default_distribution")]
@@ -30,7 +33,8 @@ pub struct NongaussianMlParams {
 
 #[derive(Debug, Deserialize)]
 pub struct NongaussianHeteroskedasticityParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default)]
     pub lags: Option<u32>,
     #[serde(default = "default_heterosk_method")]
@@ -43,14 +47,16 @@ pub struct NongaussianHeteroskedasticityParams {
 
 #[derive(Debug, Deserialize)]
 pub struct NongaussianNormalityParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default)]
     pub lags: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct NongaussianIdentifiabilityParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default)]
     pub lags: Option<u32>,
     #[serde(default = "default_test_type")]
@@ -77,8 +83,9 @@ fn default_test_type() -> String { "all".into() }
 pub async fn nongaussian_fastica(
     app: AppHandle,
     params: NongaussianFasticaParams,
-) -> Result<serde_json::Value, FriedmanError> {
-    let mut args = vec!["nongaussian", "fastica", &params.data];
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
+    let mut args = vec!["nongaussian", "fastica", data.path()];
 
     let lags_str;
     if let Some(lags) = params.lags {
@@ -92,7 +99,9 @@ pub async fn nongaussian_fastica(
     args.push("--contrast");
     args.push(&params.contrast);
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman nongaussian ml <data> [--lags N] [--distribution student_t]`
@@ -100,8 +109,9 @@ pub async fn nongaussian_fastica(
 pub async fn nongaussian_ml(
     app: AppHandle,
     params: NongaussianMlParams,
-) -> Result<serde_json::Value, FriedmanError> {
-    let mut args = vec!["nongaussian", "ml", &params.data];
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
+    let mut args = vec!["nongaussian", "ml", data.path()];
 
     let lags_str;
     if let Some(lags) = params.lags {
@@ -113,7 +123,9 @@ pub async fn nongaussian_ml(
     args.push("--distribution");
     args.push(&params.distribution);
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman nongaussian heteroskedasticity <data> [--lags N] [--method markov] [--config FILE] [--regimes N]`
@@ -121,9 +133,10 @@ pub async fn nongaussian_ml(
 pub async fn nongaussian_heteroskedasticity(
     app: AppHandle,
     params: NongaussianHeteroskedasticityParams,
-) -> Result<serde_json::Value, FriedmanError> {
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
     let regimes_str = params.regimes.to_string();
-    let mut args = vec!["nongaussian", "heteroskedasticity", &params.data];
+    let mut args = vec!["nongaussian", "heteroskedasticity", data.path()];
 
     let lags_str;
     if let Some(lags) = params.lags {
@@ -143,7 +156,9 @@ pub async fn nongaussian_heteroskedasticity(
     args.push("--regimes");
     args.push(&regimes_str);
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman nongaussian normality <data> [--lags N]`
@@ -151,8 +166,9 @@ pub async fn nongaussian_heteroskedasticity(
 pub async fn nongaussian_normality(
     app: AppHandle,
     params: NongaussianNormalityParams,
-) -> Result<serde_json::Value, FriedmanError> {
-    let mut args = vec!["nongaussian", "normality", &params.data];
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
+    let mut args = vec!["nongaussian", "normality", data.path()];
 
     let lags_str;
     if let Some(lags) = params.lags {
@@ -161,7 +177,9 @@ pub async fn nongaussian_normality(
         args.push(&lags_str);
     }
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman nongaussian identifiability <data> [--lags N] [--test all] [--method fastica] [--contrast logcosh]`
@@ -169,8 +187,9 @@ pub async fn nongaussian_normality(
 pub async fn nongaussian_identifiability(
     app: AppHandle,
     params: NongaussianIdentifiabilityParams,
-) -> Result<serde_json::Value, FriedmanError> {
-    let mut args = vec!["nongaussian", "identifiability", &params.data];
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
+    let mut args = vec!["nongaussian", "identifiability", data.path()];
 
     let lags_str;
     if let Some(lags) = params.lags {
@@ -186,5 +205,7 @@ pub async fn nongaussian_identifiability(
     args.push("--contrast");
     args.push(&params.contrast);
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
@@ -0,0 +1,29 @@
+use tauri::{AppHandle, Manager};
+
+use crate::error::FriedmanError;
+use crate::sidecar::spill::ResultPage;
+use crate::state::AppState;
+
+/// Elements `offset..offset + limit` of an array that a command returned as
+/// a `{"$spill": {...}}` handle because it was too large to send at once.
+#[tauri::command]
+pub async fn read_result_page(
+    app: AppHandle,
+    handle: String,
+    offset: usize,
+    limit: usize,
+) -> Result<ResultPage, FriedmanError> {
+    let state = app.state::<AppState>();
+    state.spills.page(&handle, offset, limit)
+}
+
+/// Delete a spilled array once the frontend no longer needs it.
+#[tauri::command]
+pub async fn release_result(
+    app: AppHandle,
+    handle: String,
+) -> Result<(), FriedmanError> {
+    let state = app.state::<AppState>();
+    state.spills.release(&handle);
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use tauri::{AppHandle, Manager};
+
+use crate::error::FriedmanError;
+use crate::settings::{ResourceSettings, SidecarSettings};
+use crate::sidecar::capabilities::{self, SidecarInfo};
+use crate::sidecar::resolve::{self, SidecarDiagnosis};
+use crate::state::AppState;
+
+/// How friedman-cli is invoked, its CLI and Julia versions, the subcommands
+/// and flags it advertises, and any incompatibility with this app.
+///
+/// The result is cached after the startup probe; pass `refresh: true` to
+/// probe again (e.g. after reinstalling the sidecar).
+#[tauri::command]
+pub async fn sidecar_info(
+    app: AppHandle,
+    refresh: Option<bool>,
+) -> Result<SidecarInfo, FriedmanError> {
+    capabilities::info(&app, refresh.unwrap_or(false)).await
+}
+
+/// Resolve the sidecar and report every binary, project and Julia candidate
+/// tried, with the reason each one was rejected.
+#[tauri::command]
+pub async fn diagnose_sidecar(
+    app: AppHandle,
+) -> Result<SidecarDiagnosis, FriedmanError> {
+    Ok(resolve::diagnose(&app))
+}
+
+/// Julia, project and binary locations saved in the settings file.
+#[tauri::command]
+pub async fn get_sidecar_settings(
+    app: AppHandle,
+) -> Result<SidecarSettings, FriedmanError> {
+    let state = app.state::<AppState>();
+    let settings = state.settings.lock().expect("settings lock poisoned");
+    Ok(settings.sidecar.clone())
+}
+
+/// Save sidecar locations and return the resulting diagnosis. The next
+/// command resolves the sidecar again and the compatibility probe is rerun.
+#[tauri::command]
+pub async fn set_sidecar_settings(
+    app: AppHandle,
+    settings: SidecarSettings,
+) -> Result<SidecarDiagnosis, FriedmanError> {
+    let state = app.state::<AppState>();
+    let saved = {
+        let mut current = state.settings.lock().expect("settings lock poisoned");
+        current.sidecar = settings;
+        current.clone()
+    };
+    saved.save(&app)?;
+    *state.sidecar_info.lock().expect("sidecar info lock poisoned") = None;
+    capabilities::check_in_background(app.clone());
+    Ok(resolve::diagnose(&app))
+}
+
+/// Thread count, nice level, memory ceiling and scratch location applied to
+/// sidecar processes.
+#[tauri::command]
+pub async fn get_resource_settings(
+    app: AppHandle,
+) -> Result<ResourceSettings, FriedmanError> {
+    let state = app.state::<AppState>();
+    let settings = state.settings.lock().expect("settings lock poisoned");
+    Ok(settings.resources.clone())
+}
+
+/// Save resource settings. They apply to jobs started afterwards; the
+/// session is restarted with the new limits on its next request.
+#[tauri::command]
+pub async fn set_resource_settings(
+    app: AppHandle,
+    resources: ResourceSettings,
+) -> Result<ResourceSettings, FriedmanError> {
+    resources.validate()?;
+    let state = app.state::<AppState>();
+    let saved = {
+        let mut current = state.settings.lock().expect("settings lock poisoned");
+        current.resources = resources.clone();
+        current.clone()
+    };
+    saved.save(&app)?;
+    Ok(resources)
+}
@@ -1,8 +1,9 @@
 use serde::Deserialize;
 use tauri::AppHandle;
 
+use crate::dataset::input::DataInput;
 use crate::error::FriedmanError;
-use crate::sidecar::run_friedman_command;
+use crate::sidecar::{run_friedman_command, CommandOutput};
 
 // ---------------------------------------------------------------------------
 // Parameter structs — matching actual CLI (unit root & cointegration tests)
@@ -10,7 +11,8 @@ use crate::sidecar::run_friedman_command;
 
 #[derive(Debug, Deserialize)]
 pub struct TestAdfParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default = "default_column")]
     pub column: u32,
     #[serde(default)]
@@ -21,7 +23,8 @@ pub struct TestAdfParams {
 
 #[derive(Debug, Deserialize)]
 pub struct TestKpssParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default = "default_column")]
     pub column: u32,
     #[serde(default = "default_trend")]
@@ -30,7 +33,8 @@ pub struct TestKpssParams {
 
 #[derive(Debug, Deserialize)]
 pub struct TestPpParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default = "default_column")]
     pub column: u32,
     #[serde(default = "default_trend")]
@@ -39,7 +43,8 @@ pub struct TestPpParams {
 
 #[derive(Debug, Deserialize)]
 pub struct TestZaParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default = "default_column")]
     pub column: u32,
     #[serde(default = "default_za_trend")]
@@ -50,7 +55,8 @@ pub struct TestZaParams {
 
 #[derive(Debug, Deserialize)]
 pub struct TestNpParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default = "default_column")]
     pub column: u32,
     #[serde(default = "default_trend")]
@@ -59,7 +65,8 @@ pub struct TestNpParams {
 
 #[derive(Debug, Deserialize)]
 pub struct TestJohansenParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default = "default_lags")]
     pub lags: u32,
     #[serde(default = "default_trend")]
@@ -81,11 +88,12 @@ fn default_lags() -> u32 { 2 }
 pub async fn test_adf(
     app: AppHandle,
     params: TestAdfParams,
-) -> Result<serde_json::Value, FriedmanError> {
-    let column = params.column.to_string();
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
+    let column = data.column(params.column)?;
 
     let mut args = vec![
-        "test", "adf", &params.data,
+        "test", "adf", data.path(),
         "--column", &column,
         "--trend", &params.trend,
     ];
@@ -97,7 +105,9 @@ pub async fn test_adf(
         args.push(&ml_str);
     }
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman test kpss <data> [--column N] [--trend constant]`
@@ -105,16 +115,19 @@ pub async fn test_adf(
 pub async fn test_kpss(
     app: AppHandle,
     params: TestKpssParams,
-) -> Result<serde_json::Value, FriedmanError> {
-    let column = params.column.to_string();
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
+    let column = data.column(params.column)?;
 
     let args = vec![
-        "test", "kpss", &params.data,
+        "test", "kpss", data.path(),
         "--column", &column,
         "--trend", &params.trend,
     ];
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman test pp <data> [--column N] [--trend constant]`
@@ -122,16 +135,19 @@ pub async fn test_kpss(
 pub async fn test_pp(
     app: AppHandle,
     params: TestPpParams,
-) -> Result<serde_json::Value, FriedmanError> {
-    let column = params.column.to_string();
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
+    let column = data.column(params.column)?;
 
     let args = vec![
-        "test", "pp", &params.data,
+        "test", "pp", data.path(),
         "--column", &column,
         "--trend", &params.trend,
     ];
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman test za <data> [--column N] [--trend both] [--trim 0.15]`
@@ -139,18 +155,21 @@ pub async fn test_pp(
 pub async fn test_za(
     app: AppHandle,
     params: TestZaParams,
-) -> Result<serde_json::Value, FriedmanError> {
-    let column = params.column.to_string();
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
+    let column = data.column(params.column)?;
     let trim = params.trim.to_string();
 
     let args = vec![
-        "test", "za", &params.data,
+        "test", "za", data.path(),
         "--column", &column,
         "--trend", &params.trend,
         "--trim", &trim,
     ];
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman test np <data> [--column N] [--trend constant]`
@@ -158,16 +177,19 @@ pub async fn test_za(
 pub async fn test_np(
     app: AppHandle,
     params: TestNpParams,
-) -> Result<serde_json::Value, FriedmanError> {
-    let column = params.column.to_string();
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
+    let column = data.column(params.column)?;
 
     let args = vec![
-        "test", "np", &params.data,
+        "test", "np", data.path(),
         "--column", &column,
         "--trend", &params.trend,
     ];
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman test johansen <data> [--lags N] [--trend constant]`
@@ -175,14 +197,17 @@ pub async fn test_np(
 pub async fn test_johansen(
     app: AppHandle,
     params: TestJohansenParams,
-) -> Result<serde_json::Value, FriedmanError> {
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
     let lags = params.lags.to_string();
 
     let args = vec![
-        "test", "johansen", &params.data,
+        "test", "johansen", data.path(),
         "--lags", &lags,
         "--trend", &params.trend,
     ];
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
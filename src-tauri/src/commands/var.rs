@@ -1,8 +1,11 @@
 use serde::Deserialize;
 use tauri::AppHandle;
 
+use crate::dataset::input::DataInput;
 use crate::error::FriedmanError;
-use crate::sidecar::run_friedman_command;
+use crate::sidecar::{
+    job_id_or_new, run_friedman_command, run_friedman_command_with_progress, CommandOutput,
+};
 
 // ---------------------------------------------------------------------------
 // Parameter structs — matching actual CLI interface
@@ -10,7 +13,8 @@ use crate::sidecar::run_friedman_command;
 
 #[derive(Debug, Deserialize)]
 pub struct VarEstimateParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default)]
     pub lags: Option<u32>,
     #[serde(default = "default_trend")]
@@ -19,7 +23,8 @@ pub struct VarEstimateParams {
 
 #[derive(Debug, Deserialize)]
 pub struct VarLagSelectParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default = "default_max_lags")]
     pub max_lags: u32,
     #[serde(default = "default_criterion")]
@@ -28,14 +33,16 @@ pub struct VarLagSelectParams {
 
 #[derive(Debug, Deserialize)]
 pub struct VarStabilityParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default)]
     pub lags: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct VarIrfParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default)]
     pub lags: Option<u32>,
     #[serde(default = "default_shock")]
@@ -54,7 +61,8 @@ pub struct VarIrfParams {
 
 #[derive(Debug, Deserialize)]
 pub struct VarFevdParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default)]
     pub lags: Option<u32>,
     #[serde(default = "default_horizons")]
@@ -67,7 +75,8 @@ pub struct VarFevdParams {
 
 #[derive(Debug, Deserialize)]
 pub struct VarHdParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default)]
     pub lags: Option<u32>,
     #[serde(default = "default_id")]
@@ -78,7 +87,8 @@ pub struct VarHdParams {
 
 #[derive(Debug, Deserialize)]
 pub struct VarForecastParams {
-    pub data: String,
+    #[serde(flatten)]
+    pub input: DataInput,
     #[serde(default)]
     pub lags: Option<u32>,
     #[serde(default = "default_horizons")]
@@ -106,8 +116,9 @@ fn default_confidence() -> f64 { 0.95 }
 pub async fn var_estimate(
     app: AppHandle,
     params: VarEstimateParams,
-) -> Result<serde_json::Value, FriedmanError> {
-    let mut args = vec!["var", "estimate", &params.data];
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
+    let mut args = vec!["var", "estimate", data.path()];
 
     let lags_str;
     if let Some(lags) = params.lags {
@@ -119,7 +130,9 @@ pub async fn var_estimate(
     args.push("--trend");
     args.push(&params.trend);
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman var lagselect <data> [--max-lags N] [--criterion aic]`
@@ -127,16 +140,19 @@ pub async fn var_estimate(
 pub async fn var_lagselect(
     app: AppHandle,
     params: VarLagSelectParams,
-) -> Result<serde_json::Value, FriedmanError> {
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
     let max_lags = params.max_lags.to_string();
 
     let args = vec![
-        "var", "lagselect", &params.data,
+        "var", "lagselect", data.path(),
         "--max-lags", &max_lags,
         "--criterion", &params.criterion,
     ];
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman var stability <data> [--lags N] [--format json]`
@@ -144,8 +160,9 @@ pub async fn var_lagselect(
 pub async fn var_stability(
     app: AppHandle,
     params: VarStabilityParams,
-) -> Result<serde_json::Value, FriedmanError> {
-    let mut args = vec!["var", "stability", &params.data];
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
+    let mut args = vec!["var", "stability", data.path()];
 
     let lags_str;
     if let Some(lags) = params.lags {
@@ -154,21 +171,27 @@ pub async fn var_stability(
         args.push(&lags_str);
     }
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman var irf <data> [--lags N] [--shock N] [--horizons N] [--id cholesky] [--ci bootstrap] [--replications N] [--config path]`
+///
+/// Bootstrap progress goes to `friedman://progress/{job_id}`; pass `job_id` to subscribe first.
 #[tauri::command]
 pub async fn var_irf(
     app: AppHandle,
     params: VarIrfParams,
-) -> Result<serde_json::Value, FriedmanError> {
+    job_id: Option<String>,
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
     let shock = params.shock.to_string();
     let horizons = params.horizons.to_string();
     let replications = params.replications.to_string();
 
     let mut args = vec![
-        "var", "irf", &params.data,
+        "var", "irf", data.path(),
         "--shock", &shock,
         "--horizons", &horizons,
         "--id", &params.id,
@@ -188,7 +211,10 @@ pub async fn var_irf(
         args.push(&params.config);
     }
 
-    run_friedman_command(&app, args).await
+    let job_id = job_id_or_new(job_id);
+    run_friedman_command_with_progress(&app, &job_id, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman var fevd <data> [--lags N] [--horizons N] [--id cholesky] [--config path]`
@@ -196,11 +222,12 @@ pub async fn var_irf(
 pub async fn var_fevd(
     app: AppHandle,
     params: VarFevdParams,
-) -> Result<serde_json::Value, FriedmanError> {
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
     let horizons = params.horizons.to_string();
 
     let mut args = vec![
-        "var", "fevd", &params.data,
+        "var", "fevd", data.path(),
         "--horizons", &horizons,
         "--id", &params.id,
     ];
@@ -217,7 +244,9 @@ pub async fn var_fevd(
         args.push(&params.config);
     }
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman var hd <data> [--lags N] [--id cholesky] [--config path]`
@@ -225,9 +254,10 @@ pub async fn var_fevd(
 pub async fn var_hd(
     app: AppHandle,
     params: VarHdParams,
-) -> Result<serde_json::Value, FriedmanError> {
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
     let mut args = vec![
-        "var", "hd", &params.data,
+        "var", "hd", data.path(),
         "--id", &params.id,
     ];
 
@@ -243,7 +273,9 @@ pub async fn var_hd(
         args.push(&params.config);
     }
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
 
 /// `friedman var forecast <data> [--lags N] [--horizons N] [--confidence F]`
@@ -251,12 +283,13 @@ pub async fn var_hd(
 pub async fn var_forecast(
     app: AppHandle,
     params: VarForecastParams,
-) -> Result<serde_json::Value, FriedmanError> {
+) -> Result<CommandOutput, FriedmanError> {
+    let data = params.input.resolve(&app).await?;
     let horizons = params.horizons.to_string();
     let confidence = params.confidence.to_string();
 
     let mut args = vec![
-        "var", "forecast", &params.data,
+        "var", "forecast", data.path(),
         "--horizons", &horizons,
         "--confidence", &confidence,
     ];
@@ -268,5 +301,7 @@ pub async fn var_forecast(
         args.push(&lags_str);
     }
 
-    run_friedman_command(&app, args).await
+    run_friedman_command(&app, args)
+        .await
+        .map(|output| data.record_input(output))
 }
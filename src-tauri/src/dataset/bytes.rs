@@ -0,0 +1,126 @@
+use crate::error::FriedmanError;
+
+/// Cursor over the bytes of a binary data file in a given byte order.
+pub(crate) struct Bytes<'a> {
+    data: &'a [u8],
+    pos: usize,
+    pub big_endian: bool,
+    /// File kind for error messages, e.g. `Stata`.
+    what: &'static str,
+}
+
+impl<'a> Bytes<'a> {
+    pub fn new(data: &'a [u8], what: &'static str) -> Self {
+        Self { data, pos: 0, big_endian: false, what }
+    }
+
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    pub fn seek(&mut self, pos: usize) -> Result<(), FriedmanError> {
+        if pos > self.data.len() {
+            return Err(self.invalid(format!("offset {pos} is past the end of the file")));
+        }
+        self.pos = pos;
+        Ok(())
+    }
+
+    pub fn take(&mut self, n: usize) -> Result<&'a [u8], FriedmanError> {
+        if n > self.remaining() {
+            return Err(self.invalid(format!("file is truncated at byte {}", self.pos)));
+        }
+        let bytes = &self.data[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    pub fn skip(&mut self, n: usize) -> Result<(), FriedmanError> {
+        self.take(n).map(|_| ())
+    }
+
+    /// Whether the next bytes are `tag`, without consuming them.
+    pub fn at(&self, tag: &[u8]) -> bool {
+        self.data[self.pos..].starts_with(tag)
+    }
+
+    /// Consume `tag` or fail.
+    pub fn expect(&mut self, tag: &str) -> Result<(), FriedmanError> {
+        if !self.at(tag.as_bytes()) {
+            return Err(self.invalid(format!("expected `{tag}` at byte {}", self.pos)));
+        }
+        self.pos += tag.len();
+        Ok(())
+    }
+
+    /// Unsigned integer of `n` (at most 8) bytes.
+    pub fn uint(&mut self, n: usize) -> Result<u64, FriedmanError> {
+        let bytes = self.take(n)?;
+        let fold = |acc: u64, b: &u8| acc << 8 | u64::from(*b);
+        Ok(if self.big_endian {
+            bytes.iter().fold(0, fold)
+        } else {
+            bytes.iter().rev().fold(0, fold)
+        })
+    }
+
+    pub fn u8(&mut self) -> Result<u8, FriedmanError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn u16(&mut self) -> Result<u16, FriedmanError> {
+        self.uint(2).map(|v| v as u16)
+    }
+
+    pub fn u32(&mut self) -> Result<u32, FriedmanError> {
+        self.uint(4).map(|v| v as u32)
+    }
+
+    pub fn u64(&mut self) -> Result<u64, FriedmanError> {
+        self.uint(8)
+    }
+
+    pub fn i8(&mut self) -> Result<i8, FriedmanError> {
+        self.u8().map(|v| v as i8)
+    }
+
+    pub fn i16(&mut self) -> Result<i16, FriedmanError> {
+        self.u16().map(|v| v as i16)
+    }
+
+    pub fn i32(&mut self) -> Result<i32, FriedmanError> {
+        self.u32().map(|v| v as i32)
+    }
+
+    pub fn f32(&mut self) -> Result<f32, FriedmanError> {
+        self.u32().map(f32::from_bits)
+    }
+
+    pub fn f64(&mut self) -> Result<f64, FriedmanError> {
+        self.u64().map(f64::from_bits)
+    }
+
+    /// Fixed-width text field, cut at the first NUL.
+    pub fn text(&mut self, n: usize, utf8: bool) -> Result<String, FriedmanError> {
+        Ok(decode(self.take(n)?, utf8))
+    }
+
+    pub fn invalid(&self, detail: String) -> FriedmanError {
+        FriedmanError::InvalidParams(format!("Invalid {} file: {detail}", self.what))
+    }
+}
+
+/// Text up to the first NUL, as UTF-8 or Windows-1252.
+pub(crate) fn decode(bytes: &[u8], utf8: bool) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    let bytes = &bytes[..end];
+    if utf8 {
+        String::from_utf8_lossy(bytes).into_owned()
+    } else {
+        encoding_rs::WINDOWS_1252.decode_without_bom_handling(bytes).0.into_owned()
+    }
+}
@@ -0,0 +1,202 @@
+use std::fs::{self, File};
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, Float64Array, Int64Array, RecordBatch, RecordBatchReader, StringArray};
+use arrow_cast::display::{ArrayFormatter, FormatOptions};
+use arrow_schema::{ArrowError, DataType, Field, Schema};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use serde::{Deserialize, Serialize};
+
+use super::period::detect_time_index;
+use super::profile::{is_missing, profile_table, ColumnKind};
+use super::{file_name, Table};
+use crate::error::FriedmanError;
+
+/// Leading bytes of an Arrow IPC file (as opposed to a stream).
+const ARROW_FILE_MAGIC: &[u8] = b"ARROW1";
+
+/// A column of a Parquet or Arrow file's schema.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldInfo {
+    pub name: String,
+    /// Arrow type, e.g. `Float64`, `Utf8` or `Date32`.
+    pub data_type: String,
+    pub nullable: bool,
+    /// Whether the column is part of the loaded dataset.
+    pub included: bool,
+}
+
+/// Columnar file formats datasets can be read from and exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnarFormat {
+    Parquet,
+    /// Arrow IPC file (Feather v2).
+    Arrow,
+}
+
+impl ColumnarFormat {
+    /// From a file extension: `.parquet`/`.pq`, or `.arrow`/`.feather`/`.ipc`.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "parquet" | "pq" => Some(Self::Parquet),
+            "arrow" | "feather" | "ipc" => Some(Self::Arrow),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Parquet => "Parquet",
+            Self::Arrow => "Arrow",
+        }
+    }
+}
+
+/// Read a Parquet or Arrow IPC (file or stream) dataset. Numeric columns are
+/// kept, plus the date or period column used as the time index; the schema
+/// records which columns were dropped.
+pub fn read_columnar(path: &Path, format: ColumnarFormat) -> Result<(Table, Vec<FieldInfo>), FriedmanError> {
+    let error = |e: &dyn std::fmt::Display| read_error(path, format, e);
+    let reader: Box<dyn RecordBatchReader> = match format {
+        ColumnarFormat::Parquet => Box::new(
+            ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)
+                .and_then(|b| b.build())
+                .map_err(|e| error(&e))?,
+        ),
+        ColumnarFormat::Arrow => {
+            let mut magic = [0u8; 6];
+            let is_file = File::open(path)?.read_exact(&mut magic).is_ok() && magic == ARROW_FILE_MAGIC;
+            if is_file {
+                Box::new(arrow_ipc::reader::FileReader::try_new(File::open(path)?, None).map_err(|e| error(&e))?)
+            } else {
+                Box::new(
+                    arrow_ipc::reader::StreamReader::try_new(BufReader::new(File::open(path)?), None)
+                        .map_err(|e| error(&e))?,
+                )
+            }
+        }
+    };
+
+    let schema = reader.schema();
+    let mut all = Table {
+        columns: schema.fields().iter().map(|f| f.name().clone()).collect(),
+        rows: Vec::new(),
+    };
+    for batch in reader {
+        let batch = batch.map_err(|e| error(&e))?;
+        let columns = batch
+            .columns()
+            .iter()
+            .map(column_text)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| error(&e))?;
+        all.rows
+            .extend((0..batch.num_rows()).map(|r| columns.iter().map(|c| c[r].clone()).collect::<Vec<_>>()));
+    }
+
+    let index_column = detect_time_index(&all).map(|index| index.column);
+    let fields: Vec<FieldInfo> = schema
+        .fields()
+        .iter()
+        .map(|f| FieldInfo {
+            name: f.name().clone(),
+            data_type: f.data_type().to_string(),
+            nullable: f.is_nullable(),
+            included: f.data_type().is_numeric() || index_column.as_ref() == Some(f.name()),
+        })
+        .collect();
+    if !fields.iter().any(|f| f.included) {
+        return Err(error(&"no numeric columns"));
+    }
+
+    let keep: Vec<usize> = (0..fields.len()).filter(|&i| fields[i].included).collect();
+    let table = Table {
+        columns: keep.iter().map(|&i| all.columns[i].clone()).collect(),
+        rows: all
+            .rows
+            .into_iter()
+            .map(|row| keep.iter().map(|&i| row[i].clone()).collect())
+            .collect(),
+    };
+    Ok((table, fields))
+}
+
+/// Write `table` as Parquet (Snappy-compressed) or an Arrow IPC file.
+/// Numeric columns become `Int64` or `Float64` and the others `Utf8`, with
+/// missing cells as nulls.
+pub fn write_columnar(table: &Table, path: &Path, format: ColumnarFormat) -> Result<(), FriedmanError> {
+    let error = |e: &dyn std::fmt::Display| {
+        FriedmanError::InvalidParams(format!("Cannot write {} file {}: {e}", format.label(), path.display()))
+    };
+    let profiles = profile_table(table);
+    let mut fields = Vec::with_capacity(table.columns.len());
+    let mut arrays: Vec<ArrayRef> = Vec::with_capacity(table.columns.len());
+    for (i, (name, profile)) in table.columns.iter().zip(&profiles).enumerate() {
+        let cells = table.rows.iter().map(|row| Some(row[i].as_str()).filter(|c| !is_missing(c)));
+        let array: ArrayRef = match profile.kind {
+            ColumnKind::Numeric if cells.clone().flatten().all(|c| c.parse::<i64>().is_ok()) => {
+                Arc::new(cells.map(|c| c.and_then(|c| c.parse::<i64>().ok())).collect::<Int64Array>())
+            }
+            ColumnKind::Numeric => {
+                Arc::new(cells.map(|c| c.and_then(|c| c.parse::<f64>().ok())).collect::<Float64Array>())
+            }
+            _ => Arc::new(cells.collect::<StringArray>()),
+        };
+        fields.push(Field::new(name, array.data_type().clone(), true));
+        arrays.push(array);
+    }
+    let schema = Arc::new(Schema::new(fields));
+    let batch = RecordBatch::try_new(schema.clone(), arrays).map_err(|e| error(&e))?;
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let file = File::create(path)?;
+    match format {
+        ColumnarFormat::Parquet => {
+            let props = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+            let mut writer = ArrowWriter::try_new(file, schema, Some(props)).map_err(|e| error(&e))?;
+            writer.write(&batch).map_err(|e| error(&e))?;
+            writer.close().map_err(|e| error(&e))?;
+        }
+        ColumnarFormat::Arrow => {
+            let mut writer = arrow_ipc::writer::FileWriter::try_new(file, &schema).map_err(|e| error(&e))?;
+            writer.write(&batch).map_err(|e| error(&e))?;
+            writer.finish().map_err(|e| error(&e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Cells of `array` as text: nulls are empty and timestamps at midnight are
+/// written as plain dates.
+fn column_text(array: &ArrayRef) -> Result<Vec<String>, ArrowError> {
+    let options = FormatOptions::default();
+    let formatter = ArrayFormatter::try_new(array.as_ref(), &options)?;
+    let mut cells: Vec<String> = (0..array.len())
+        .map(|i| if array.is_null(i) { String::new() } else { formatter.value(i).to_string() })
+        .collect();
+    if matches!(array.data_type(), DataType::Timestamp(..))
+        && cells.iter().all(|c| c.is_empty() || c.contains("T00:00:00"))
+    {
+        for cell in &mut cells {
+            cell.truncate(cell.find('T').unwrap_or(cell.len()));
+        }
+    }
+    Ok(cells)
+}
+
+fn read_error(path: &Path, format: ColumnarFormat, e: &dyn std::fmt::Display) -> FriedmanError {
+    FriedmanError::InvalidParams(format!(
+        "Cannot read {} file {}: {e}",
+        format.label(),
+        file_name(&path.display().to_string())
+    ))
+}
@@ -0,0 +1,265 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::Table;
+use crate::error::FriedmanError;
+
+/// Delimiters tried by the sniffer, in order of preference on a tie.
+const DELIMITERS: &[char] = &[',', ';', '\t', '|'];
+/// Records inspected when sniffing the delimiter.
+const SNIFF_RECORDS: usize = 50;
+/// Bytes of text inspected when sniffing the delimiter.
+const SNIFF_BYTES: usize = 64 * 1024;
+
+/// How a delimited text file is written, as detected when it was loaded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CsvDialect {
+    pub delimiter: char,
+    pub quote: char,
+    /// `utf-8`, `utf-16le`, `utf-16be` or `windows-1252`.
+    pub encoding: String,
+    /// Whether the file starts with a byte-order mark.
+    pub bom: bool,
+    /// Decimal separator of numeric cells: `.` or `,`.
+    pub decimal: char,
+}
+
+impl CsvDialect {
+    /// Whether friedman-cli can read the file as it is; otherwise a
+    /// normalized copy is handed to the sidecar.
+    pub fn is_plain(&self) -> bool {
+        self.delimiter == ','
+            && self.quote == '"'
+            && self.encoding == "utf-8"
+            && !self.bom
+            && self.decimal == '.'
+    }
+}
+
+/// Read a delimited file, detecting its dialect.
+pub fn read_csv(path: &Path) -> Result<(Table, CsvDialect), FriedmanError> {
+    parse_csv(&fs::read(path)?)
+}
+
+/// Parse delimited text (RFC 4180 quoting, first record is the header).
+///
+/// Numeric cells written with a decimal comma are converted to `.`
+/// decimals, dropping `.` thousands separators. Repeated header names get a
+/// suffix (`x`, `x_2`). Rows with a different number of fields than the
+/// header are rejected with the offending row, line and column.
+pub fn parse_csv(bytes: &[u8]) -> Result<(Table, CsvDialect), FriedmanError> {
+    let (text, encoding, bom) = decode_text(bytes);
+    if text.trim().is_empty() {
+        return Err(FriedmanError::InvalidParams("CSV file is empty".into()));
+    }
+    let delimiter = sniff_delimiter(&text);
+    let quote = sniff_quote(&text, delimiter);
+
+    let mut records = reader(&text, delimiter, quote).into_records();
+    let header = records
+        .next()
+        .transpose()
+        .map_err(|e| invalid_csv(e, 0))?
+        .ok_or_else(|| FriedmanError::InvalidParams("CSV file is empty".into()))?;
+    let columns = unique_names(header.iter().map(|c| c.trim().to_string()).collect());
+
+    let mut rows = Vec::new();
+    for (index, record) in records.enumerate() {
+        let row = index + 1;
+        let record = record.map_err(|e| invalid_csv(e, row))?;
+        if record.len() != columns.len() {
+            let line = record.position().map_or(0, |p| p.line());
+            return Err(ragged_row(row, line, &record, &columns));
+        }
+        rows.push(record.iter().map(|c| c.trim().to_string()).collect::<Vec<_>>());
+    }
+
+    let decimal = if delimiter != ',' && decimal_comma(&rows) { ',' } else { '.' };
+    if decimal == ',' {
+        for cell in rows.iter_mut().flatten() {
+            if let Some(converted) = from_decimal_comma(cell) {
+                *cell = converted;
+            }
+        }
+    }
+
+    let dialect = CsvDialect {
+        delimiter,
+        quote,
+        encoding: encoding.to_string(),
+        bom,
+        decimal,
+    };
+    Ok((Table { columns, rows }, dialect))
+}
+
+/// Decode `bytes` as UTF-8 or UTF-16 (by BOM), falling back to Windows-1252
+/// for legacy spreadsheet exports.
+fn decode_text(bytes: &[u8]) -> (String, &'static str, bool) {
+    if let Some(rest) = bytes.strip_prefix(b"\xEF\xBB\xBF") {
+        return (String::from_utf8_lossy(rest).into_owned(), "utf-8", true);
+    }
+    if let Some(rest) = bytes.strip_prefix(b"\xFF\xFE") {
+        let (text, _) = encoding_rs::UTF_16LE.decode_without_bom_handling(rest);
+        return (text.into_owned(), "utf-16le", true);
+    }
+    if let Some(rest) = bytes.strip_prefix(b"\xFE\xFF") {
+        let (text, _) = encoding_rs::UTF_16BE.decode_without_bom_handling(rest);
+        return (text.into_owned(), "utf-16be", true);
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(text) => (text.to_string(), "utf-8", false),
+        Err(_) => {
+            let (text, _) = encoding_rs::WINDOWS_1252.decode_without_bom_handling(bytes);
+            (text.into_owned(), "windows-1252", false)
+        }
+    }
+}
+
+fn reader(text: &str, delimiter: char, quote: char) -> csv::Reader<&[u8]> {
+    csv::ReaderBuilder::new()
+        .delimiter(delimiter as u8)
+        .quote(quote as u8)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes())
+}
+
+/// The delimiter giving the most records with the same field count (more
+/// than one field), preferring the earlier candidate on a tie.
+fn sniff_delimiter(text: &str) -> char {
+    let mut end = text.len().min(SNIFF_BYTES);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    let sample = &text[..end];
+
+    let mut best = (',', 0, 0);
+    for &delimiter in DELIMITERS {
+        let counts: Vec<usize> = reader(sample, delimiter, '"')
+            .into_records()
+            .take(SNIFF_RECORDS)
+            .filter_map(Result::ok)
+            .map(|r| r.len())
+            .collect();
+        let Some((fields, frequency)) = mode(&counts) else { continue };
+        if fields > 1 && (frequency, fields) > (best.1, best.2) {
+            best = (delimiter, frequency, fields);
+        }
+    }
+    best.0
+}
+
+/// Most frequent value and its frequency.
+fn mode(values: &[usize]) -> Option<(usize, usize)> {
+    let mut best: Option<(usize, usize)> = None;
+    for &v in values {
+        let frequency = values.iter().filter(|&&x| x == v).count();
+        if best.map_or(true, |(_, f)| frequency > f) {
+            best = Some((v, frequency));
+        }
+    }
+    best
+}
+
+/// `'` when it opens fields and `"` never does; `"` otherwise.
+fn sniff_quote(text: &str, delimiter: char) -> char {
+    let opens = |q: char| {
+        text.lines()
+            .take(SNIFF_RECORDS)
+            .map(|line| {
+                usize::from(line.starts_with(q))
+                    + line.matches(&format!("{delimiter}{q}")).count()
+            })
+            .sum::<usize>()
+    };
+    if opens('"') == 0 && opens('\'') > 0 {
+        '\''
+    } else {
+        '"'
+    }
+}
+
+/// Suffix repeated names with `_2`, `_3`, ..., skipping names the header
+/// already has.
+fn unique_names(names: Vec<String>) -> Vec<String> {
+    let mut unique: Vec<String> = Vec::with_capacity(names.len());
+    for name in &names {
+        let mut candidate = name.clone();
+        let mut n = 1;
+        while unique.contains(&candidate) {
+            n += 1;
+            candidate = format!("{name}_{n}");
+            if names.contains(&candidate) {
+                candidate = name.clone();
+            }
+        }
+        unique.push(candidate);
+    }
+    unique
+}
+
+/// Whether numeric cells use a decimal comma (`1,5`) more often than a
+/// decimal point (`1.5`). Cells like `1.234` could be either and count for
+/// neither.
+fn decimal_comma(rows: &[Vec<String>]) -> bool {
+    let (mut comma, mut point) = (0usize, 0usize);
+    for cell in rows.iter().flatten() {
+        if cell.contains(',') && from_decimal_comma(cell).is_some() {
+            comma += 1;
+        } else if cell.contains('.') && cell.parse::<f64>().is_ok() && !thousands_grouped(cell) {
+            point += 1;
+        }
+    }
+    comma > point
+}
+
+/// `1.234,5` → `1234.5` and `1.234` → `1234`; `None` if the cell is not a
+/// decimal-comma number.
+fn from_decimal_comma(cell: &str) -> Option<String> {
+    if !cell.contains(',') {
+        return thousands_grouped(cell).then(|| cell.replace('.', ""));
+    }
+    let converted = cell.replace('.', "").replace(',', ".");
+    let digits = converted.trim_start_matches(['-', '+']);
+    let valid = digits.split('.').count() == 2
+        && digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && converted.parse::<f64>().is_ok();
+    valid.then_some(converted)
+}
+
+/// Whether `cell` is an integer with `.` between groups of three digits.
+fn thousands_grouped(cell: &str) -> bool {
+    let mut groups = cell.trim_start_matches(['-', '+']).split('.');
+    let lead = groups.next().unwrap_or_default();
+    let mut rest = groups.peekable();
+    (1..=3).contains(&lead.len())
+        && lead.chars().all(|c| c.is_ascii_digit())
+        && rest.peek().is_some()
+        && rest.all(|g| g.len() == 3 && g.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn ragged_row(row: usize, line: u64, record: &csv::StringRecord, columns: &[String]) -> FriedmanError {
+    let detail = if record.len() < columns.len() {
+        format!("column `{}` is missing", columns[record.len()])
+    } else {
+        format!(
+            "unexpected value `{}` in column {} after `{}`",
+            &record[columns.len()],
+            columns.len() + 1,
+            columns.last().map(String::as_str).unwrap_or_default()
+        )
+    };
+    FriedmanError::InvalidParams(format!(
+        "CSV row {row} (line {line}) has {} fields but the header has {}: {detail}",
+        record.len(),
+        columns.len()
+    ))
+}
+
+fn invalid_csv(e: csv::Error, row: usize) -> FriedmanError {
+    let line = e.position().map_or(0, |p| p.line());
+    FriedmanError::InvalidParams(format!("CSV row {row} (line {line}): {e}"))
+}
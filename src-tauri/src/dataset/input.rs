@@ -0,0 +1,380 @@
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use super::delimited::read_csv;
+use super::missing::{reject_remaining, treat_missing, MissingPolicy, MissingReport, MissingTreatment};
+use super::period::{detect_time_index, parse_period, Period, TimeIndex};
+use super::profile::is_missing;
+use super::{blocking, dataset_dir, Table};
+use crate::error::FriedmanError;
+use crate::sidecar::CommandOutput;
+use crate::state::AppState;
+
+/// The data an analysis runs on, flattened into every command's params:
+/// either `data`, a file passed to friedman-cli as-is, or `dataset_id`, a
+/// loaded dataset. Either can be narrowed to an ordered list of `columns`
+/// and a sample window, and have its missing values treated.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DataInput {
+    /// Path of a data file.
+    #[serde(default)]
+    pub data: String,
+    /// Loaded dataset to use instead of `data`.
+    #[serde(default)]
+    pub dataset_id: Option<String>,
+    /// Columns to pass, in this order, which sets the variable order of
+    /// recursive (Cholesky) identification. Every column if empty. A
+    /// command's `column` option still counts the columns of the dataset or
+    /// file, and is moved to that column's place in the selection.
+    #[serde(default)]
+    pub columns: Vec<String>,
+    /// First observation of the sample; the first row if unset.
+    #[serde(default)]
+    pub sample_start: Option<SampleBound>,
+    /// Last observation of the sample; the last row if unset.
+    #[serde(default)]
+    pub sample_end: Option<SampleBound>,
+    #[serde(default)]
+    pub missing: MissingPolicy,
+}
+
+/// One end of a sample window: a date or period label such as `2007Q4`,
+/// `2008` or `2019-12-31`, matched against the time index, or an
+/// observation number counting from 1. Only a JSON number is an observation
+/// number; a string is always a label, so `"5"` means the year 5, not row 5.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SampleBound {
+    Observation(usize),
+    Label(String),
+}
+
+/// The rows an analysis ran on, returned with its result.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EffectiveSample {
+    /// Observation numbers (from 1) of the first and last row.
+    pub first: usize,
+    pub last: usize,
+    /// Fewer than `last - first + 1` when rows were dropped for missing
+    /// values.
+    pub observations: usize,
+    /// Time index labels of the first and last row, when there is an index.
+    pub start: Option<String>,
+    pub end: Option<String>,
+}
+
+impl EffectiveSample {
+    pub fn new(rows: Range<usize>, index: Option<&TimeIndex>) -> Option<Self> {
+        Self::from_rows(&rows.collect::<Vec<_>>(), index)
+    }
+
+    /// The sample of `rows`, 0-based indices in increasing order.
+    pub fn from_rows(rows: &[usize], index: Option<&TimeIndex>) -> Option<Self> {
+        let (&first, &last) = (rows.first()?, rows.last()?);
+        let label = |r: usize| {
+            index
+                .and_then(|index| index.labels.get(r))
+                .filter(|label| !label.is_empty())
+                .cloned()
+        };
+        Some(Self {
+            first: first + 1,
+            last: last + 1,
+            observations: rows.len(),
+            start: label(first),
+            end: label(last),
+        })
+    }
+}
+
+impl DataInput {
+    /// The file to hand to friedman-cli. A loaded dataset, a column
+    /// selection or a sample window is written to a temporary CSV, after
+    /// treating missing values, that is deleted when the returned
+    /// [`DataFile`] drops. A plain file without options is passed as-is.
+    /// The file work runs on the blocking pool.
+    pub async fn resolve(&self, app: &AppHandle) -> Result<DataFile, FriedmanError> {
+        let (input, app) = (self.clone(), app.clone());
+        blocking(move || input.resolve_blocking(&app)).await
+    }
+
+    fn resolve_blocking(&self, app: &AppHandle) -> Result<DataFile, FriedmanError> {
+        let state = app.state::<AppState>();
+        let (path, dataset) = match &self.dataset_id {
+            Some(id) => {
+                let info = state
+                    .datasets
+                    .lock()
+                    .expect("datasets lock poisoned")
+                    .get(id)
+                    .cloned()
+                    .ok_or_else(|| FriedmanError::InvalidParams(format!("Dataset not found: {id}")))?;
+                (info.path.clone(), Some(info))
+            }
+            None if self.data.is_empty() => {
+                return Err(FriedmanError::InvalidParams("No data file or dataset given".into()));
+            }
+            None => (self.data.clone(), None),
+        };
+        if dataset.is_none()
+            && self.columns.is_empty()
+            && self.sample_start.is_none()
+            && self.sample_end.is_none()
+            && self.missing == MissingPolicy::default()
+        {
+            return Ok(DataFile {
+                path,
+                temporary: false,
+                source_columns: Vec::new(),
+                columns: Vec::new(),
+                sample: None,
+                missing: None,
+            });
+        }
+
+        let (table, index) = match &dataset {
+            Some(info) => (state.tables.get_or_load(&info.id, Path::new(&path))?, info.time_index.clone()),
+            None => {
+                let table = read_csv(Path::new(&path))?.0;
+                let index = detect_time_index(&table);
+                (Arc::new(table), index)
+            }
+        };
+        let rows = sample_rows(
+            index.as_ref(),
+            table.rows.len(),
+            self.sample_start.as_ref(),
+            self.sample_end.as_ref(),
+        )?;
+        let slice = if self.missing.tokens.is_empty() {
+            slice_table(&table, &self.columns, rows.clone())?
+        } else {
+            let mut window = Table { columns: table.columns.clone(), rows: table.rows[rows.clone()].to_vec() };
+            self.missing.blank_tokens(&mut window);
+            slice_table(&window, &self.columns, 0..window.rows.len())?
+        };
+        let skip = index.as_ref().map(|index| index.column.as_str());
+        let (slice, kept, report) = treat_missing(slice, self.missing.treatment, skip)?;
+        let kept: Vec<usize> = kept.iter().map(|r| rows.start + r).collect();
+        if self.missing.treatment == MissingTreatment::None {
+            reject_remaining(&slice, &kept, &report)?;
+        }
+
+        // The file name depends only on the data, so recorded transcripts
+        // replay; the directory keeps concurrent runs apart.
+        let stem = dataset.as_ref().map_or(path.as_str(), |info| info.name.as_str());
+        let stem = Path::new(stem).file_stem().map_or("data".into(), |s| s.to_string_lossy());
+        let path = dataset_dir(app)
+            .join("slices")
+            .join(uuid::Uuid::new_v4().to_string())
+            .join(format!("{stem}-{:016x}.csv", content_hash(&slice)));
+        let file = DataFile {
+            path: path.display().to_string(),
+            temporary: true,
+            source_columns: if self.columns.is_empty() { Vec::new() } else { table.columns.clone() },
+            columns: self.columns.clone(),
+            sample: EffectiveSample::from_rows(&kept, index.as_ref()),
+            missing: Some(report),
+        };
+        slice.write_csv(&path)?;
+        Ok(file)
+    }
+}
+
+/// A data file for one friedman-cli run.
+pub struct DataFile {
+    path: String,
+    /// Written for this run and removed on drop.
+    temporary: bool,
+    /// Header of the dataset or file and the columns selected from it, in
+    /// file order; both empty without a selection.
+    source_columns: Vec<String>,
+    columns: Vec<String>,
+    sample: Option<EffectiveSample>,
+    missing: Option<MissingReport>,
+}
+
+impl DataFile {
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The `--column` value for column `column` (from 1) of the dataset or
+    /// file, counted in the file actually passed.
+    pub fn column(&self, column: u32) -> Result<String, FriedmanError> {
+        if self.columns.is_empty() {
+            return Ok(column.to_string());
+        }
+        selected_column(&self.source_columns, &self.columns, column).map(|c| c.to_string())
+    }
+
+    /// Attach the effective sample and missing-value report, when known,
+    /// to a command's output.
+    pub fn record_input(&self, mut output: CommandOutput) -> CommandOutput {
+        output.sample = self.sample.clone();
+        if let Some(report) = &self.missing {
+            output.warnings.extend(report.warnings());
+        }
+        output.missing = self.missing.clone();
+        output
+    }
+}
+
+impl Drop for DataFile {
+    fn drop(&mut self) {
+        if self.temporary {
+            let path = Path::new(&self.path);
+            let _ = std::fs::remove_file(path);
+            if let Some(dir) = path.parent() {
+                let _ = std::fs::remove_dir(dir);
+            }
+        }
+    }
+}
+
+/// FNV-1a over the header and cells of `table`; stable across builds,
+/// unlike `DefaultHasher`.
+pub fn content_hash(table: &Table) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for cell in table.columns.iter().chain(table.rows.iter().flatten()) {
+        // The separator keeps `ab,c` and `a,bc` apart.
+        for &byte in cell.as_bytes().iter().chain(&[0xff]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+/// `rows` of `table`, with `columns` in the given order (or every column
+/// if empty). Selected columns must exist, be numeric and appear once.
+pub fn slice_table(table: &Table, columns: &[String], rows: Range<usize>) -> Result<Table, FriedmanError> {
+    let rows = &table.rows[rows];
+    if columns.is_empty() {
+        return Ok(Table { columns: table.columns.clone(), rows: rows.to_vec() });
+    }
+    let mut indices = Vec::with_capacity(columns.len());
+    for (i, name) in columns.iter().enumerate() {
+        if columns[..i].contains(name) {
+            return Err(FriedmanError::InvalidParams(format!("Column `{name}` is selected twice")));
+        }
+        let c = table
+            .columns
+            .iter()
+            .position(|col| col == name)
+            .ok_or_else(|| FriedmanError::InvalidParams(format!("Unknown column `{name}`")))?;
+        if let Some(cell) = rows
+            .iter()
+            .map(|row| row[c].trim())
+            .find(|cell| !is_missing(cell) && !cell.parse::<f64>().is_ok_and(f64::is_finite))
+        {
+            return Err(FriedmanError::InvalidParams(format!(
+                "Column `{name}` is not numeric (found `{cell}`) and cannot be analyzed"
+            )));
+        }
+        indices.push(c);
+    }
+    Ok(Table {
+        columns: columns.to_vec(),
+        rows: rows
+            .iter()
+            .map(|row| indices.iter().map(|&c| row[c].clone()).collect())
+            .collect(),
+    })
+}
+
+/// Position (from 1) in `selected` of column `column` (from 1) of `source`.
+pub fn selected_column(source: &[String], selected: &[String], column: u32) -> Result<u32, FriedmanError> {
+    let name = (column as usize)
+        .checked_sub(1)
+        .and_then(|c| source.get(c))
+        .ok_or_else(|| {
+            FriedmanError::InvalidParams(format!("Column {column} is not between 1 and {}", source.len()))
+        })?;
+    let position = selected.iter().position(|c| c == name).ok_or_else(|| {
+        FriedmanError::InvalidParams(format!("Column {column} (`{name}`) is not among the selected columns"))
+    })?;
+    Ok(position as u32 + 1)
+}
+
+/// Rows from `start` to `end` (inclusive) of a dataset with `len` rows.
+/// Labels are matched against `index`, whose rows must be in time order: the
+/// sample starts at the first row at or after `start` and ends at the last
+/// row at or before `end`, so `2008` on quarterly data means 2008Q1 as a
+/// start and 2008Q4 as an end.
+pub fn sample_rows(
+    index: Option<&TimeIndex>,
+    len: usize,
+    start: Option<&SampleBound>,
+    end: Option<&SampleBound>,
+) -> Result<Range<usize>, FriedmanError> {
+    let first = match start {
+        Some(bound) => bound_row(index, len, bound, false)?,
+        None => 0,
+    };
+    let last = match end {
+        Some(bound) => bound_row(index, len, bound, true)?,
+        None => len.saturating_sub(1),
+    };
+    if len == 0 || first > last {
+        return Err(FriedmanError::InvalidParams("The sample window contains no observations".into()));
+    }
+    Ok(first..last + 1)
+}
+
+fn bound_row(index: Option<&TimeIndex>, len: usize, bound: &SampleBound, is_end: bool) -> Result<usize, FriedmanError> {
+    let which = if is_end { "end" } else { "start" };
+    let label = match bound {
+        SampleBound::Observation(n) if (1..=len).contains(n) => return Ok(n - 1),
+        SampleBound::Observation(n) => {
+            return Err(FriedmanError::InvalidParams(format!(
+                "Sample {which} {n} is not an observation number between 1 and {len}"
+            )));
+        }
+        SampleBound::Label(label) => label.trim(),
+    };
+    let index = index.ok_or_else(|| {
+        FriedmanError::InvalidParams(format!(
+            "Sample {which} `{label}` needs a date column; give an observation number instead"
+        ))
+    })?;
+    if !index.sorted {
+        return Err(FriedmanError::InvalidParams(format!(
+            "Rows are not in time order by `{}`; give the sample {which} as an observation number",
+            index.column
+        )));
+    }
+    let bound = parse_period(label)
+        .ok_or_else(|| FriedmanError::InvalidParams(format!("Sample {which} `{label}` is not a date or period")))?;
+    // Weekly and business-day labels parse as plain dates.
+    let ordinal = |period: Period, last: bool| {
+        if period.frequency == index.frequency {
+            period.ordinal
+        } else {
+            let day = if last { period.last_day() } else { period.first_day() };
+            Period::containing(day, index.frequency).ordinal
+        }
+    };
+    let target = ordinal(bound, is_end);
+
+    let ordinals = index.labels.iter().map(|label| parse_period(label).map(|p| ordinal(p, false)));
+    let row = if is_end {
+        ordinals
+            .enumerate()
+            .rfind(|(_, ordinal)| ordinal.is_some_and(|o| o <= target))
+    } else {
+        ordinals
+            .enumerate()
+            .find(|(_, ordinal)| ordinal.is_some_and(|o| o >= target))
+    };
+    row.map(|(r, _)| r).ok_or_else(|| {
+        FriedmanError::InvalidParams(format!(
+            "Sample {which} `{label}` is outside the data, which runs from {} to {}",
+            index.start, index.end
+        ))
+    })
+}
@@ -0,0 +1,279 @@
+use serde::{Deserialize, Serialize};
+
+use super::profile::is_missing;
+use super::Table;
+use crate::error::FriedmanError;
+
+/// How to handle missing cells in the columns an analysis runs on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingTreatment {
+    /// Change nothing; analyses refuse columns that have missing values.
+    #[default]
+    None,
+    /// Drop leading and trailing rows where any column is missing.
+    Trim,
+    /// Drop every row where any column is missing.
+    Listwise,
+    /// Trim, then fill interior gaps by linear interpolation.
+    Linear,
+    /// Trim, then fill interior gaps with a natural cubic spline.
+    Spline,
+    /// Trim, then carry the last observation forward.
+    Locf,
+}
+
+/// Missing-value options of an analysis.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MissingPolicy {
+    #[serde(default)]
+    pub treatment: MissingTreatment,
+    /// Extra cells to read as missing, e.g. `-999`, on top of empty cells,
+    /// `NA`, `NaN`, `.` and the other built-in tokens.
+    #[serde(default)]
+    pub tokens: Vec<String>,
+}
+
+impl MissingPolicy {
+    /// Blank every cell that matches one of the custom tokens.
+    pub fn blank_tokens(&self, table: &mut Table) {
+        let tokens: Vec<&str> = self.tokens.iter().map(|t| t.trim()).collect();
+        for cell in table.rows.iter_mut().flatten() {
+            if tokens.iter().any(|t| cell.trim().eq_ignore_ascii_case(t)) {
+                cell.clear();
+            }
+        }
+    }
+}
+
+/// What a treatment changed, returned with the analysis result.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MissingReport {
+    pub treatment: MissingTreatment,
+    /// The numeric columns that were checked.
+    pub columns: Vec<ColumnMissing>,
+    pub rows_dropped: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ColumnMissing {
+    pub column: String,
+    /// Missing cells before treatment.
+    pub missing: usize,
+    /// Cells filled by interpolation or carried forward.
+    pub filled: usize,
+    /// Missing cells left in the rows that were kept.
+    pub remaining: usize,
+}
+
+impl MissingReport {
+    /// One warning per column that still has missing values.
+    pub fn warnings(&self) -> Vec<String> {
+        self.columns
+            .iter()
+            .filter(|column| column.remaining > 0)
+            .map(|column| {
+                format!(
+                    "Column `{}` still has {} missing values; choose a missing-value treatment",
+                    column.column, column.remaining
+                )
+            })
+            .collect()
+    }
+}
+
+/// Apply `treatment` to the numeric columns of `table`, other than `skip`
+/// (the time index). Returns the treated table, the indices of the rows it
+/// kept and a report. Infinite values are refused.
+pub fn treat_missing(
+    mut table: Table,
+    treatment: MissingTreatment,
+    skip: Option<&str>,
+) -> Result<(Table, Vec<usize>, MissingReport), FriedmanError> {
+    let n = table.rows.len();
+    let numeric: Vec<usize> = (0..table.columns.len())
+        .filter(|&c| Some(table.columns[c].as_str()) != skip)
+        .filter(|&c| {
+            table
+                .rows
+                .iter()
+                .all(|row| is_missing(&row[c]) || row[c].trim().parse::<f64>().is_ok())
+        })
+        .collect();
+    for &c in &numeric {
+        if let Some((r, row)) = table
+            .rows
+            .iter()
+            .enumerate()
+            .find(|(_, row)| row[c].trim().parse::<f64>().is_ok_and(|v| v.is_infinite()))
+        {
+            return Err(FriedmanError::InvalidParams(format!(
+                "Column `{}` has an infinite value (`{}`) in row {}",
+                table.columns[c],
+                row[c].trim(),
+                r + 1
+            )));
+        }
+    }
+    let mut values: Vec<Vec<Option<f64>>> = numeric
+        .iter()
+        .map(|&c| {
+            // `NaN` parses as a number, so check for missing cells first.
+            table
+                .rows
+                .iter()
+                .map(|row| Some(row[c].trim()).filter(|cell| !is_missing(cell)).and_then(|cell| cell.parse().ok()))
+                .collect()
+        })
+        .collect();
+    let mut columns: Vec<ColumnMissing> = numeric
+        .iter()
+        .zip(&values)
+        .map(|(&c, x)| ColumnMissing {
+            column: table.columns[c].clone(),
+            missing: x.iter().filter(|v| v.is_none()).count(),
+            filled: 0,
+            remaining: 0,
+        })
+        .collect();
+
+    let kept: Vec<usize> = match treatment {
+        MissingTreatment::None => (0..n).collect(),
+        MissingTreatment::Listwise => (0..n).filter(|&r| values.iter().all(|x| x[r].is_some())).collect(),
+        _ => {
+            let mut start = 0;
+            let mut end = n;
+            for (x, column) in values.iter().zip(&columns) {
+                let first = x.iter().position(Option::is_some).ok_or_else(|| {
+                    FriedmanError::InvalidParams(format!("Column `{}` has no values", column.column))
+                })?;
+                let last = x.iter().rposition(Option::is_some).unwrap_or(first);
+                start = start.max(first);
+                end = end.min(last + 1);
+            }
+            (start..end.max(start)).collect()
+        }
+    };
+    if kept.is_empty() {
+        return Err(FriedmanError::InvalidParams(
+            "No observations are left after treating missing values".into(),
+        ));
+    }
+
+    // Gaps are filled over the whole column, so values outside the kept
+    // rows still anchor the ones inside.
+    let fills = matches!(treatment, MissingTreatment::Linear | MissingTreatment::Spline | MissingTreatment::Locf);
+    for ((&c, x), column) in numeric.iter().zip(&mut values).zip(&mut columns) {
+        if fills {
+            fill(x, treatment);
+        }
+        for &r in &kept {
+            match x[r] {
+                Some(v) if is_missing(&table.rows[r][c]) => {
+                    table.rows[r][c] = v.to_string();
+                    column.filled += 1;
+                }
+                Some(_) => {}
+                None => column.remaining += 1,
+            }
+        }
+    }
+    let rows_dropped = n - kept.len();
+    if rows_dropped > 0 {
+        let mut keep = vec![false; n];
+        for &r in &kept {
+            keep[r] = true;
+        }
+        let mut r = 0;
+        table.rows.retain(|_| {
+            r += 1;
+            keep[r - 1]
+        });
+    }
+    Ok((table, kept, MissingReport { treatment, columns, rows_dropped }))
+}
+
+/// Refuse a treated table that still has missing cells in its numeric
+/// columns, naming the first one; friedman-cli cannot read them. `rows` are
+/// the observation indices (from 0) of the table's rows, as returned by
+/// [`treat_missing`].
+pub fn reject_remaining(table: &Table, rows: &[usize], report: &MissingReport) -> Result<(), FriedmanError> {
+    for column in report.columns.iter().filter(|column| column.remaining > 0) {
+        let Some(c) = table.columns.iter().position(|name| *name == column.column) else {
+            continue;
+        };
+        if let Some((r, row)) = table.rows.iter().enumerate().find(|(_, row)| is_missing(&row[c])) {
+            return Err(FriedmanError::InvalidParams(format!(
+                "Column `{}` has a missing value (`{}`) at observation {}; choose a missing-value treatment",
+                column.column,
+                row[c].trim(),
+                rows.get(r).copied().unwrap_or(r) + 1
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Fill the gaps of `x` that have a value on both sides; leading and
+/// trailing gaps stay missing.
+fn fill(x: &mut [Option<f64>], treatment: MissingTreatment) {
+    let known: Vec<(f64, f64)> = x
+        .iter()
+        .enumerate()
+        .filter_map(|(t, v)| v.map(|v| (t as f64, v)))
+        .collect();
+    let spline = match treatment {
+        MissingTreatment::Spline if known.len() > 2 => Some(natural_spline(&known)),
+        _ => None,
+    };
+    // Index in `known` of the first value after the current row.
+    let mut next = 0;
+    for (i, value) in x.iter_mut().enumerate() {
+        if value.is_some() {
+            next += 1;
+            continue;
+        }
+        if next == 0 || next == known.len() {
+            continue;
+        }
+        let k = next - 1;
+        let (t0, y0) = known[k];
+        let (t1, y1) = known[k + 1];
+        let t = i as f64;
+        *value = Some(match (treatment, &spline) {
+            (MissingTreatment::Locf, _) => y0,
+            (_, Some(m)) => {
+                // Cubic on [t0, t1] with second derivatives m[k], m[k + 1].
+                let h = t1 - t0;
+                let (a, b) = ((t1 - t) / h, (t - t0) / h);
+                a * y0 + b * y1 + ((a.powi(3) - a) * m[k] + (b.powi(3) - b) * m[k + 1]) * h * h / 6.0
+            }
+            _ => y0 + (y1 - y0) * (t - t0) / (t1 - t0),
+        });
+    }
+}
+
+/// Second derivatives at the knots of the natural cubic spline through
+/// `points`, by the tridiagonal (Thomas) algorithm.
+fn natural_spline(points: &[(f64, f64)]) -> Vec<f64> {
+    let n = points.len();
+    let mut m = vec![0.0; n];
+    let mut diag = vec![0.0; n];
+    let mut rhs = vec![0.0; n];
+    for i in 1..n - 1 {
+        let (h0, h1) = (points[i].0 - points[i - 1].0, points[i + 1].0 - points[i].0);
+        let slope = (points[i + 1].1 - points[i].1) / h1 - (points[i].1 - points[i - 1].1) / h0;
+        diag[i] = 2.0 * (h0 + h1);
+        rhs[i] = 6.0 * slope;
+        if i > 1 {
+            let w = h0 / diag[i - 1];
+            diag[i] -= w * h0;
+            rhs[i] -= w * rhs[i - 1];
+        }
+    }
+    for i in (1..n - 1).rev() {
+        let h1 = points[i + 1].0 - points[i].0;
+        m[i] = (rhs[i] - h1 * m[i + 1]) / diag[i];
+    }
+    m
+}
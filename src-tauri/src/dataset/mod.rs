@@ -0,0 +1,95 @@
+mod bytes;
+pub mod columnar;
+pub mod delimited;
+pub mod input;
+pub mod missing;
+pub mod period;
+pub mod preview;
+pub mod profile;
+pub mod spreadsheet;
+pub mod spss;
+pub mod stata;
+pub mod transform;
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::error::FriedmanError;
+
+/// A dataset held in memory as text cells, row-major, as read from the file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Table {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Write as a plain CSV (comma-separated, `"`-quoted, UTF-8, `.`
+    /// decimals), the format friedman-cli reads.
+    pub fn write_csv(&self, path: &Path) -> Result<(), FriedmanError> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut writer = csv::Writer::from_path(path).map_err(csv_error)?;
+        writer.write_record(&self.columns).map_err(csv_error)?;
+        for row in &self.rows {
+            writer.write_record(row).map_err(csv_error)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Labels and display format of a column, as stored in Stata and SPSS files.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ColumnMeta {
+    pub name: String,
+    /// Variable label, e.g. `Real GDP growth (percent)`.
+    pub label: Option<String>,
+    /// Display format, e.g. `%tq` or `F8.2`.
+    pub format: Option<String>,
+    /// Labels of coded values, in the file's order.
+    pub value_labels: Vec<ValueLabel>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValueLabel {
+    /// The value as written in the dataset, e.g. `1`.
+    pub value: String,
+    pub label: String,
+}
+
+/// Where normalized and derived dataset files are written.
+pub fn dataset_dir(app: &AppHandle) -> PathBuf {
+    app.path()
+        .app_cache_dir()
+        .unwrap_or_else(|_| std::env::temp_dir().join("friedman"))
+        .join("datasets")
+}
+
+/// File name of `path` for display, e.g. `macro.csv`.
+pub fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "unknown".into())
+}
+
+/// Run file reading, parsing and writing on the blocking pool, off the
+/// async runtime's worker threads.
+pub async fn blocking<T: Send + 'static>(
+    work: impl FnOnce() -> Result<T, FriedmanError> + Send + 'static,
+) -> Result<T, FriedmanError> {
+    tauri::async_runtime::spawn_blocking(work)
+        .await
+        .map_err(|e| FriedmanError::Io(std::io::Error::other(e)))?
+}
+
+fn csv_error(e: csv::Error) -> FriedmanError {
+    match e.into_kind() {
+        csv::ErrorKind::Io(e) => FriedmanError::Io(e),
+        kind => FriedmanError::InvalidParams(format!("CSV error: {kind:?}")),
+    }
+}
@@ -0,0 +1,404 @@
+use serde::{Deserialize, Serialize};
+
+use super::profile::is_missing;
+use super::Table;
+
+/// Column names that suggest a time index, compared case-insensitively.
+const INDEX_NAMES: &[&str] = &["date", "time", "period", "year", "quarter", "month", "obs", "t"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Frequency {
+    Annual,
+    Quarterly,
+    Monthly,
+    Weekly,
+    Daily,
+    /// Daily observations on weekdays only.
+    BusinessDaily,
+}
+
+impl Frequency {
+    /// Observations per year, for annualizing rates.
+    pub fn periods_per_year(self) -> f64 {
+        match self {
+            Self::Annual => 1.0,
+            Self::Quarterly => 4.0,
+            Self::Monthly => 12.0,
+            Self::Weekly => 52.0,
+            Self::Daily => 365.0,
+            Self::BusinessDaily => 260.0,
+        }
+    }
+}
+
+/// A parsed period: `ordinal` counts periods of `frequency` since year 0
+/// (annual, quarterly, monthly) or since the week of 1970-01-01.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Period {
+    pub frequency: Frequency,
+    pub ordinal: i64,
+}
+
+impl Period {
+    /// `2000`, `2000Q1`, `2000-01` or `2000-01-31`; weeks are labelled with
+    /// their Monday.
+    pub fn label(&self) -> String {
+        match self.frequency {
+            Frequency::Annual => self.ordinal.to_string(),
+            Frequency::Quarterly => {
+                format!("{}Q{}", self.ordinal.div_euclid(4), self.ordinal.rem_euclid(4) + 1)
+            }
+            Frequency::Monthly => {
+                format!("{}-{:02}", self.ordinal.div_euclid(12), self.ordinal.rem_euclid(12) + 1)
+            }
+            Frequency::Weekly | Frequency::Daily | Frequency::BusinessDaily => {
+                let (y, m, d) = civil_from_days(self.first_day());
+                format!("{y}-{m:02}-{d:02}")
+            }
+        }
+    }
+
+    /// First day of the period, as days since 1970-01-01.
+    pub fn first_day(&self) -> i64 {
+        let o = self.ordinal;
+        match self.frequency {
+            Frequency::Annual => days_from_civil(o, 1, 1),
+            Frequency::Quarterly => days_from_civil(o.div_euclid(4), o.rem_euclid(4) * 3 + 1, 1),
+            Frequency::Monthly => days_from_civil(o.div_euclid(12), o.rem_euclid(12) + 1, 1),
+            Frequency::Weekly => o * 7 - 3,
+            Frequency::Daily => o,
+            Frequency::BusinessDaily => o.div_euclid(5) * 7 + o.rem_euclid(5) - 3,
+        }
+    }
+
+    /// Last day of the period, as days since 1970-01-01.
+    pub fn last_day(&self) -> i64 {
+        match self.frequency {
+            Frequency::Daily | Frequency::BusinessDaily => self.first_day(),
+            _ => Period { ordinal: self.ordinal + 1, ..*self }.first_day() - 1,
+        }
+    }
+}
+
+impl Period {
+    /// The period of `frequency` containing a date, given as days since
+    /// 1970-01-01.
+    pub fn containing(days: i64, frequency: Frequency) -> Self {
+        let (y, m, _) = civil_from_days(days);
+        let ordinal = match frequency {
+            Frequency::Annual => y,
+            Frequency::Quarterly => y * 4 + (m - 1) / 3,
+            Frequency::Monthly => y * 12 + m - 1,
+            Frequency::Weekly => (days + 3).div_euclid(7),
+            Frequency::Daily => days,
+            Frequency::BusinessDaily => business_day(days),
+        };
+        Period { frequency, ordinal }
+    }
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date.
+pub fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// `2000-01-31` for a count of days since 1970-01-01, with `T12:30:00`
+/// appended when `seconds` into the day is not zero.
+pub fn date_time_label(days: i64, seconds: i64) -> String {
+    let date = Period { frequency: Frequency::Daily, ordinal: days }.label();
+    if seconds == 0 {
+        date
+    } else {
+        format!("{date}T{:02}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+    }
+}
+
+/// Parse one cell: `2000`, `2000Q1`, `2000:1` (quarter 1–4, month above 4),
+/// `2000M01`, `2000-01`, `2000-01-31` or `2000/01/31`.
+pub fn parse_period(cell: &str) -> Option<Period> {
+    let (frequency, ordinal) = match parse_raw(cell.trim())? {
+        Raw::Year(y) => (Frequency::Annual, y),
+        Raw::Colon(y, n) if n <= 4 => (Frequency::Quarterly, y * 4 + n - 1),
+        Raw::Colon(y, n) | Raw::Month(y, n) => (Frequency::Monthly, y * 12 + n - 1),
+        Raw::Quarter(y, q) => (Frequency::Quarterly, y * 4 + q - 1),
+        Raw::Date(days) => (Frequency::Daily, days),
+    };
+    Some(Period { frequency, ordinal })
+}
+
+/// Observations missing between two consecutive periods of the index.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexGap {
+    pub after: String,
+    pub before: String,
+    pub periods: usize,
+}
+
+/// The column detected as the dataset's time index.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeIndex {
+    pub column: String,
+    pub frequency: Frequency,
+    pub start: String,
+    pub end: String,
+    /// Label of each row in file order, normalized (e.g. `2000:1` → `2000Q1`);
+    /// empty for rows whose index cell is missing.
+    pub labels: Vec<String>,
+    pub gaps: Vec<IndexGap>,
+    /// Labels that occur on more than one row.
+    pub duplicates: Vec<String>,
+    /// Whether the rows are in increasing time order.
+    pub sorted: bool,
+    /// Rows whose index cell is missing.
+    pub missing: usize,
+}
+
+impl TimeIndex {
+    /// No gaps, duplicates or missing cells, in increasing order.
+    pub fn is_regular(&self) -> bool {
+        self.gaps.is_empty() && self.duplicates.is_empty() && self.sorted && self.missing == 0
+    }
+}
+
+/// Find the time index of `table`: columns named like `date` or `period`
+/// are tried first, then the others in order. Bare years are only accepted
+/// in a column with such a name, or in the first column if they are
+/// consecutive and increasing.
+pub fn detect_time_index(table: &Table) -> Option<TimeIndex> {
+    let named = |i: usize| INDEX_NAMES.iter().any(|n| table.columns[i].trim().eq_ignore_ascii_case(n));
+    let mut candidates: Vec<usize> = (0..table.columns.len()).filter(|&i| named(i)).collect();
+    candidates.extend((0..table.columns.len()).filter(|&i| !named(i)));
+
+    candidates.into_iter().find_map(|i| {
+        let cells: Vec<&str> = table.rows.iter().map(|r| r[i].as_str()).collect();
+        let index = index_from_cells(&table.columns[i], &cells, i == 0 || named(i))?;
+        let plain_years = index.frequency == Frequency::Annual
+            && !named(i)
+            && cells.iter().all(|c| is_missing(c) || c.trim().len() == 4);
+        (!plain_years || index.is_regular()).then_some(index)
+    })
+}
+
+fn index_from_cells(column: &str, cells: &[&str], years_allowed: bool) -> Option<TimeIndex> {
+    let mut raws = Vec::with_capacity(cells.len());
+    for cell in cells {
+        if is_missing(cell) {
+            raws.push(None);
+        } else {
+            raws.push(Some(parse_raw(cell.trim())?));
+        }
+    }
+    let present: Vec<Raw> = raws.iter().flatten().copied().collect();
+    if present.len() < 2 {
+        return None;
+    }
+
+    let periods: Vec<Option<Period>> = match present[0] {
+        Raw::Year(_) if years_allowed => resolve(&raws, |r| match r {
+            Raw::Year(y) => Some(Period { frequency: Frequency::Annual, ordinal: y }),
+            _ => None,
+        })?,
+        Raw::Year(_) => return None,
+        Raw::Quarter(..) => resolve(&raws, |r| match r {
+            Raw::Quarter(y, q) => Some(Period { frequency: Frequency::Quarterly, ordinal: y * 4 + q - 1 }),
+            _ => None,
+        })?,
+        Raw::Month(..) => resolve(&raws, |r| match r {
+            Raw::Month(y, m) => Some(Period { frequency: Frequency::Monthly, ordinal: y * 12 + m - 1 }),
+            _ => None,
+        })?,
+        Raw::Colon(..) => {
+            let monthly = present.iter().any(|r| matches!(r, Raw::Colon(_, n) if *n > 4));
+            let (frequency, per_year) = if monthly {
+                (Frequency::Monthly, 12)
+            } else {
+                (Frequency::Quarterly, 4)
+            };
+            resolve(&raws, |r| match r {
+                Raw::Colon(y, n) => Some(Period { frequency, ordinal: y * per_year + n - 1 }),
+                _ => None,
+            })?
+        }
+        Raw::Date(_) => {
+            let days: Vec<Option<i64>> = raws
+                .iter()
+                .map(|r| match r {
+                    None => Some(None),
+                    Some(Raw::Date(d)) => Some(Some(*d)),
+                    Some(_) => None,
+                })
+                .collect::<Option<_>>()?;
+            date_periods(&days)
+        }
+    };
+    Some(build_index(column, &periods))
+}
+
+fn resolve(raws: &[Option<Raw>], f: impl Fn(Raw) -> Option<Period>) -> Option<Vec<Option<Period>>> {
+    raws.iter()
+        .map(|r| match r {
+            None => Some(None),
+            Some(r) => f(*r).map(Some),
+        })
+        .collect()
+}
+
+/// Infer the frequency of dated observations from their typical spacing and
+/// map each date to a period of that frequency.
+fn date_periods(days: &[Option<i64>]) -> Vec<Option<Period>> {
+    let mut sorted: Vec<i64> = days.iter().flatten().copied().collect();
+    sorted.sort_unstable();
+    sorted.dedup();
+    let mut steps: Vec<i64> = sorted.windows(2).map(|w| w[1] - w[0]).collect();
+    steps.sort_unstable();
+    let step = steps.get(steps.len().saturating_sub(1) / 2).copied().unwrap_or(1);
+
+    let weekdays_only = sorted.iter().all(|&d| weekday(d) < 5);
+    let frequency = match step {
+        1..=3 if weekdays_only => Frequency::BusinessDaily,
+        1..=3 => Frequency::Daily,
+        4..=10 => Frequency::Weekly,
+        11..=45 => Frequency::Monthly,
+        46..=135 => Frequency::Quarterly,
+        _ => Frequency::Annual,
+    };
+    days.iter()
+        .map(|d| d.map(|d| Period::containing(d, frequency)))
+        .collect()
+}
+
+fn build_index(column: &str, periods: &[Option<Period>]) -> TimeIndex {
+    let present: Vec<Period> = periods.iter().flatten().copied().collect();
+    let frequency = present[0].frequency;
+    let labels: Vec<String> = periods
+        .iter()
+        .map(|p| p.map(|p| p.label()).unwrap_or_default())
+        .collect();
+
+    let sorted = present.windows(2).all(|w| w[0].ordinal <= w[1].ordinal);
+    let mut ordered: Vec<(i64, String)> = periods
+        .iter()
+        .zip(&labels)
+        .filter_map(|(p, l)| p.map(|p| (p.ordinal, l.clone())))
+        .collect();
+    ordered.sort();
+
+    let mut duplicates = Vec::new();
+    let mut gaps = Vec::new();
+    for w in ordered.windows(2) {
+        let ((a, after), (b, before)) = (&w[0], &w[1]);
+        if a == b {
+            if duplicates.last() != Some(after) {
+                duplicates.push(after.clone());
+            }
+        } else if b - a > 1 {
+            gaps.push(IndexGap {
+                after: after.clone(),
+                before: before.clone(),
+                periods: (b - a - 1) as usize,
+            });
+        }
+    }
+
+    TimeIndex {
+        column: column.to_string(),
+        frequency,
+        start: ordered.first().map(|(_, l)| l.clone()).unwrap_or_default(),
+        end: ordered.last().map(|(_, l)| l.clone()).unwrap_or_default(),
+        missing: periods.len() - present.len(),
+        labels,
+        gaps,
+        duplicates,
+        sorted,
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Raw {
+    Year(i64),
+    /// `2000:1`, quarterly or monthly depending on the rest of the column.
+    Colon(i64, i64),
+    Quarter(i64, i64),
+    Month(i64, i64),
+    /// Days since 1970-01-01.
+    Date(i64),
+}
+
+fn parse_raw(cell: &str) -> Option<Raw> {
+    let year: i64 = digits(cell.get(..4)?)?;
+    let rest = &cell[4..];
+    let sub = |s: &str, max: i64| -> Option<i64> {
+        (1..=2).contains(&s.len()).then_some(())?;
+        digits(s).filter(|n| (1..=max).contains(n))
+    };
+
+    if rest.is_empty() {
+        return Some(Raw::Year(year));
+    }
+    if let Some(q) = rest.strip_prefix(['Q', 'q']) {
+        return sub(q, 4).map(|q| Raw::Quarter(year, q));
+    }
+    if let Some(m) = rest.strip_prefix(['M', 'm']) {
+        return sub(m, 12).map(|m| Raw::Month(year, m));
+    }
+    if let Some(n) = rest.strip_prefix(':') {
+        return sub(n, 12).map(|n| Raw::Colon(year, n));
+    }
+    let sep = rest.chars().next().filter(|c| *c == '-' || *c == '/')?;
+    let mut parts = rest[1..].split(sep);
+    let month = parts.next().filter(|m| m.len() == 2).and_then(|m| sub(m, 12))?;
+    match (parts.next(), parts.next()) {
+        (None, _) if sep == '-' => Some(Raw::Month(year, month)),
+        (Some(d), None) if d.len() == 2 => {
+            let day = digits(d).filter(|d| (1..=days_in_month(year, month)).contains(d))?;
+            Some(Raw::Date(days_from_civil(year, month, day)))
+        }
+        _ => None,
+    }
+}
+
+fn digits(s: &str) -> Option<i64> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Monday = 0 … Sunday = 6.
+fn weekday(days: i64) -> i64 {
+    (days + 3).rem_euclid(7)
+}
+
+/// Weekdays since the Monday before 1970-01-01.
+fn business_day(days: i64) -> i64 {
+    (days + 3).div_euclid(7) * 5 + weekday(days).min(4)
+}
@@ -0,0 +1,319 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use super::delimited::read_csv;
+use super::profile::is_missing;
+use super::Table;
+use crate::error::FriedmanError;
+
+/// Rows returned when a query sets no limit.
+pub const DEFAULT_PAGE_ROWS: usize = 100;
+
+/// Largest page served at once.
+pub const MAX_PAGE_ROWS: usize = 5_000;
+
+/// Parsed tables kept in memory before the least recently used is dropped.
+const MAX_CACHED_TABLES: usize = 8;
+
+/// Which rows and columns of a dataset to show.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PreviewQuery {
+    /// First row of the page, counted after filtering and sorting.
+    pub offset: usize,
+    /// Rows in the page, [`DEFAULT_PAGE_ROWS`] if unset, at most
+    /// [`MAX_PAGE_ROWS`].
+    pub limit: Option<usize>,
+    /// Columns to show, in order; all of them if empty.
+    pub columns: Vec<String>,
+    pub sort: Option<SortKey>,
+    pub filter: Option<PreviewFilter>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SortKey {
+    pub column: String,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+/// Quick filter on cell text.
+///
+/// `text` is matched case-insensitively as a substring, unless it starts
+/// with a comparison (`=`, `!=`, `<`, `<=`, `>`, `>=`): then cells are
+/// compared as numbers when both sides are numeric, and as text otherwise,
+/// so `>= 2000-01` works on date columns. Missing cells never match.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreviewFilter {
+    /// Column to search; any of the shown columns if unset.
+    #[serde(default)]
+    pub column: Option<String>,
+    pub text: String,
+}
+
+/// One page of a dataset.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PreviewPage {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    /// Row index (0-based) of each page row in the dataset.
+    pub row_numbers: Vec<usize>,
+    pub offset: usize,
+    /// Rows matching the filter.
+    pub total_rows: usize,
+    /// Rows in the dataset.
+    pub dataset_rows: usize,
+}
+
+/// Serve one page of `table`. Sorting is stable, with missing cells last in
+/// either direction.
+pub fn preview(table: &Table, query: &PreviewQuery) -> Result<PreviewPage, FriedmanError> {
+    let shown = shown_columns(table, query)?;
+    let order = view(table, query, &shown)?;
+    Ok(page(table, query, &shown, &order))
+}
+
+/// Row order of `table` after filtering and sorting.
+fn view(table: &Table, query: &PreviewQuery, shown: &[usize]) -> Result<Vec<usize>, FriedmanError> {
+    let mut order: Vec<usize> = (0..table.rows.len()).collect();
+
+    if let Some(filter) = query.filter.as_ref().filter(|f| !f.text.trim().is_empty()) {
+        let searched = match &filter.column {
+            Some(name) => vec![column_index(table, name)?],
+            None => shown.to_vec(),
+        };
+        let condition = Condition::parse(&filter.text);
+        order.retain(|&r| searched.iter().any(|&c| condition.matches(&table.rows[r][c])));
+    }
+
+    if let Some(sort) = &query.sort {
+        let c = column_index(table, &sort.column)?;
+        let mut keyed: Vec<(SortValue, usize)> =
+            order.iter().map(|&r| (SortValue::of(&table.rows[r][c]), r)).collect();
+        keyed.sort_by(|(a, _), (b, _)| a.compare(b, sort.descending));
+        order = keyed.into_iter().map(|(_, r)| r).collect();
+    }
+    Ok(order)
+}
+
+fn page(table: &Table, query: &PreviewQuery, shown: &[usize], order: &[usize]) -> PreviewPage {
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_ROWS).min(MAX_PAGE_ROWS);
+    let row_numbers: Vec<usize> = order.iter().skip(query.offset).take(limit).copied().collect();
+    PreviewPage {
+        columns: shown.iter().map(|&c| table.columns[c].clone()).collect(),
+        rows: row_numbers
+            .iter()
+            .map(|&r| shown.iter().map(|&c| table.rows[r][c].clone()).collect())
+            .collect(),
+        row_numbers,
+        offset: query.offset,
+        total_rows: order.len(),
+        dataset_rows: table.rows.len(),
+    }
+}
+
+fn shown_columns(table: &Table, query: &PreviewQuery) -> Result<Vec<usize>, FriedmanError> {
+    if query.columns.is_empty() {
+        return Ok((0..table.columns.len()).collect());
+    }
+    query.columns.iter().map(|name| column_index(table, name)).collect()
+}
+
+fn column_index(table: &Table, name: &str) -> Result<usize, FriedmanError> {
+    table
+        .columns
+        .iter()
+        .position(|c| c == name)
+        .ok_or_else(|| FriedmanError::InvalidParams(format!("Unknown column `{name}`")))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Contains,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+struct Condition {
+    comparison: Comparison,
+    /// Lowercased text to compare with.
+    text: String,
+    number: Option<f64>,
+}
+
+impl Condition {
+    fn parse(filter: &str) -> Self {
+        let filter = filter.trim();
+        let (comparison, rest) = [
+            (">=", Comparison::Ge),
+            ("<=", Comparison::Le),
+            ("!=", Comparison::Ne),
+            (">", Comparison::Gt),
+            ("<", Comparison::Lt),
+            ("=", Comparison::Eq),
+        ]
+        .into_iter()
+        .find_map(|(op, comparison)| filter.strip_prefix(op).map(|rest| (comparison, rest.trim())))
+        .unwrap_or((Comparison::Contains, filter));
+        Self {
+            comparison,
+            text: rest.to_lowercase(),
+            number: rest.parse().ok(),
+        }
+    }
+
+    fn matches(&self, cell: &str) -> bool {
+        if is_missing(cell) {
+            return false;
+        }
+        let cell = cell.trim();
+        if self.comparison == Comparison::Contains {
+            return cell.to_lowercase().contains(&self.text);
+        }
+        let ordering = match (self.number, cell.parse::<f64>()) {
+            (Some(number), Ok(value)) => value.partial_cmp(&number),
+            _ => Some(cell.to_lowercase().cmp(&self.text)),
+        };
+        let Some(ordering) = ordering else {
+            return false;
+        };
+        match self.comparison {
+            Comparison::Eq => ordering == Ordering::Equal,
+            Comparison::Ne => ordering != Ordering::Equal,
+            Comparison::Lt => ordering == Ordering::Less,
+            Comparison::Le => ordering != Ordering::Greater,
+            Comparison::Gt => ordering == Ordering::Greater,
+            Comparison::Ge => ordering != Ordering::Less,
+            Comparison::Contains => unreachable!(),
+        }
+    }
+}
+
+/// Sort key of a cell: numbers before text, missing cells last.
+enum SortValue {
+    Number(f64),
+    Text(String),
+    Missing,
+}
+
+impl SortValue {
+    fn of(cell: &str) -> Self {
+        if is_missing(cell) {
+            Self::Missing
+        } else if let Ok(value) = cell.trim().parse::<f64>() {
+            Self::Number(value)
+        } else {
+            Self::Text(cell.to_lowercase())
+        }
+    }
+
+    fn compare(&self, other: &Self, descending: bool) -> Ordering {
+        let ordering = match (self, other) {
+            (Self::Missing, Self::Missing) => return Ordering::Equal,
+            (Self::Missing, _) => return Ordering::Greater,
+            (_, Self::Missing) => return Ordering::Less,
+            (Self::Number(a), Self::Number(b)) => a.total_cmp(b),
+            (Self::Number(_), Self::Text(_)) => Ordering::Less,
+            (Self::Text(_), Self::Number(_)) => Ordering::Greater,
+            (Self::Text(a), Self::Text(b)) => a.cmp(b),
+        };
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Table cache
+// ---------------------------------------------------------------------------
+
+/// Filter and sort a cached row order was built for.
+type ViewKey = (Vec<String>, Option<SortKey>, Option<PreviewFilter>);
+
+struct CachedTable {
+    table: Arc<Table>,
+    /// Row order of the last query, reused while paging through it.
+    view: Option<(ViewKey, Arc<Vec<usize>>)>,
+    seq: u64,
+}
+
+/// Parsed datasets by ID, so pages are served without re-reading the file.
+#[derive(Default)]
+pub struct TableCache {
+    entries: Mutex<HashMap<String, CachedTable>>,
+}
+
+impl TableCache {
+    /// Keep `table` for the dataset `id`, dropping the least recently used
+    /// table beyond the cache size.
+    pub fn insert(&self, id: &str, table: Table) -> Arc<Table> {
+        let table = Arc::new(table);
+        let mut entries = self.entries.lock().expect("table cache lock poisoned");
+        let seq = entries.values().map(|e| e.seq + 1).max().unwrap_or(0);
+        entries.insert(id.to_string(), CachedTable { table: table.clone(), view: None, seq });
+        while entries.len() > MAX_CACHED_TABLES {
+            let Some(oldest) = entries.iter().min_by_key(|(_, e)| e.seq).map(|(k, _)| k.clone()) else {
+                break;
+            };
+            entries.remove(&oldest);
+        }
+        table
+    }
+
+    /// The table of dataset `id`, read from its CSV at `path` if not cached.
+    pub fn get_or_load(&self, id: &str, path: &Path) -> Result<Arc<Table>, FriedmanError> {
+        {
+            let mut entries = self.entries.lock().expect("table cache lock poisoned");
+            let seq = entries.values().map(|e| e.seq + 1).max().unwrap_or(0);
+            if let Some(entry) = entries.get_mut(id) {
+                entry.seq = seq;
+                return Ok(entry.table.clone());
+            }
+        }
+        let (table, _) = read_csv(path)?;
+        Ok(self.insert(id, table))
+    }
+
+    /// Serve a page of dataset `id`, reusing the row order of the previous
+    /// query when only the offset, limit or shown columns changed.
+    pub fn preview(&self, id: &str, path: &Path, query: &PreviewQuery) -> Result<PreviewPage, FriedmanError> {
+        let table = self.get_or_load(id, path)?;
+        let shown = shown_columns(&table, query)?;
+        let searched = if query.filter.as_ref().is_some_and(|f| f.column.is_none()) {
+            query.columns.clone()
+        } else {
+            Vec::new()
+        };
+        let key: ViewKey = (searched, query.sort.clone(), query.filter.clone());
+
+        let cached = self
+            .entries
+            .lock()
+            .expect("table cache lock poisoned")
+            .get(id)
+            .and_then(|e| e.view.as_ref())
+            .filter(|(k, _)| *k == key)
+            .map(|(_, order)| order.clone());
+        let order = match cached {
+            Some(order) => order,
+            None => {
+                let order = Arc::new(view(&table, query, &shown)?);
+                if let Some(entry) = self.entries.lock().expect("table cache lock poisoned").get_mut(id) {
+                    entry.view = Some((key, order.clone()));
+                }
+                order
+            }
+        };
+        Ok(page(&table, query, &shown, &order))
+    }
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+use super::period::parse_period;
+use super::Table;
+
+/// Cells treated as missing, compared case-insensitively after trimming.
+pub const MISSING_TOKENS: &[&str] = &["", "na", "n/a", "nan", ".", "null", "missing", "#n/a"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnKind {
+    Numeric,
+    /// Dates or periods such as `2000Q1`, `2000-01` or `2000-01-31`.
+    Date,
+    Categorical,
+    /// Every cell is missing.
+    Empty,
+}
+
+/// Type and summary statistics of one column, computed when the dataset is
+/// loaded. Statistics other than the counts are only set for numeric
+/// columns.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnProfile {
+    pub name: String,
+    pub kind: ColumnKind,
+    /// Non-missing cells.
+    pub count: usize,
+    pub missing: usize,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub mean: Option<f64>,
+    /// Sample standard deviation.
+    pub std: Option<f64>,
+    /// Row index (0-based) of the first and last non-missing cell.
+    pub first_valid: Option<usize>,
+    pub last_valid: Option<usize>,
+}
+
+impl ColumnProfile {
+    /// Share of missing cells, from 0 to 1.
+    pub fn missing_share(&self) -> f64 {
+        let total = self.count + self.missing;
+        if total == 0 {
+            1.0
+        } else {
+            self.missing as f64 / total as f64
+        }
+    }
+}
+
+pub fn is_missing(cell: &str) -> bool {
+    let cell = cell.trim();
+    MISSING_TOKENS.iter().any(|t| cell.eq_ignore_ascii_case(t))
+}
+
+/// Profile every column of `table`.
+pub fn profile_table(table: &Table) -> Vec<ColumnProfile> {
+    table
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, name)| profile_column(name, table.rows.iter().map(|r| r[i].as_str())))
+        .collect()
+}
+
+pub fn profile_column<'a>(name: &str, cells: impl Iterator<Item = &'a str>) -> ColumnProfile {
+    let mut missing = 0;
+    let mut present: Vec<(usize, &str)> = Vec::new();
+    for (row, cell) in cells.enumerate() {
+        if is_missing(cell) {
+            missing += 1;
+        } else {
+            present.push((row, cell.trim()));
+        }
+    }
+
+    let values: Option<Vec<f64>> = present.iter().map(|(_, c)| c.parse::<f64>().ok()).collect();
+    let kind = match &values {
+        _ if present.is_empty() => ColumnKind::Empty,
+        Some(_) => ColumnKind::Numeric,
+        None if present.iter().all(|(_, c)| parse_period(c).is_some()) => ColumnKind::Date,
+        None => ColumnKind::Categorical,
+    };
+
+    let mut profile = ColumnProfile {
+        name: name.to_string(),
+        kind,
+        count: present.len(),
+        missing,
+        min: None,
+        max: None,
+        mean: None,
+        std: None,
+        first_valid: present.first().map(|(row, _)| *row),
+        last_valid: present.last().map(|(row, _)| *row),
+    };
+    if let (ColumnKind::Numeric, Some(values)) = (kind, values) {
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        profile.min = values.iter().copied().reduce(f64::min);
+        profile.max = values.iter().copied().reduce(f64::max);
+        profile.mean = Some(mean);
+        profile.std = (values.len() > 1)
+            .then(|| (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt());
+    }
+    profile
+}
@@ -0,0 +1,224 @@
+use std::path::Path;
+
+use calamine::{open_workbook_auto, Data, Range, Reader};
+use serde::{Deserialize, Serialize};
+
+use super::{file_name, Table};
+use crate::error::FriedmanError;
+
+/// Zero-based (row, column) of a sheet cell.
+type Cell = (u32, u32);
+
+/// A worksheet and the extent of its used cells.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SheetInfo {
+    pub name: String,
+    pub rows: usize,
+    pub columns: usize,
+    /// Used range in A1 notation, e.g. `A1:E81`; `None` for an empty sheet.
+    pub range: Option<String>,
+}
+
+/// Which part of a workbook to load. Unset fields default to the first
+/// sheet, its used range, and the first non-empty row as the header.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SheetSelection {
+    pub sheet: Option<String>,
+    /// Cell range in A1 notation, `B3:F40`, or a top-left cell, `B3`.
+    pub range: Option<String>,
+    /// Sheet row (1-based) holding the column names.
+    pub header_row: Option<u32>,
+}
+
+/// List the worksheets of an XLSX, XLS, XLSB or ODS file.
+pub fn list_sheets(path: &Path) -> Result<Vec<SheetInfo>, FriedmanError> {
+    let mut workbook = open_workbook_auto(path).map_err(|e| workbook_error(path, e))?;
+    let mut sheets = Vec::new();
+    for name in workbook.sheet_names() {
+        let range = workbook.worksheet_range(&name).map_err(|e| workbook_error(path, e))?;
+        let (rows, columns) = range.get_size();
+        let used = range
+            .start()
+            .zip(range.end())
+            .map(|(start, end)| format!("{}:{}", cell_name(start), cell_name(end)));
+        sheets.push(SheetInfo { name, rows, columns, range: used });
+    }
+    Ok(sheets)
+}
+
+/// Read the selected part of a worksheet as text cells. Returns the table
+/// and the selection with every field resolved.
+///
+/// Numbers are written with `.` decimals and date cells as ISO dates.
+/// Blank rows at the end and columns without a header or values are
+/// dropped.
+pub fn read_sheet(path: &Path, selection: &SheetSelection) -> Result<(Table, SheetSelection), FriedmanError> {
+    let mut workbook = open_workbook_auto(path).map_err(|e| workbook_error(path, e))?;
+    let names = workbook.sheet_names();
+    let sheet = match &selection.sheet {
+        Some(sheet) if names.contains(sheet) => sheet.clone(),
+        Some(sheet) => {
+            return Err(FriedmanError::InvalidParams(format!(
+                "Sheet `{sheet}` not found in {}; sheets are: {}",
+                file_name(&path.display().to_string()),
+                names.join(", ")
+            )))
+        }
+        None => names
+            .first()
+            .cloned()
+            .ok_or_else(|| FriedmanError::InvalidParams("Workbook has no sheets".into()))?,
+    };
+    let used = workbook.worksheet_range(&sheet).map_err(|e| workbook_error(path, e))?;
+    let (Some(used_start), Some(used_end)) = (used.start(), used.end()) else {
+        return Err(FriedmanError::InvalidParams(format!("Sheet `{sheet}` is empty")));
+    };
+
+    let (start, end) = match &selection.range {
+        Some(range) => {
+            let (start, end) = parse_range(range, used_end)?;
+            (start, (end.0.min(used_end.0), end.1.min(used_end.1)))
+        }
+        None => (used_start, used_end),
+    };
+    if start.0 > end.0 || start.1 > end.1 {
+        return Err(FriedmanError::InvalidParams(format!(
+            "Range `{}` is outside the used cells {}:{} of sheet `{sheet}`",
+            selection.range.as_deref().unwrap_or_default(),
+            cell_name(used_start),
+            cell_name(used_end)
+        )));
+    }
+    let cells: Range<Data> = used.range(start, end);
+
+    let header_row = match selection.header_row {
+        Some(row) if row == 0 || row - 1 < start.0 || row - 1 > end.0 => {
+            return Err(FriedmanError::InvalidParams(format!(
+                "Header row {row} is outside the range {}:{}",
+                cell_name(start),
+                cell_name(end)
+            )))
+        }
+        Some(row) => row - 1,
+        None => (start.0..=end.0)
+            .find(|&r| (start.1..=end.1).any(|c| !is_blank(cells.get_value((r, c)))))
+            .ok_or_else(|| FriedmanError::InvalidParams(format!("Sheet `{sheet}` has no values in the range")))?,
+    };
+
+    let text = |r: u32, c: u32| cells.get_value((r, c)).map(cell_text).unwrap_or_default();
+    let header: Vec<String> = (start.1..=end.1).map(|c| text(header_row, c)).collect();
+    let mut rows: Vec<Vec<String>> = (header_row + 1..=end.0)
+        .map(|r| (start.1..=end.1).map(|c| text(r, c)).collect())
+        .collect();
+    while rows.last().is_some_and(|row| row.iter().all(String::is_empty)) {
+        rows.pop();
+    }
+
+    let keep: Vec<usize> = (0..header.len())
+        .filter(|&i| !header[i].is_empty() || rows.iter().any(|row| !row[i].is_empty()))
+        .collect();
+    let table = Table {
+        columns: keep
+            .iter()
+            .map(|&i| match &header[i] {
+                name if name.is_empty() => column_name(start.1 + i as u32),
+                name => name.clone(),
+            })
+            .collect(),
+        rows: rows
+            .into_iter()
+            .map(|row| keep.iter().map(|&i| row[i].clone()).collect())
+            .collect(),
+    };
+    let resolved = SheetSelection {
+        sheet: Some(sheet),
+        range: Some(format!("{}:{}", cell_name(start), cell_name(end))),
+        header_row: Some(header_row + 1),
+    };
+    Ok((table, resolved))
+}
+
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::Int(i) => i.to_string(),
+        Data::Float(f) if f.is_finite() => f.to_string(),
+        Data::String(s) => s.trim().to_string(),
+        Data::Bool(b) => b.to_string(),
+        Data::DateTime(dt) if dt.is_datetime() => {
+            // `2000-03-31 00:00:00` → `2000-03-31`, otherwise ISO 8601
+            let text = dt.as_datetime().map(|d| d.to_string()).unwrap_or_default();
+            match text.strip_suffix(" 00:00:00") {
+                Some(date) => date.to_string(),
+                None => text.replacen(' ', "T", 1),
+            }
+        }
+        Data::DateTime(dt) => dt.as_f64().to_string(),
+        Data::DateTimeIso(s) | Data::DurationIso(s) => s.clone(),
+        Data::Float(_) | Data::Error(_) | Data::Empty => String::new(),
+    }
+}
+
+fn is_blank(cell: Option<&Data>) -> bool {
+    cell.map_or(true, |c| cell_text(c).is_empty())
+}
+
+/// `B3:F40` or `B3` (to `last`, the bottom-right used cell) as zero-based
+/// (row, column) corners.
+fn parse_range(range: &str, last: Cell) -> Result<(Cell, Cell), FriedmanError> {
+    let invalid = || FriedmanError::InvalidParams(format!("Invalid cell range `{range}`, expected e.g. `B3:F40`"));
+    let (first, second) = match range.split_once(':') {
+        Some((a, b)) => (a, Some(b)),
+        None => (range, None),
+    };
+    let start = parse_cell(first.trim()).ok_or_else(invalid)?;
+    let Some(second) = second else {
+        return Ok((start, last));
+    };
+    let end = parse_cell(second.trim()).ok_or_else(invalid)?;
+    if end.0 < start.0 || end.1 < start.1 {
+        return Err(invalid());
+    }
+    Ok((start, end))
+}
+
+/// `B3` → (2, 1).
+fn parse_cell(cell: &str) -> Option<Cell> {
+    let split = cell.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = cell.split_at(split);
+    if letters.is_empty() || !letters.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let column = letters
+        .to_ascii_uppercase()
+        .bytes()
+        .try_fold(0u32, |acc, b| acc.checked_mul(26)?.checked_add(u32::from(b - b'A') + 1))?;
+    let row: u32 = digits.parse().ok()?;
+    (row >= 1).then_some((row - 1, column - 1))
+}
+
+/// (2, 1) → `B3`.
+fn cell_name((row, column): Cell) -> String {
+    format!("{}{}", column_name(column), row + 1)
+}
+
+/// 0 → `A`, 27 → `AB`.
+fn column_name(column: u32) -> String {
+    let mut name = Vec::new();
+    let mut n = column + 1;
+    while n > 0 {
+        name.push(b'A' + ((n - 1) % 26) as u8);
+        n = (n - 1) / 26;
+    }
+    name.reverse();
+    String::from_utf8(name).expect("column letters are ASCII")
+}
+
+fn workbook_error(path: &Path, e: calamine::Error) -> FriedmanError {
+    match e {
+        calamine::Error::Io(e) => FriedmanError::Io(e),
+        e => FriedmanError::InvalidParams(format!(
+            "Cannot read spreadsheet {}: {e}",
+            file_name(&path.display().to_string())
+        )),
+    }
+}
@@ -0,0 +1,349 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use encoding_rs::Encoding;
+
+use super::bytes::Bytes;
+use super::period::{date_time_label, days_from_civil, Frequency, Period};
+use super::{ColumnMeta, Table, ValueLabel};
+use crate::error::FriedmanError;
+
+/// System-missing value of numeric variables.
+const SYSMIS: f64 = -f64::MAX;
+
+const RECORD_VARIABLE: i32 = 2;
+const RECORD_VALUE_LABELS: i32 = 3;
+const RECORD_LABEL_VARIABLES: i32 = 4;
+const RECORD_DOCUMENT: i32 = 6;
+const RECORD_EXTENSION: i32 = 7;
+const RECORD_END: i32 = 999;
+
+const EXT_INTEGER_INFO: i32 = 3;
+const EXT_LONG_NAMES: i32 = 13;
+const EXT_ENCODING: i32 = 20;
+
+/// One 8-byte slot of a case: a numeric variable, or part of a string.
+struct Slot {
+    /// 0 for numeric, the string width for the first slot of a string, -1
+    /// for its continuation slots.
+    width: i32,
+    name: String,
+    label: Option<String>,
+    print_format: u32,
+    missing: MissingValues,
+}
+
+enum MissingValues {
+    None,
+    Values(Vec<[u8; 8]>),
+    /// Range, with an extra discrete value for `-3`.
+    Range(f64, f64, Option<f64>),
+}
+
+/// A value label record and the 1-based slots of the variables it applies to.
+struct LabelSet {
+    values: Vec<[u8; 8]>,
+    labels: Vec<Vec<u8>>,
+    slots: Vec<usize>,
+}
+
+/// Read an SPSS `.sav` system file, uncompressed or bytecode-compressed.
+///
+/// System- and user-missing values become empty cells. Variables with
+/// `DATE`, `ADATE`, `EDATE`, `SDATE`, `JDATE`, `DATETIME`, `QYR`, `MOYR` or
+/// `WKYR` formats are written as dates or period labels; variable and value
+/// labels are returned as column metadata. Strings longer than 255 bytes
+/// are read as their first segment.
+pub fn read_sav(path: &Path) -> Result<(Table, Vec<ColumnMeta>), FriedmanError> {
+    parse_sav(&fs::read(path)?)
+}
+
+pub fn parse_sav(data: &[u8]) -> Result<(Table, Vec<ColumnMeta>), FriedmanError> {
+    let mut r = Bytes::new(data, "SPSS");
+    if r.at(b"$FL3") {
+        return Err(r.invalid("ZLIB-compressed (.zsav) files are not supported".into()));
+    }
+    r.expect("$FL2")?;
+    r.skip(60)?;
+    let layout = r.take(4)?;
+    r.big_endian = !matches!(layout, [2 | 3, 0, 0, 0]);
+    let _nominal_case_size = r.i32()?;
+    let compression = r.i32()?;
+    let _weight_index = r.i32()?;
+    let ncases = r.i32()?;
+    let bias = r.f64()?;
+    r.skip(9 + 8 + 64 + 3)?;
+    if !matches!(compression, 0 | 1) {
+        return Err(r.invalid(format!("compression type {compression} is not supported")));
+    }
+
+    let mut slots: Vec<Slot> = Vec::new();
+    let mut label_sets: Vec<LabelSet> = Vec::new();
+    let mut long_names: HashMap<String, String> = HashMap::new();
+    let mut encoding: Option<&'static Encoding> = None;
+    loop {
+        match r.i32()? {
+            RECORD_VARIABLE => slots.push(variable_record(&mut r)?),
+            RECORD_VALUE_LABELS => {
+                let count = r.u32()? as usize;
+                let mut values = Vec::with_capacity(count);
+                let mut labels = Vec::with_capacity(count);
+                for _ in 0..count {
+                    values.push(raw8(&mut r)?);
+                    let len = usize::from(r.u8()?);
+                    labels.push(r.take(len)?.to_vec());
+                    // value label length byte and text are padded to 8 bytes
+                    r.skip((8 - (len + 1) % 8) % 8)?;
+                }
+                if r.i32()? != RECORD_LABEL_VARIABLES {
+                    return Err(r.invalid("value labels are not followed by their variables".into()));
+                }
+                let n = r.u32()? as usize;
+                let slots = (0..n).map(|_| r.u32().map(|i| i as usize)).collect::<Result<Vec<_>, _>>()?;
+                label_sets.push(LabelSet { values, labels, slots });
+            }
+            RECORD_DOCUMENT => {
+                let lines = r.u32()? as usize;
+                r.skip(lines * 80)?;
+            }
+            RECORD_EXTENSION => {
+                let subtype = r.i32()?;
+                let size = r.u32()? as usize;
+                let count = r.u32()? as usize;
+                let body = r.take(size * count)?;
+                match subtype {
+                    EXT_LONG_NAMES => {
+                        let text = String::from_utf8_lossy(body);
+                        for pair in text.split('\t') {
+                            if let Some((short, long)) = pair.split_once('=') {
+                                long_names.insert(short.trim().to_string(), long.trim_end_matches('\0').to_string());
+                            }
+                        }
+                    }
+                    EXT_ENCODING => encoding = encoding.or(Encoding::for_label(body)),
+                    EXT_INTEGER_INFO if body.len() >= 32 => {
+                        let mut info = Bytes::new(&body[28..32], "SPSS");
+                        info.big_endian = r.big_endian;
+                        if info.i32()? == 65001 {
+                            encoding = encoding.or(Some(encoding_rs::UTF_8));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            RECORD_END => {
+                r.skip(4)?;
+                break;
+            }
+            other => return Err(r.invalid(format!("unknown record type {other} at byte {}", r.pos() - 4))),
+        }
+    }
+    let encoding = encoding.unwrap_or(encoding_rs::WINDOWS_1252);
+    let text = |bytes: &[u8]| encoding.decode_without_bom_handling(bytes).0.trim_end().to_string();
+
+    // Variables and the slots they span
+    let mut vars: Vec<(usize, usize)> = Vec::new();
+    for (i, slot) in slots.iter().enumerate() {
+        if slot.width >= 0 {
+            vars.push((i, 1));
+        } else if let Some(last) = vars.last_mut() {
+            last.1 += 1;
+        }
+    }
+
+    let mut units = Units { compressed: compression == 1, bias, commands: Vec::new() };
+    let mut rows = Vec::new();
+    'cases: while ncases < 0 || rows.len() < ncases as usize {
+        let mut row = Vec::with_capacity(vars.len());
+        for &(first, span) in &vars {
+            let slot = &slots[first];
+            let mut bytes = Vec::with_capacity(span * 8);
+            for _ in 0..span {
+                match units.next(&mut r)? {
+                    Some(unit) => bytes.extend_from_slice(&unit),
+                    None if row.is_empty() && bytes.is_empty() => break 'cases,
+                    None => return Err(r.invalid(format!("case {} is truncated", rows.len() + 1))),
+                }
+            }
+            row.push(if slot.width > 0 {
+                text(&bytes[..(slot.width as usize).min(bytes.len())])
+            } else {
+                number_cell(&bytes, slot, r.big_endian)
+            });
+        }
+        rows.push(row);
+    }
+
+    // Value labels are attached by 1-based slot index
+    let mut value_labels: HashMap<usize, Vec<ValueLabel>> = HashMap::new();
+    for set in label_sets {
+        for slot_index in set.slots {
+            let Some(slot) = slot_index.checked_sub(1).and_then(|i| slots.get(i)) else { continue };
+            let entries = set
+                .values
+                .iter()
+                .zip(&set.labels)
+                .map(|(value, label)| ValueLabel {
+                    value: if slot.width > 0 { text(value) } else { number(value, r.big_endian).to_string() },
+                    label: text(label),
+                })
+                .collect();
+            value_labels.insert(slot_index - 1, entries);
+        }
+    }
+
+    let meta: Vec<ColumnMeta> = vars
+        .iter()
+        .map(|&(first, _)| {
+            let slot = &slots[first];
+            ColumnMeta {
+                name: long_names.get(&slot.name).cloned().unwrap_or_else(|| slot.name.clone()),
+                label: slot.label.clone(),
+                format: Some(format_name(slot.print_format)),
+                value_labels: value_labels.remove(&first).unwrap_or_default(),
+            }
+        })
+        .collect();
+    let columns = meta.iter().map(|m| m.name.clone()).collect();
+    Ok((Table { columns, rows }, meta))
+}
+
+fn variable_record(r: &mut Bytes) -> Result<Slot, FriedmanError> {
+    let width = r.i32()?;
+    let has_label = r.i32()? == 1;
+    let n_missing = r.i32()?;
+    let print_format = r.u32()?;
+    let _write_format = r.u32()?;
+    let name = String::from_utf8_lossy(r.take(8)?).trim_end().to_string();
+    let label = if has_label {
+        let len = r.u32()? as usize;
+        let label = String::from_utf8_lossy(r.take(len)?).trim_end().to_string();
+        r.skip((4 - len % 4) % 4)?;
+        Some(label)
+    } else {
+        None
+    };
+    let values = (0..n_missing.unsigned_abs()).map(|_| raw8(r)).collect::<Result<Vec<_>, _>>()?;
+    let value = |i: usize| number(&values[i], r.big_endian);
+    let missing = match n_missing {
+        0 => MissingValues::None,
+        -2 => MissingValues::Range(value(0), value(1), None),
+        -3 => MissingValues::Range(value(0), value(1), Some(value(2))),
+        1..=3 => MissingValues::Values(values),
+        n => return Err(r.invalid(format!("variable `{name}` has {n} missing values"))),
+    };
+    Ok(Slot { width, name, label, print_format, missing })
+}
+
+fn raw8(r: &mut Bytes) -> Result<[u8; 8], FriedmanError> {
+    let mut out = [0u8; 8];
+    out.copy_from_slice(r.take(8)?);
+    Ok(out)
+}
+
+/// Text of a numeric value: empty when missing, a date or period label for
+/// date formats.
+fn number_cell(bytes: &[u8], slot: &Slot, big_endian: bool) -> String {
+    let value = number(bytes, big_endian);
+    let user_missing = match &slot.missing {
+        MissingValues::None => false,
+        MissingValues::Values(values) => values.iter().any(|v| v[..] == bytes[..8]),
+        MissingValues::Range(low, high, extra) => (*low..=*high).contains(&value) || *extra == Some(value),
+    };
+    if value == SYSMIS || value.is_nan() || user_missing {
+        return String::new();
+    }
+
+    // Dates are seconds since 1582-10-14
+    let days = (value / 86_400.0).floor() as i64 + days_from_civil(1582, 10, 14);
+    let seconds = value.rem_euclid(86_400.0) as i64;
+    match (slot.print_format >> 16) & 0xff {
+        20 | 23 | 24 | 38 | 39 => date_time_label(days, 0),
+        22 => date_time_label(days, seconds),
+        28 => Period::containing(days, Frequency::Weekly).label(),
+        29 => Period::containing(days, Frequency::Quarterly).label(),
+        30 => Period::containing(days, Frequency::Monthly).label(),
+        _ => value.to_string(),
+    }
+}
+
+fn number(bytes: &[u8], big_endian: bool) -> f64 {
+    let mut b = Bytes::new(bytes, "SPSS");
+    b.big_endian = big_endian;
+    b.f64().unwrap_or(SYSMIS)
+}
+
+/// `F8.2`, `A20`, `QYR6`, from a packed print format.
+fn format_name(format: u32) -> String {
+    let (kind, width, decimals) = ((format >> 16) & 0xff, (format >> 8) & 0xff, format & 0xff);
+    let name = match kind {
+        1 => "A",
+        2 => "AHEX",
+        3 => "COMMA",
+        4 => "DOLLAR",
+        5 => "F",
+        17 => "E",
+        20 => "DATE",
+        21 => "TIME",
+        22 => "DATETIME",
+        23 => "ADATE",
+        24 => "JDATE",
+        25 => "DTIME",
+        26 => "WKDAY",
+        27 => "MONTH",
+        28 => "WKYR",
+        29 => "QYR",
+        30 => "MOYR",
+        31 => "PCT",
+        32 => "DOT",
+        38 => "EDATE",
+        39 => "SDATE",
+        _ => "F",
+    };
+    match (name, decimals) {
+        ("F" | "COMMA" | "DOLLAR" | "E" | "PCT" | "DOT", d) if d > 0 => format!("{name}{width}.{d}"),
+        _ => format!("{name}{width}"),
+    }
+}
+
+/// The 8-byte units of the case data, decoding bytecode compression.
+struct Units {
+    compressed: bool,
+    bias: f64,
+    /// Pending command bytes of the current block, in reverse order.
+    commands: Vec<u8>,
+}
+
+impl Units {
+    /// The next unit, or `None` at the end of the data.
+    fn next(&mut self, r: &mut Bytes) -> Result<Option<[u8; 8]>, FriedmanError> {
+        if !self.compressed {
+            return if r.remaining() >= 8 { raw8(r).map(Some) } else { Ok(None) };
+        }
+        loop {
+            let Some(command) = self.commands.pop() else {
+                if r.remaining() < 8 {
+                    return Ok(None);
+                }
+                self.commands = r.take(8)?.iter().rev().copied().collect();
+                continue;
+            };
+            let number = |v: f64| {
+                if r.big_endian {
+                    v.to_be_bytes()
+                } else {
+                    v.to_le_bytes()
+                }
+            };
+            return match command {
+                0 => continue,
+                1..=251 => Ok(Some(number(f64::from(command) - self.bias))),
+                252 => Ok(None),
+                253 => raw8(r).map(Some),
+                254 => Ok(Some(*b"        ")),
+                _ => Ok(Some(number(SYSMIS))),
+            };
+        }
+    }
+}
@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use super::bytes::{decode, Bytes};
+use super::period::{date_time_label, days_from_civil, Frequency, Period};
+use super::{ColumnMeta, Table, ValueLabel};
+use crate::error::FriedmanError;
+
+/// Section order of the `<map>` in format 117 and later.
+const MAP_TYPES: usize = 2;
+const MAP_NAMES: usize = 3;
+const MAP_FORMATS: usize = 5;
+const MAP_VALUE_LABEL_NAMES: usize = 6;
+const MAP_VARIABLE_LABELS: usize = 7;
+const MAP_DATA: usize = 9;
+const MAP_STRLS: usize = 10;
+const MAP_VALUE_LABELS: usize = 11;
+
+/// Storage type of a variable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum VarType {
+    Str(usize),
+    StrL,
+    Byte,
+    Int,
+    Long,
+    Float,
+    Double,
+}
+
+/// Field widths and encodings that differ between format releases.
+struct Layout {
+    release: u16,
+    name_len: usize,
+    format_len: usize,
+    variable_label_len: usize,
+    utf8: bool,
+}
+
+impl Layout {
+    fn new(release: u16) -> Result<Self, FriedmanError> {
+        let (name_len, format_len, variable_label_len) = match release {
+            114..=117 => (33, 49, 81),
+            118 | 119 => (129, 57, 321),
+            _ => {
+                return Err(FriedmanError::InvalidParams(format!(
+                    "Stata format {release} is not supported (versions 114-119 are)"
+                )))
+            }
+        };
+        Ok(Self {
+            release,
+            name_len,
+            format_len,
+            variable_label_len,
+            utf8: release >= 118,
+        })
+    }
+}
+
+/// Read a Stata `.dta` file (format 114 to 119, Stata 10 to 18).
+///
+/// Missing values, including `.a`-`.z`, become empty cells. Variables with
+/// a `%tq`, `%tm`, `%ty`, `%td` or `%tc` format are written as period labels
+/// (`2000Q1`, `2000-01`, `2000`, `2000-01-31`) so they can serve as the time
+/// index; variable and value labels are returned as column metadata.
+pub fn read_dta(path: &Path) -> Result<(Table, Vec<ColumnMeta>), FriedmanError> {
+    parse_dta(&fs::read(path)?)
+}
+
+pub fn parse_dta(data: &[u8]) -> Result<(Table, Vec<ColumnMeta>), FriedmanError> {
+    let mut r = Bytes::new(data, "Stata");
+    if r.at(b"<stata_dta>") {
+        parse_tagged(&mut r)
+    } else {
+        parse_legacy(&mut r)
+    }
+}
+
+/// Formats 114 and 115: fixed-layout header and descriptors.
+fn parse_legacy(r: &mut Bytes) -> Result<(Table, Vec<ColumnMeta>), FriedmanError> {
+    let layout = Layout::new(u16::from(r.u8()?))?;
+    if layout.release > 115 {
+        return Err(r.invalid("format 116 and later files start with `<stata_dta>`".into()));
+    }
+    r.big_endian = r.u8()? == 1;
+    r.skip(2)?;
+    let nvar = usize::from(r.u16()?);
+    let nobs = r.u32()? as usize;
+    r.skip(81 + 18)?;
+
+    let types = (0..nvar)
+        .map(|_| match r.u8()? {
+            n @ 1..=244 => Ok(VarType::Str(usize::from(n))),
+            251 => Ok(VarType::Byte),
+            252 => Ok(VarType::Int),
+            253 => Ok(VarType::Long),
+            254 => Ok(VarType::Float),
+            255 => Ok(VarType::Double),
+            t => Err(r.invalid(format!("unknown variable type {t}"))),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let names = texts(r, nvar, layout.name_len, layout.utf8)?;
+    r.skip(2 * (nvar + 1))?;
+    let formats = texts(r, nvar, layout.format_len, layout.utf8)?;
+    let label_names = texts(r, nvar, layout.name_len, layout.utf8)?;
+    let labels = texts(r, nvar, layout.variable_label_len, layout.utf8)?;
+    loop {
+        let kind = r.u8()?;
+        let len = r.u32()? as usize;
+        if kind == 0 && len == 0 {
+            break;
+        }
+        r.skip(len)?;
+    }
+
+    let rows = read_rows(r, &types, &formats, nobs, &layout, &HashMap::new())?;
+    let mut tables = HashMap::new();
+    while r.remaining() >= 4 {
+        let len = r.u32()? as usize;
+        let name = r.text(layout.name_len, layout.utf8)?;
+        r.skip(3)?;
+        tables.insert(name, value_label_table(r, len, layout.utf8)?);
+    }
+    Ok(assemble(names, formats, labels, label_names, rows, tables))
+}
+
+/// Formats 117 to 119: tagged sections located through the `<map>`.
+fn parse_tagged(r: &mut Bytes) -> Result<(Table, Vec<ColumnMeta>), FriedmanError> {
+    r.expect("<stata_dta><header><release>")?;
+    let release = r.text(3, true)?;
+    let layout = Layout::new(release.parse().map_err(|_| r.invalid(format!("bad release `{release}`")))?)?;
+    r.expect("</release><byteorder>")?;
+    r.big_endian = r.text(3, true)? == "MSF";
+    r.expect("</byteorder><K>")?;
+    let nvar = r.uint(if layout.release == 119 { 4 } else { 2 })? as usize;
+    r.expect("</K><N>")?;
+    let nobs = r.uint(if layout.release == 117 { 4 } else { 8 })? as usize;
+    r.expect("</N><label>")?;
+    let label_len = r.uint(if layout.release == 117 { 1 } else { 2 })? as usize;
+    r.skip(label_len)?;
+    r.expect("</label><timestamp>")?;
+    let stamp_len = usize::from(r.u8()?);
+    r.skip(stamp_len)?;
+    r.expect("</timestamp></header><map>")?;
+    let map = (0..14).map(|_| r.u64().map(|v| v as usize)).collect::<Result<Vec<_>, _>>()?;
+
+    r.seek(map[MAP_TYPES])?;
+    r.expect("<variable_types>")?;
+    let types = (0..nvar)
+        .map(|_| match r.u16()? {
+            n @ 1..=2045 => Ok(VarType::Str(usize::from(n))),
+            32768 => Ok(VarType::StrL),
+            65526 => Ok(VarType::Double),
+            65527 => Ok(VarType::Float),
+            65528 => Ok(VarType::Long),
+            65529 => Ok(VarType::Int),
+            65530 => Ok(VarType::Byte),
+            t => Err(r.invalid(format!("unknown variable type {t}"))),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut section = |index: usize, tag: &str, width: usize| -> Result<Vec<String>, FriedmanError> {
+        r.seek(map[index])?;
+        r.expect(tag)?;
+        texts(r, nvar, width, layout.utf8)
+    };
+    let names = section(MAP_NAMES, "<varnames>", layout.name_len)?;
+    let formats = section(MAP_FORMATS, "<formats>", layout.format_len)?;
+    let label_names = section(MAP_VALUE_LABEL_NAMES, "<value_label_names>", layout.name_len)?;
+    let labels = section(MAP_VARIABLE_LABELS, "<variable_labels>", layout.variable_label_len)?;
+
+    let mut strls = HashMap::new();
+    r.seek(map[MAP_STRLS])?;
+    r.expect("<strls>")?;
+    while r.at(b"GSO") {
+        r.skip(3)?;
+        let v = r.u32()? as u64;
+        let o = r.uint(if layout.release == 117 { 4 } else { 8 })?;
+        let kind = r.u8()?;
+        let len = r.u32()? as usize;
+        let bytes = r.take(len)?;
+        // 130: ASCII/UTF-8 text stored with its terminating NUL; 129: binary
+        let text = if kind == 130 { decode(bytes, layout.utf8) } else { String::new() };
+        strls.insert((v, o), text);
+    }
+
+    r.seek(map[MAP_DATA])?;
+    r.expect("<data>")?;
+    let rows = read_rows(r, &types, &formats, nobs, &layout, &strls)?;
+
+    let mut tables = HashMap::new();
+    r.seek(map[MAP_VALUE_LABELS])?;
+    r.expect("<value_labels>")?;
+    while r.at(b"<lbl>") {
+        r.skip(5)?;
+        let len = r.u32()? as usize;
+        let name = r.text(layout.name_len, layout.utf8)?;
+        r.skip(3)?;
+        tables.insert(name, value_label_table(r, len, layout.utf8)?);
+        r.expect("</lbl>")?;
+    }
+    Ok(assemble(names, formats, labels, label_names, rows, tables))
+}
+
+fn texts(r: &mut Bytes, n: usize, width: usize, utf8: bool) -> Result<Vec<String>, FriedmanError> {
+    (0..n).map(|_| r.text(width, utf8)).collect()
+}
+
+fn read_rows(
+    r: &mut Bytes,
+    types: &[VarType],
+    formats: &[String],
+    nobs: usize,
+    layout: &Layout,
+    strls: &HashMap<(u64, u64), String>,
+) -> Result<Vec<Vec<String>>, FriedmanError> {
+    let times: Vec<Option<TimeFormat>> = formats.iter().map(|f| time_format(f)).collect();
+    // Bytes of the (variable, observation) key of a strL
+    let v_len = match layout.release {
+        117 => 4,
+        118 => 2,
+        _ => 3,
+    };
+    let mut rows = Vec::with_capacity(nobs.min(1 << 20));
+    for _ in 0..nobs {
+        let mut row = Vec::with_capacity(types.len());
+        for (ty, &time) in types.iter().zip(&times) {
+            let text = match *ty {
+                VarType::Str(len) => r.text(len, layout.utf8)?.trim().to_string(),
+                VarType::StrL => {
+                    let v = r.uint(v_len)?;
+                    let o = r.uint(8 - v_len)?;
+                    strls.get(&(v, o)).cloned().unwrap_or_default()
+                }
+                VarType::Byte => number_cell(Some(r.i8()?).filter(|&v| v <= 100).map(f64::from), time, None),
+                VarType::Int => number_cell(Some(r.i16()?).filter(|&v| v <= 32_740).map(f64::from), time, None),
+                VarType::Long => {
+                    number_cell(Some(r.i32()?).filter(|&v| v <= 2_147_483_620).map(f64::from), time, None)
+                }
+                VarType::Float => {
+                    let v = r.f32()?;
+                    let present = v <= f32::from_bits(0x7eff_ffff);
+                    number_cell(present.then_some(f64::from(v)), time, Some(v.to_string()))
+                }
+                VarType::Double => {
+                    number_cell(Some(r.f64()?).filter(|&v| v <= f64::from_bits(0x7fdf_ffff_ffff_ffff)), time, None)
+                }
+            };
+            row.push(text);
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// Empty for a missing value, a period label for a time-formatted value,
+/// otherwise `plain` or the number itself.
+fn number_cell(value: Option<f64>, time: Option<TimeFormat>, plain: Option<String>) -> String {
+    match (value, time) {
+        (None, _) => String::new(),
+        (Some(v), Some(t)) => t.label(v),
+        (Some(v), None) => plain.unwrap_or_else(|| v.to_string()),
+    }
+}
+
+/// A value label table: `n`, text length, offsets, values, then the text.
+fn value_label_table(r: &mut Bytes, len: usize, utf8: bool) -> Result<Vec<ValueLabel>, FriedmanError> {
+    let start = r.pos();
+    let n = r.u32()? as usize;
+    let text_len = r.u32()? as usize;
+    let offsets = (0..n).map(|_| r.u32().map(|o| o as usize)).collect::<Result<Vec<_>, _>>()?;
+    let values = (0..n).map(|_| r.i32()).collect::<Result<Vec<_>, _>>()?;
+    let text = r.take(text_len)?;
+    r.seek(start + len)?;
+    Ok(values
+        .into_iter()
+        .zip(offsets)
+        .map(|(value, offset)| ValueLabel {
+            value: value.to_string(),
+            label: decode(text.get(offset..).unwrap_or_default(), utf8),
+        })
+        .collect())
+}
+
+fn assemble(
+    names: Vec<String>,
+    formats: Vec<String>,
+    labels: Vec<String>,
+    label_names: Vec<String>,
+    rows: Vec<Vec<String>>,
+    tables: HashMap<String, Vec<ValueLabel>>,
+) -> (Table, Vec<ColumnMeta>) {
+    let meta = names
+        .iter()
+        .zip(formats)
+        .zip(labels)
+        .zip(label_names)
+        .map(|(((name, format), label), table)| ColumnMeta {
+            name: name.clone(),
+            label: Some(label).filter(|l| !l.is_empty()),
+            format: Some(format).filter(|f| !f.is_empty()),
+            value_labels: tables.get(&table).cloned().unwrap_or_default(),
+        })
+        .collect();
+    (Table { columns: names, rows }, meta)
+}
+
+/// Stata date and time formats, counted from 1960.
+#[derive(Debug, Clone, Copy)]
+enum TimeFormat {
+    Period(Frequency),
+    /// `%td`: days since 1960-01-01.
+    Day,
+    /// `%tc`: milliseconds since 1960-01-01 00:00.
+    Clock,
+}
+
+fn time_format(format: &str) -> Option<TimeFormat> {
+    let spec = format.strip_prefix('%')?.trim_start_matches('-');
+    if spec.starts_with('d') || spec.starts_with("td") {
+        return Some(TimeFormat::Day);
+    }
+    match spec.get(..2)? {
+        "tq" => Some(TimeFormat::Period(Frequency::Quarterly)),
+        "tm" => Some(TimeFormat::Period(Frequency::Monthly)),
+        "ty" => Some(TimeFormat::Period(Frequency::Annual)),
+        "tc" | "tC" => Some(TimeFormat::Clock),
+        _ => None,
+    }
+}
+
+impl TimeFormat {
+    /// Period label of a stored value; non-integer periods are kept as numbers.
+    fn label(self, value: f64) -> String {
+        if value.fract() != 0.0 && !matches!(self, TimeFormat::Clock) {
+            return value.to_string();
+        }
+        let epoch = days_from_civil(1960, 1, 1);
+        let v = value as i64;
+        match self {
+            TimeFormat::Period(frequency) => {
+                let base = match frequency {
+                    Frequency::Quarterly => 1960 * 4,
+                    Frequency::Monthly => 1960 * 12,
+                    _ => 0,
+                };
+                Period { frequency, ordinal: base + v }.label()
+            }
+            TimeFormat::Day => date_time_label(epoch + v, 0),
+            TimeFormat::Clock => {
+                const DAY_MS: i64 = 86_400_000;
+                date_time_label(epoch + v.div_euclid(DAY_MS), v.rem_euclid(DAY_MS) / 1000)
+            }
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+
+use super::period::Frequency;
+use super::profile::is_missing;
+use super::{ColumnMeta, Table};
+use crate::error::FriedmanError;
+
+/// A column transformation. Rows are taken to be in time order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Transform {
+    /// Natural logarithm; values that are not positive become missing.
+    Log,
+    /// `x[t] - x[t-periods]`.
+    Diff {
+        #[serde(default = "one")]
+        periods: usize,
+    },
+    /// `ln x[t] - ln x[t-periods]`, the continuously compounded growth rate.
+    LogDiff {
+        #[serde(default = "one")]
+        periods: usize,
+    },
+    /// Growth on the previous period compounded to an annual rate, in
+    /// percent: `100 * ((x[t] / x[t-1])^k - 1)` with `k` periods per year.
+    Annualized,
+    /// `x[t-periods]`.
+    Lag {
+        #[serde(default = "one")]
+        periods: usize,
+    },
+    /// `x[t+periods]`.
+    Lead {
+        #[serde(default = "one")]
+        periods: usize,
+    },
+    /// Mean of the `window` values ending at `t`, or centered on `t`
+    /// (for even windows, the extra value is taken before `t`). Missing if
+    /// any value in the window is.
+    MovingAverage {
+        window: usize,
+        #[serde(default)]
+        centered: bool,
+    },
+    /// `(x - mean) / std` over the non-missing values, with the sample
+    /// standard deviation.
+    Standardize,
+}
+
+fn one() -> usize {
+    1
+}
+
+/// A transform of one column into a new one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransformSpec {
+    /// Source column; may be one created by an earlier spec in the list.
+    pub column: String,
+    #[serde(flatten)]
+    pub transform: Transform,
+    /// Name of the new column, e.g. `dlog_gdp` by default.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+impl TransformSpec {
+    pub fn output_name(&self) -> String {
+        if let Some(name) = &self.name {
+            return name.clone();
+        }
+        let column = &self.column;
+        let times = |prefix: &str, periods: usize| {
+            if periods == 1 {
+                format!("{prefix}_{column}")
+            } else {
+                format!("{prefix}{periods}_{column}")
+            }
+        };
+        match self.transform {
+            Transform::Log => format!("log_{column}"),
+            Transform::Diff { periods } => times("d", periods),
+            Transform::LogDiff { periods } => times("dlog", periods),
+            Transform::Annualized => format!("ann_{column}"),
+            Transform::Lag { periods } => format!("{column}_lag{periods}"),
+            Transform::Lead { periods } => format!("{column}_lead{periods}"),
+            Transform::MovingAverage { window, .. } => format!("ma{window}_{column}"),
+            Transform::Standardize => format!("z_{column}"),
+        }
+    }
+}
+
+/// Where a derived dataset came from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lineage {
+    pub parent_id: String,
+    pub parent_name: String,
+    /// The transforms applied to the parent, in order.
+    pub transforms: Vec<TransformSpec>,
+}
+
+/// Labels and formats for the `columns` of a derived dataset: the parent's
+/// for columns it already had, none for new ones.
+pub fn derived_meta(parent: &[ColumnMeta], columns: &[String]) -> Vec<ColumnMeta> {
+    if parent.is_empty() {
+        return Vec::new();
+    }
+    columns
+        .iter()
+        .map(|name| {
+            parent
+                .iter()
+                .find(|meta| &meta.name == name)
+                .cloned()
+                .unwrap_or_else(|| ColumnMeta { name: name.clone(), ..ColumnMeta::default() })
+        })
+        .collect()
+}
+
+/// Apply `specs` in order, each adding a column to a copy of `table`.
+/// `frequency` is the time index's, needed by [`Transform::Annualized`].
+pub fn apply_transforms(
+    table: &Table,
+    specs: &[TransformSpec],
+    frequency: Option<Frequency>,
+) -> Result<Table, FriedmanError> {
+    if specs.is_empty() {
+        return Err(FriedmanError::InvalidParams("No transforms given".into()));
+    }
+    let mut table = table.clone();
+    for spec in specs {
+        let name = spec.output_name();
+        if table.columns.contains(&name) {
+            return Err(FriedmanError::InvalidParams(format!("Column `{name}` already exists")));
+        }
+        let values = numeric_column(&table, &spec.column)?;
+        let output = transform(&values, &spec.transform, frequency)?;
+        table.columns.push(name);
+        for (row, value) in table.rows.iter_mut().zip(output) {
+            row.push(value.filter(|v| v.is_finite()).map(|v| v.to_string()).unwrap_or_default());
+        }
+    }
+    Ok(table)
+}
+
+fn numeric_column(table: &Table, name: &str) -> Result<Vec<Option<f64>>, FriedmanError> {
+    let c = table
+        .columns
+        .iter()
+        .position(|col| col == name)
+        .ok_or_else(|| FriedmanError::InvalidParams(format!("Unknown column `{name}`")))?;
+    table
+        .rows
+        .iter()
+        .map(|row| {
+            let cell = row[c].trim();
+            if is_missing(cell) {
+                return Ok(None);
+            }
+            cell.parse().map(Some).map_err(|_| {
+                FriedmanError::InvalidParams(format!("Column `{name}` is not numeric: found `{cell}`"))
+            })
+        })
+        .collect()
+}
+
+fn transform(
+    x: &[Option<f64>],
+    transform: &Transform,
+    frequency: Option<Frequency>,
+) -> Result<Vec<Option<f64>>, FriedmanError> {
+    let n = x.len();
+    let at = |t: usize, back: usize| t.checked_sub(back).and_then(|s| x[s]);
+    let ln = |v: f64| Some(v).filter(|v| *v > 0.0).map(f64::ln);
+    let positive = |name: &str, value: usize| {
+        if value == 0 {
+            Err(FriedmanError::InvalidParams(format!("`{name}` must be at least 1")))
+        } else {
+            Ok(value)
+        }
+    };
+
+    Ok(match *transform {
+        Transform::Log => x.iter().map(|v| v.and_then(ln)).collect(),
+        Transform::Diff { periods } => {
+            let p = positive("periods", periods)?;
+            (0..n).map(|t| Some(x[t]? - at(t, p)?)).collect()
+        }
+        Transform::LogDiff { periods } => {
+            let p = positive("periods", periods)?;
+            (0..n).map(|t| Some(ln(x[t]?)? - ln(at(t, p)?)?)).collect()
+        }
+        Transform::Annualized => {
+            let k = frequency
+                .ok_or_else(|| {
+                    FriedmanError::InvalidParams(
+                        "Annualized rates need a time index with a known frequency".into(),
+                    )
+                })?
+                .periods_per_year();
+            (0..n)
+                .map(|t| {
+                    let previous = at(t, 1).filter(|v| *v != 0.0)?;
+                    Some(100.0 * ((x[t]? / previous).powf(k) - 1.0))
+                })
+                .collect()
+        }
+        Transform::Lag { periods } => {
+            let p = positive("periods", periods)?;
+            (0..n).map(|t| at(t, p)).collect()
+        }
+        Transform::Lead { periods } => {
+            let p = positive("periods", periods)?;
+            (0..n).map(|t| t.checked_add(p).and_then(|s| x.get(s)).copied().flatten()).collect()
+        }
+        Transform::MovingAverage { window, centered } => {
+            let w = positive("window", window)?;
+            let after = if centered { (w - 1) / 2 } else { 0 };
+            (0..n)
+                .map(|t| {
+                    let end = t + after;
+                    let start = (end + 1).checked_sub(w)?;
+                    let values = x.get(start..=end)?;
+                    values.iter().copied().sum::<Option<f64>>().map(|sum| sum / w as f64)
+                })
+                .collect()
+        }
+        Transform::Standardize => {
+            let present: Vec<f64> = x.iter().flatten().copied().collect();
+            if present.len() < 2 {
+                return Err(FriedmanError::InvalidParams(
+                    "Standardizing needs at least two non-missing values".into(),
+                ));
+            }
+            let mean = present.iter().sum::<f64>() / present.len() as f64;
+            let var = present.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (present.len() - 1) as f64;
+            let std = var.sqrt();
+            x.iter().map(|v| v.map(|v| (v - mean) / std)).collect()
+        }
+    })
+}
@@ -1,10 +1,22 @@
 use serde::Serialize;
 use thiserror::Error;
 
+use crate::sidecar::exception::{self, JuliaException};
+
 #[derive(Debug, Error)]
 pub enum FriedmanError {
     #[error("Sidecar execution failed: {0}")]
     SidecarExec(String),
+    /// No usable Julia executable, or it could not be started.
+    #[error("Sidecar execution failed: {0}")]
+    JuliaNotFound(String),
+    /// No friedman-cli binary or project, or the binary could not be found.
+    #[error("Sidecar execution failed: {0}")]
+    SidecarNotFound(String),
+    /// The sidecar exists but could not be started, e.g. for lack of
+    /// permission.
+    #[error("Sidecar execution failed: {0}")]
+    SpawnFailed(String),
     #[error("Sidecar returned non-zero exit: {stderr}")]
     SidecarExit { code: i32, stderr: String },
     #[error("Failed to parse JSON output: {0}")]
@@ -13,11 +25,247 @@ pub enum FriedmanError {
     Io(#[from] std::io::Error),
     #[error("Invalid parameters: {0}")]
     InvalidParams(String),
+    #[error("Incompatible sidecar: {0}")]
+    IncompatibleSidecar(String),
+    #[error("Job cancelled: {0}")]
+    Cancelled(String),
+    #[error("Sidecar timed out after {seconds}s ({reason}): {stderr}")]
+    Timeout {
+        seconds: u64,
+        reason: String,
+        stderr: String,
+    },
+    /// Any of the above, raised while running the friedman-cli invocation
+    /// `args`.
+    #[error("{source}")]
+    Command {
+        args: Vec<String>,
+        #[source]
+        source: Box<FriedmanError>,
+    },
+}
+
+/// Machine-readable form of a [`FriedmanError`], which is what Tauri
+/// commands return to the frontend.
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorReport {
+    /// The error variant in snake_case, e.g. `sidecar_exit`.
+    pub kind: &'static str,
+    /// Stable classification to branch on, e.g. `julia_not_found` or
+    /// `singular_matrix`.
+    pub code: &'static str,
+    /// One-line summary; for sidecar failures the Julia exception rather
+    /// than the full stderr.
+    pub message: String,
+    pub exit_code: Option<i32>,
+    pub stderr: Option<String>,
+    /// friedman-cli args of the failing invocation, empty if none ran.
+    pub args: Vec<String>,
+    /// What the user can do about it.
+    pub hint: Option<&'static str>,
+    pub exception: Option<JuliaException>,
+}
+
+impl FriedmanError {
+    /// Attach the friedman-cli args that produced this error.
+    pub fn in_command(self, args: &[String]) -> Self {
+        match self {
+            e @ FriedmanError::Command { .. } => e,
+            e => FriedmanError::Command {
+                args: args.to_vec(),
+                source: Box::new(e),
+            },
+        }
+    }
+
+    /// The error without the [`FriedmanError::Command`] wrapper.
+    pub fn inner(&self) -> &FriedmanError {
+        match self {
+            FriedmanError::Command { source, .. } => source.inner(),
+            e => e,
+        }
+    }
+
+    pub fn report(&self) -> ErrorReport {
+        let args = match self {
+            FriedmanError::Command { args, .. } => args.clone(),
+            _ => Vec::new(),
+        };
+        let mut report = ErrorReport {
+            kind: "",
+            code: "",
+            message: String::new(),
+            exit_code: None,
+            stderr: None,
+            args,
+            hint: None,
+            exception: None,
+        };
+
+        match self.inner() {
+            FriedmanError::SidecarExec(_) => {
+                report.kind = "sidecar_exec";
+                report.code = "sidecar_exec";
+                report.message = self.to_string();
+            }
+            FriedmanError::JuliaNotFound(_) => {
+                report.kind = "julia_not_found";
+                report.code = "julia_not_found";
+                report.hint = Some(
+                    "Install Julia (juliaup is recommended) or set its path in the \
+                     sidecar settings; diagnose_sidecar lists every location tried.",
+                );
+                report.message = self.to_string();
+            }
+            FriedmanError::SidecarNotFound(_) => {
+                report.kind = "sidecar_not_found";
+                report.code = "sidecar_not_found";
+                report.hint = Some(
+                    "Configure the sidecar in settings or set FRIEDMAN_CLI_BINARY or \
+                     FRIEDMAN_SIDECAR_PROJECT; diagnose_sidecar lists every location tried.",
+                );
+                report.message = self.to_string();
+            }
+            FriedmanError::SpawnFailed(_) => {
+                report.kind = "spawn_failed";
+                report.code = "spawn_failed";
+                report.hint = Some("Check that the sidecar executable can be run, e.g. its permissions.");
+                report.message = self.to_string();
+            }
+            FriedmanError::SidecarExit { code, stderr } => {
+                let exception = exception::parse(stderr);
+                report.kind = "sidecar_exit";
+                (report.code, report.hint) = classify_exit(exception.as_ref(), stderr);
+                report.message = match &exception {
+                    Some(e) => e.message.clone(),
+                    None => stderr
+                        .lines()
+                        .map(str::trim)
+                        .rfind(|l| !l.is_empty())
+                        .map(String::from)
+                        .unwrap_or_else(|| format!("Sidecar exited with code {code}")),
+                };
+                report.exit_code = Some(*code);
+                report.stderr = Some(stderr.clone());
+                report.exception = exception;
+            }
+            FriedmanError::JsonParse(_) => {
+                report.kind = "json_parse";
+                report.code = "invalid_output";
+                report.hint = Some(
+                    "friedman-cli printed output the app could not read; check that it is \
+                     the expected version with sidecar_info.",
+                );
+                report.message = self.to_string();
+            }
+            FriedmanError::Io(_) => {
+                report.kind = "io";
+                report.code = "io_error";
+                report.message = self.to_string();
+            }
+            FriedmanError::InvalidParams(_) => {
+                report.kind = "invalid_params";
+                report.code = "invalid_params";
+                report.message = self.to_string();
+            }
+            FriedmanError::IncompatibleSidecar(_) => {
+                report.kind = "incompatible_sidecar";
+                report.code = "incompatible_sidecar";
+                report.hint = Some(
+                    "Install the friedman-cli release the app expects; sidecar_info lists \
+                     what was found.",
+                );
+                report.message = self.to_string();
+            }
+            FriedmanError::Cancelled(_) => {
+                report.kind = "cancelled";
+                report.code = "cancelled";
+                report.message = self.to_string();
+            }
+            FriedmanError::Timeout {
+                seconds,
+                reason,
+                stderr,
+            } => {
+                report.kind = "timeout";
+                report.code = "timeout";
+                report.hint = Some(
+                    "Raise the limit with set_timeouts or reduce the workload, e.g. fewer \
+                     draws or bootstrap replications.",
+                );
+                report.message = format!("Sidecar timed out after {seconds}s ({reason})");
+                report.exception = exception::parse(stderr);
+                report.stderr = Some(stderr.clone()).filter(|s| !s.is_empty());
+            }
+            FriedmanError::Command { .. } => unreachable!("inner() unwraps Command"),
+        }
+        report
+    }
+}
+
+/// Code and hint for a non-zero exit, from the Julia exception when one was
+/// printed.
+fn classify_exit(exception: Option<&JuliaException>, stderr: &str) -> (&'static str, Option<&'static str>) {
+    let Some(e) = exception else {
+        let lower = stderr.to_ascii_lowercase();
+        return if lower.contains("unknown option") || lower.contains("unrecognized option") {
+            (
+                "unknown_option",
+                Some("friedman-cli does not accept an option the app passed; check its version with sidecar_info."),
+            )
+        } else {
+            ("sidecar_exit", None)
+        };
+    };
+
+    if e.message.contains("Package") && e.message.contains("not found") {
+        return (
+            "sidecar_not_installed",
+            Some("The sidecar's Julia dependencies are missing; run ./scripts/setup-sidecar.sh."),
+        );
+    }
+    match e.short_type() {
+        "SingularException" => (
+            "singular_matrix",
+            Some(
+                "A matrix in the estimation is singular. Check for constant or perfectly \
+                 collinear columns, or reduce the number of lags.",
+            ),
+        ),
+        "PosDefException" => (
+            "not_positive_definite",
+            Some(
+                "A covariance matrix is not positive definite. Try fewer variables or lags, \
+                 or a longer sample.",
+            ),
+        ),
+        "DimensionMismatch" | "BoundsError" => (
+            "dimension_mismatch",
+            Some(
+                "The data does not have the shape the model needs; check the selected \
+                 columns and that the sample is long enough.",
+            ),
+        ),
+        "DomainError" => (
+            "domain_error",
+            Some("A value is outside its valid domain, e.g. the log of a non-positive number."),
+        ),
+        "ArgumentError" => (
+            "invalid_argument",
+            Some("friedman-cli rejected a parameter value; check the model options."),
+        ),
+        "MethodError" => (
+            "method_error",
+            Some("friedman-cli may not be the version the app expects; check sidecar_info."),
+        ),
+        "SystemError" | "IOError" => ("file_error", None),
+        _ => ("julia_exception", None),
+    }
 }
 
 // Tauri commands require Serialize on errors
 impl Serialize for FriedmanError {
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        serializer.serialize_str(&self.to_string())
+        self.report().serialize(serializer)
     }
 }
@@ -1,8 +1,13 @@
-mod commands;
-mod error;
-mod sidecar;
-mod state;
+pub mod commands;
+pub mod dataset;
+pub mod error;
+pub mod settings;
+pub mod sidecar;
+pub mod state;
 
+use tauri::Manager;
+
+use settings::Settings;
 use state::AppState;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -17,10 +22,26 @@ pub fn run() {
                 .build(),
         )
         .manage(AppState::default())
+        .setup(|app| {
+            let settings = Settings::load(app.handle());
+            *app.state::<AppState>().settings.lock().expect("settings lock poisoned") = settings;
+            if app.state::<AppState>().runner.spawns_sidecar() {
+                sidecar::session::warm_up(app.handle().clone());
+                sidecar::capabilities::check_in_background(app.handle().clone());
+            }
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             // data
             commands::data::load_csv,
             commands::data::load_xlsx,
+            commands::data::list_sheets,
+            commands::data::load_parquet,
+            commands::data::load_arrow,
+            commands::data::load_stata,
+            commands::data::load_spss,
+            commands::data::export_dataset,
+            commands::data::transform_dataset,
             commands::data::get_dataset,
             commands::data::list_datasets,
             commands::data::preview_data,
@@ -66,7 +87,29 @@ pub fn run() {
             commands::nongaussian::nongaussian_heteroskedasticity,
             commands::nongaussian::nongaussian_normality,
             commands::nongaussian::nongaussian_identifiability,
+            // jobs
+            commands::jobs::list_jobs,
+            commands::jobs::cancel_job,
+            commands::jobs::get_max_concurrency,
+            commands::jobs::set_max_concurrency,
+            commands::jobs::get_timeouts,
+            commands::jobs::set_timeouts,
+            // sidecar
+            commands::sidecar::sidecar_info,
+            commands::sidecar::diagnose_sidecar,
+            commands::sidecar::get_sidecar_settings,
+            commands::sidecar::set_sidecar_settings,
+            commands::sidecar::get_resource_settings,
+            commands::sidecar::set_resource_settings,
+            // results
+            commands::results::read_result_page,
+            commands::results::release_result,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running Friedman");
+        .build(tauri::generate_context!())
+        .expect("error while running Friedman")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                app.state::<AppState>().spills.clear();
+            }
+        });
 }
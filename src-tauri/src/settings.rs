@@ -0,0 +1,106 @@
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::error::FriedmanError;
+
+const SETTINGS_FILE: &str = "settings.json";
+
+/// User preferences persisted as `settings.json` in the app config directory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub sidecar: SidecarSettings,
+    pub resources: ResourceSettings,
+}
+
+/// Explicit locations for the sidecar, tried before the built-in search.
+///
+/// Each entry can be overridden for a single run with an environment
+/// variable: `FRIEDMAN_JULIA`, `FRIEDMAN_SIDECAR_PROJECT` and
+/// `FRIEDMAN_CLI_BINARY`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SidecarSettings {
+    /// Julia executable used in dev (project) mode.
+    pub julia: Option<PathBuf>,
+    /// Julia project containing `main.jl` (normally `src-tauri/sidecar`).
+    pub project_dir: Option<PathBuf>,
+    /// Compiled friedman-cli binary; takes precedence over a project.
+    pub binary: Option<PathBuf>,
+}
+
+/// Limits applied to every sidecar process, so a long estimation does not
+/// take over a shared machine. `None` leaves the inherited value alone.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResourceSettings {
+    /// Julia threads (`JULIA_NUM_THREADS`, equivalent to `--threads`); also
+    /// caps the BLAS thread pool.
+    pub threads: Option<u32>,
+    /// Nice level from 0 (normal) to 19 (lowest priority). Unix only.
+    pub nice: Option<i32>,
+    /// Address-space ceiling in MiB (`RLIMIT_AS`). Linux only; Julia needs
+    /// a few GiB of address space just to start.
+    pub memory_limit_mb: Option<u64>,
+    /// Parent of the per-job scratch directories; defaults to the system
+    /// temporary directory.
+    pub scratch_dir: Option<PathBuf>,
+    /// Keep each job's scratch directory after it finishes, for debugging.
+    pub keep_scratch: bool,
+}
+
+impl ResourceSettings {
+    pub fn validate(&self) -> Result<(), FriedmanError> {
+        if self.threads == Some(0) {
+            return Err(FriedmanError::InvalidParams("threads must be at least 1".into()));
+        }
+        if self.nice.is_some_and(|n| !(0..=19).contains(&n)) {
+            return Err(FriedmanError::InvalidParams(
+                "nice must be between 0 and 19".into(),
+            ));
+        }
+        if self.memory_limit_mb == Some(0) {
+            return Err(FriedmanError::InvalidParams(
+                "memory_limit_mb must be positive".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Settings {
+    /// Read the settings file, falling back to defaults if it is missing or
+    /// unreadable.
+    pub fn load(app: &AppHandle) -> Self {
+        let Some(path) = settings_path(app) else {
+            return Self::default();
+        };
+        match fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                log::warn!("Ignoring malformed {}: {e}", path.display());
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self, app: &AppHandle) -> Result<(), FriedmanError> {
+        let path = settings_path(app).ok_or_else(|| {
+            FriedmanError::SidecarExec("app config directory is unavailable".into())
+        })?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let text = serde_json::to_string_pretty(self)
+            .map_err(|e| FriedmanError::JsonParse(e.to_string()))?;
+        fs::write(path, text)?;
+        Ok(())
+    }
+}
+
+fn settings_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|d| d.join(SETTINGS_FILE))
+}
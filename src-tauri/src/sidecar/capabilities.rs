@@ -0,0 +1,339 @@
+use std::collections::BTreeMap;
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::jobs::JobHandle;
+use super::scheduler::Priority;
+use super::watchdog::Limits;
+use super::{execute, job_id_or_new, resolve_sidecar, OutputSink, SidecarMode};
+use crate::error::FriedmanError;
+use crate::state::AppState;
+
+/// friedman-cli release the `commands::*` modules were written against.
+pub const EXPECTED_CLI_VERSION: &str = "0.1.3";
+
+/// Subcommands invoked by the `commands::*` modules, by command group.
+const EXPECTED_COMMANDS: &[(&str, &[&str])] = &[
+    ("var", &["estimate", "lagselect", "stability", "irf", "fevd", "hd", "forecast"]),
+    ("bvar", &["estimate", "posterior", "irf", "fevd", "hd", "forecast"]),
+    ("lp", &["estimate", "irf", "fevd", "hd", "forecast"]),
+    ("factor", &["estimate", "forecast"]),
+    ("test", &["adf", "kpss", "pp", "za", "np", "johansen"]),
+    ("gmm", &["estimate"]),
+    ("arima", &["estimate", "forecast"]),
+    (
+        "nongaussian",
+        &["fastica", "ml", "heteroskedasticity", "normality", "identifiability"],
+    ),
+];
+
+/// Upper bound for one `--version` / `--help` probe (Julia start-up included).
+const PROBE_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// What the resolved friedman-cli reports about itself, as returned by
+/// `sidecar_info`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SidecarInfo {
+    pub mode: SidecarMode,
+    pub cli_version: Option<String>,
+    pub julia_version: Option<String>,
+    pub expected_cli_version: String,
+    /// Top-level command groups listed by `friedman --help`.
+    pub groups: Vec<String>,
+    /// Whether `groups` looks like the CLI's command list, i.e. has most of
+    /// the groups the app uses. Missing groups are only refused if so.
+    pub groups_parsed: bool,
+    /// Subcommands and flags listed by `friedman <group> --help`, for every
+    /// group the app uses.
+    pub commands: BTreeMap<String, CommandGroup>,
+    /// `false` when any entry in `issues` was found.
+    pub compatible: bool,
+    pub issues: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CommandGroup {
+    pub subcommands: Vec<String>,
+    pub flags: Vec<String>,
+}
+
+impl SidecarInfo {
+    /// Refuse an invocation the CLI is known not to support.
+    ///
+    /// Only help pages that could be parsed are checked, so a CLI with
+    /// unexpected help formatting degrades to a warning instead of blocking
+    /// commands.
+    pub fn check(&self, args: &[String]) -> Result<(), FriedmanError> {
+        let (Some(group), Some(sub)) = (args.first(), args.get(1)) else {
+            return Ok(());
+        };
+        if !self.groups.is_empty() && !self.groups.contains(group) {
+            if self.groups_parsed {
+                return Err(self.incompatible(format!("command group `{group}` is not available")));
+            }
+            log::warn!("`{group}` is not in the unrecognised `friedman --help` command list; running anyway");
+        }
+        match self.commands.get(group) {
+            Some(g) if !g.subcommands.is_empty() && !g.subcommands.contains(sub) => Err(
+                self.incompatible(format!("`{group} {sub}` is not available")),
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    fn incompatible(&self, reason: String) -> FriedmanError {
+        let found = self.cli_version.as_deref().unwrap_or("unknown version");
+        FriedmanError::IncompatibleSidecar(format!(
+            "{reason} in friedman-cli {found} (the app expects {EXPECTED_CLI_VERSION})"
+        ))
+    }
+}
+
+/// Probe the sidecar in the background (at startup and after the sidecar
+/// settings change), cache the result and log any incompatibility. The
+/// result is also emitted as `friedman://sidecar-info`.
+pub fn check_in_background(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        match refresh(&app).await {
+            Ok(info) => {
+                for issue in &info.issues {
+                    log::warn!("friedman-cli compatibility: {issue}");
+                }
+                let _ = app.emit("friedman://sidecar-info", &info);
+            }
+            Err(e) => log::warn!("Could not probe friedman-cli: {e}"),
+        }
+    });
+}
+
+/// The cached [`SidecarInfo`], probing the sidecar if there is none yet.
+pub async fn info(app: &AppHandle, force: bool) -> Result<SidecarInfo, FriedmanError> {
+    if !force {
+        let state = app.state::<AppState>();
+        let cached = state.sidecar_info.lock().expect("sidecar info lock poisoned").clone();
+        if let Some(info) = cached {
+            return Ok(info);
+        }
+    }
+    refresh(app).await
+}
+
+async fn refresh(app: &AppHandle) -> Result<SidecarInfo, FriedmanError> {
+    let mode = resolve_sidecar(app)?;
+    let info = probe(app, mode).await?;
+    let state = app.state::<AppState>();
+    *state.sidecar_info.lock().expect("sidecar info lock poisoned") = Some(info.clone());
+    Ok(info)
+}
+
+/// Run the probes one after another as a single low-priority job, so they
+/// share the Julia session and respect the concurrency limit.
+async fn probe(app: &AppHandle, mode: SidecarMode) -> Result<SidecarInfo, FriedmanError> {
+    let state = app.state::<AppState>();
+    let job_id = job_id_or_new(None);
+    let mut job = state.jobs.register(&job_id, &["--help".to_string()], Priority::Low)?;
+    let result = async {
+        let _slot = state.scheduler.acquire(app, &mut job, Priority::Low).await?;
+        state.jobs.set_running(&job_id);
+        probe_help(app, &mode, &mut job).await
+    }
+    .await;
+    state.jobs.finish(&job_id, &result);
+    let (version_text, groups, commands, mut issues) = result?;
+    let expected: Vec<&str> = EXPECTED_COMMANDS.iter().map(|(group, _)| *group).collect();
+    let groups_parsed = is_command_list(&groups, &expected);
+    if !groups.is_empty() && !groups_parsed {
+        log::warn!("Unrecognised command list in `friedman --help`; command groups are not checked");
+    }
+
+    let cli_version = version_text.as_deref().and_then(find_version);
+    let julia_version = match &mode {
+        SidecarMode::Julia { julia, .. } => {
+            let mut cmd = tokio::process::Command::new(julia);
+            cmd.arg("--version");
+            run_for_text(cmd).await.ok().as_deref().and_then(find_version)
+        }
+        // PackageCompiler binaries mention the runtime in `--version`, if at all.
+        SidecarMode::Binary(_) => version_text.as_deref().and_then(|text| {
+            let at = text.to_ascii_lowercase().find("julia")?;
+            find_version(&text[at..])
+        }),
+    };
+
+    match &cli_version {
+        Some(found) if !same_minor(found, EXPECTED_CLI_VERSION) => issues.push(format!(
+            "friedman-cli {found} differs from the expected {EXPECTED_CLI_VERSION}"
+        )),
+        Some(_) => {}
+        None => issues.push("could not determine the friedman-cli version".into()),
+    }
+    for (group, subs) in EXPECTED_COMMANDS {
+        if groups_parsed && !groups.iter().any(|g| g == group) {
+            issues.push(format!("command group `{group}` is not available"));
+            continue;
+        }
+        let Some(found) = commands.get(*group).filter(|g| !g.subcommands.is_empty()) else {
+            continue;
+        };
+        for sub in *subs {
+            if !found.subcommands.iter().any(|s| s == sub) {
+                issues.push(format!("`{group} {sub}` is not available"));
+            }
+        }
+    }
+
+    Ok(SidecarInfo {
+        mode,
+        cli_version,
+        julia_version,
+        expected_cli_version: EXPECTED_CLI_VERSION.into(),
+        groups,
+        groups_parsed,
+        commands,
+        compatible: issues.is_empty(),
+        issues,
+    })
+}
+
+type HelpTexts = (Option<String>, Vec<String>, BTreeMap<String, CommandGroup>, Vec<String>);
+
+/// `--version`, the top-level help and the help of every expected group:
+/// the version text, the groups, their commands and any failed probes.
+async fn probe_help(app: &AppHandle, mode: &SidecarMode, job: &mut JobHandle) -> Result<HelpTexts, FriedmanError> {
+    let probes = [(None, vec!["--version"]), (Some(""), vec!["--help"])]
+        .into_iter()
+        .chain(EXPECTED_COMMANDS.iter().map(|(group, _)| (Some(*group), vec![*group, "--help"])));
+
+    let mut issues = Vec::new();
+    let mut version_text = None;
+    let mut groups = Vec::new();
+    let mut commands = BTreeMap::new();
+    for (key, args) in probes {
+        let text = match capture(app, mode, job, &args).await {
+            Ok(text) => text,
+            Err(
+                e @ (FriedmanError::JuliaNotFound(_)
+                | FriedmanError::SidecarNotFound(_)
+                | FriedmanError::SpawnFailed(_)),
+            ) if key.is_none() => {
+                // Nothing can run if the sidecar cannot even be spawned.
+                return Err(e);
+            }
+            Err(e @ FriedmanError::Cancelled(_)) => return Err(e),
+            Err(e) => {
+                issues.push(format!("probe failed: {e}"));
+                continue;
+            }
+        };
+        match key {
+            None => version_text = Some(text),
+            Some("") => groups = parse_help(&text).subcommands,
+            Some(group) => {
+                commands.insert(group.to_string(), parse_help(&text));
+            }
+        }
+    }
+    Ok((version_text, groups, commands, issues))
+}
+
+/// Run a short sidecar invocation, through the session when there is one,
+/// and return stdout followed by stderr.
+async fn capture(
+    app: &AppHandle,
+    mode: &SidecarMode,
+    job: &mut JobHandle,
+    args: &[&str],
+) -> Result<String, FriedmanError> {
+    let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+    let sink = OutputSink::new(app, &job.id, false);
+    let limits = Limits { total: Some(PROBE_TIMEOUT), idle: None };
+    let output = execute(app, mode, job, &sink, limits, &args).await?;
+    let mut text = match output.stdout_file {
+        Some(path) => {
+            let text = std::fs::read_to_string(&path).unwrap_or_default();
+            let _ = std::fs::remove_file(path);
+            text
+        }
+        None => output.stdout,
+    };
+    text.push('\n');
+    text.push_str(&output.stderr);
+    Ok(text)
+}
+
+async fn run_for_text(mut cmd: tokio::process::Command) -> Result<String, FriedmanError> {
+    let child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+    let output = tokio::time::timeout(PROBE_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| FriedmanError::Timeout {
+            seconds: PROBE_TIMEOUT.as_secs(),
+            reason: "capability probe".into(),
+            stderr: String::new(),
+        })??;
+    let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+    text.push('\n');
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    Ok(text)
+}
+
+/// Subcommands are indented lines starting with a bare lowercase word that
+/// is followed by a column gap (or nothing); flags are any `--long` tokens.
+fn parse_help(text: &str) -> CommandGroup {
+    let mut group = CommandGroup::default();
+    for line in text.lines() {
+        if line.starts_with([' ', '\t']) {
+            let trimmed = line.trim_start();
+            let word_end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+            let word = trimmed[..word_end].trim_end_matches([':', ',']);
+            let rest = &trimmed[word_end..];
+            let is_name = word.starts_with(|c: char| c.is_ascii_lowercase())
+                && word.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+            let is_column = rest.is_empty() || rest.starts_with("  ") || rest.starts_with('\t');
+            if is_name && is_column && !group.subcommands.iter().any(|s| s == word) {
+                group.subcommands.push(word.to_string());
+            }
+        }
+
+        for token in line.split(|c: char| c.is_whitespace() || matches!(c, ',' | '[' | ']' | '=' | '|')) {
+            let Some(name) = token.strip_prefix("--") else { continue };
+            let valid = name.starts_with(|c: char| c.is_ascii_alphabetic())
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+            if valid && !group.flags.iter().any(|f| f == token) {
+                group.flags.push(token.to_string());
+            }
+        }
+    }
+    group.flags.sort();
+    group
+}
+
+/// Whether `found`, scraped from help output, has at least half of the
+/// `expected` names; anything less is more likely a misparse than a CLI
+/// without those commands.
+fn is_command_list(found: &[String], expected: &[&str]) -> bool {
+    let hits = expected.iter().filter(|name| found.iter().any(|f| f == *name)).count();
+    !found.is_empty() && hits * 2 >= expected.len()
+}
+
+/// First `X.Y[.Z]` token in `text`, e.g. `0.1.3` from `friedman v0.1.3`.
+fn find_version(text: &str) -> Option<String> {
+    text.split(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .map(|s| s.trim_matches('.'))
+        .find(|s| s.split('.').count() >= 2 && s.split('.').all(|p| !p.is_empty()))
+        .map(String::from)
+}
+
+/// Same major and minor version; for `0.x` releases the minor is the
+/// breaking component.
+fn same_minor(found: &str, expected: &str) -> bool {
+    let key = |v: &str| v.split('.').take(2).map(String::from).collect::<Vec<_>>();
+    key(found) == key(expected)
+}
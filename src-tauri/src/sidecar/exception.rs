@@ -0,0 +1,136 @@
+use serde::Serialize;
+
+/// A Julia exception recovered from sidecar stderr.
+///
+/// Handles both the uncaught form printed by a spawned CLI
+/// (`ERROR: LoadError: ArgumentError: ...`) and the bare
+/// `showerror(stderr, e, catch_backtrace())` form written by the session.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct JuliaException {
+    /// Exception type as printed, e.g. `ArgumentError` or
+    /// `LinearAlgebra.SingularException`. `ErrorException` for `error("...")`,
+    /// which Julia prints without a type.
+    #[serde(rename = "type")]
+    pub exception_type: String,
+    /// The exception as Julia displays it, without the `ERROR:` prefix.
+    pub message: String,
+    /// Innermost stack frame, if a stacktrace was printed.
+    pub frame: Option<StackFrame>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StackFrame {
+    /// Function signature, e.g. `checknonsingular(info::Int64)`.
+    pub function: String,
+    /// `[Module] file:line`, when Julia printed one.
+    pub location: Option<String>,
+}
+
+impl JuliaException {
+    /// Type name without its module, e.g. `SingularException`.
+    pub fn short_type(&self) -> &str {
+        self.exception_type
+            .rsplit('.')
+            .next()
+            .unwrap_or(&self.exception_type)
+    }
+}
+
+/// Find the last Julia exception in `stderr`.
+///
+/// Returns `None` unless there is an `ERROR:` line or a `Stacktrace:`
+/// header, so ordinary log output is never mistaken for an exception.
+pub fn parse(stderr: &str) -> Option<JuliaException> {
+    let lines: Vec<&str> = stderr.lines().collect();
+    let trace = lines.iter().rposition(|l| l.trim() == "Stacktrace:");
+    let end = trace.unwrap_or(lines.len());
+
+    let start = match lines[..end].iter().rposition(|l| l.starts_with("ERROR: ")) {
+        Some(i) => i,
+        None => {
+            // showerror output: the block of lines right above the stacktrace.
+            let trace = trace?;
+            let mut i = trace;
+            while i > 0 && is_message_line(lines[i - 1]) {
+                i -= 1;
+            }
+            if i == trace {
+                return None;
+            }
+            i
+        }
+    };
+
+    let mut header: Vec<&str> = lines[start..end]
+        .iter()
+        .map(|l| l.trim_end())
+        .take_while(|l| !l.is_empty() && !l.starts_with("in expression starting at"))
+        .collect();
+    if header.is_empty() {
+        return None;
+    }
+    let mut first = header[0].strip_prefix("ERROR: ").unwrap_or(header[0]);
+    while let Some(rest) = first.strip_prefix("LoadError: ") {
+        first = rest;
+    }
+    header[0] = first;
+    let message = header.join("\n").trim().to_string();
+
+    Some(JuliaException {
+        exception_type: exception_type(first).unwrap_or("ErrorException").to_string(),
+        message,
+        frame: trace.and_then(|t| top_frame(&lines[t + 1..])),
+    })
+}
+
+/// Lines that can belong to a printed exception, as opposed to logging
+/// (`[ Info:`, `┌ Warning:`) or NDJSON frames.
+fn is_message_line(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    !trimmed.is_empty()
+        && !trimmed.starts_with('{')
+        && !trimmed.starts_with("[ ")
+        && !trimmed.starts_with(['┌', '│', '└'])
+}
+
+/// `ArgumentError` from `ArgumentError: msg`, `SingularException` from
+/// `SingularException(3)`; `None` for a bare `error("msg")` message.
+fn exception_type(line: &str) -> Option<&str> {
+    let end = line
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .unwrap_or(line.len());
+    let name = &line[..end];
+    let is_type = name
+        .rsplit('.')
+        .next()
+        .is_some_and(|last| last.starts_with(|c: char| c.is_ascii_uppercase()));
+    let rest = &line[end..];
+    (is_type && (rest.starts_with(": ") || rest.starts_with('(') || rest.is_empty())).then_some(name)
+}
+
+/// `[1] f(x::Int64)` followed by `@ Module file.jl:10` (Julia ≥ 1.6), or
+/// `[1] f(x::Int64) at file.jl:10` (older releases).
+fn top_frame(lines: &[&str]) -> Option<StackFrame> {
+    let (i, first) = lines
+        .iter()
+        .enumerate()
+        .find_map(|(i, l)| l.trim_start().strip_prefix("[1] ").map(|f| (i, f.trim())))?;
+
+    if let Some(next) = lines.get(i + 1).and_then(|l| l.trim_start().strip_prefix("@ ")) {
+        let location = next.trim().trim_end_matches("[inlined]").trim_end();
+        return Some(StackFrame {
+            function: first.to_string(),
+            location: Some(location.to_string()),
+        });
+    }
+    match first.rsplit_once(" at ") {
+        Some((function, location)) => Some(StackFrame {
+            function: function.to_string(),
+            location: Some(location.to_string()),
+        }),
+        None => Some(StackFrame {
+            function: first.to_string(),
+            location: None,
+        }),
+    }
+}
@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tokio::sync::watch;
+
+use super::resources::JobResources;
+use super::scheduler::Priority;
+use crate::error::FriedmanError;
+
+/// Finished jobs kept around for `list_jobs` before the oldest are dropped.
+const MAX_FINISHED_JOBS: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// Public view of a sidecar invocation, as returned by `list_jobs`.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobInfo {
+    pub id: String,
+    /// Command group and subcommand, e.g. `bvar estimate`.
+    pub command: String,
+    pub args: Vec<String>,
+    pub status: JobStatus,
+    pub priority: Priority,
+    /// OS process id serving the job (the shared session's pid in dev mode).
+    pub pid: Option<u32>,
+    /// Thread count, nice level, memory ceiling and scratch directory in
+    /// effect, once the job has started.
+    pub resources: Option<JobResources>,
+    /// Unix timestamps in milliseconds.
+    pub started_at: u64,
+    pub finished_at: Option<u64>,
+}
+
+impl JobInfo {
+    fn is_active(&self) -> bool {
+        matches!(self.status, JobStatus::Queued | JobStatus::Running)
+    }
+}
+
+struct JobEntry {
+    info: JobInfo,
+    cancel: watch::Sender<bool>,
+}
+
+/// Tracks every sidecar invocation by job id so it can be listed and cancelled.
+#[derive(Default)]
+pub struct JobRegistry {
+    jobs: Mutex<HashMap<String, JobEntry>>,
+}
+
+/// Held by the code running a job; resolves [`JobHandle::cancelled`] when
+/// `cancel_job` is called for it.
+pub struct JobHandle {
+    pub id: String,
+    cancel: watch::Receiver<bool>,
+}
+
+impl JobHandle {
+    /// Completes once the job has been cancelled. Never completes otherwise.
+    pub async fn cancelled(&mut self) {
+        if self.cancel.wait_for(|c| *c).await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
+impl JobRegistry {
+    /// Register a job in the [`JobStatus::Queued`] state.
+    pub fn register(
+        &self,
+        id: &str,
+        args: &[String],
+        priority: Priority,
+    ) -> Result<JobHandle, FriedmanError> {
+        let mut jobs = self.jobs.lock().expect("jobs lock poisoned");
+        if jobs.get(id).is_some_and(|j| j.info.is_active()) {
+            return Err(FriedmanError::InvalidParams(format!("Job already running: {id}")));
+        }
+
+        let (tx, rx) = watch::channel(false);
+        let info = JobInfo {
+            id: id.to_string(),
+            command: args.iter().take(2).cloned().collect::<Vec<_>>().join(" "),
+            args: args.to_vec(),
+            status: JobStatus::Queued,
+            priority,
+            pid: None,
+            resources: None,
+            started_at: now_millis(),
+            finished_at: None,
+        };
+        jobs.insert(id.to_string(), JobEntry { info, cancel: tx });
+        prune(&mut jobs);
+
+        Ok(JobHandle {
+            id: id.to_string(),
+            cancel: rx,
+        })
+    }
+
+    /// Mark a queued job as started once the scheduler gave it a slot.
+    pub fn set_running(&self, id: &str) {
+        if let Some(job) = self.jobs.lock().expect("jobs lock poisoned").get_mut(id) {
+            job.info.status = JobStatus::Running;
+        }
+    }
+
+    pub fn set_pid(&self, id: &str, pid: Option<u32>) {
+        if let Some(job) = self.jobs.lock().expect("jobs lock poisoned").get_mut(id) {
+            job.info.pid = pid;
+        }
+    }
+
+    pub fn set_resources(&self, id: &str, resources: JobResources) {
+        if let Some(job) = self.jobs.lock().expect("jobs lock poisoned").get_mut(id) {
+            job.info.resources = Some(resources);
+        }
+    }
+
+    /// Record the outcome of a job.
+    pub fn finish<T>(&self, id: &str, result: &Result<T, FriedmanError>) {
+        let status = match result {
+            Ok(_) => JobStatus::Completed,
+            Err(FriedmanError::Cancelled(_)) => JobStatus::Cancelled,
+            Err(_) => JobStatus::Failed,
+        };
+        if let Some(job) = self.jobs.lock().expect("jobs lock poisoned").get_mut(id) {
+            job.info.status = status;
+            job.info.finished_at = Some(now_millis());
+        }
+    }
+
+    /// Signal a queued or running job to stop. A queued job leaves the queue;
+    /// a running one has its process tree killed. Either way the job
+    /// finishes with [`FriedmanError::Cancelled`].
+    pub fn cancel(&self, id: &str) -> Result<JobInfo, FriedmanError> {
+        let jobs = self.jobs.lock().expect("jobs lock poisoned");
+        let job = jobs
+            .get(id)
+            .ok_or_else(|| FriedmanError::InvalidParams(format!("Job not found: {id}")))?;
+        if !job.info.is_active() {
+            return Err(FriedmanError::InvalidParams(format!("Job is not running: {id}")));
+        }
+        let _ = job.cancel.send(true);
+        Ok(job.info.clone())
+    }
+
+    pub fn list(&self) -> Vec<JobInfo> {
+        let jobs = self.jobs.lock().expect("jobs lock poisoned");
+        let mut list: Vec<JobInfo> = jobs.values().map(|j| j.info.clone()).collect();
+        list.sort_by_key(|j| j.started_at);
+        list
+    }
+}
+
+/// Drop the oldest finished jobs beyond [`MAX_FINISHED_JOBS`].
+fn prune(jobs: &mut HashMap<String, JobEntry>) {
+    let mut finished: Vec<(u64, String)> = jobs
+        .values()
+        .filter(|j| !j.info.is_active())
+        .map(|j| (j.info.started_at, j.info.id.clone()))
+        .collect();
+    if finished.len() <= MAX_FINISHED_JOBS {
+        return;
+    }
+    finished.sort();
+    let excess = finished.len() - MAX_FINISHED_JOBS;
+    for (_, id) in finished.into_iter().take(excess) {
+        jobs.remove(&id);
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Put a child in its own process group so the whole tree (Julia plus any
+/// workers it starts) can be killed at once.
+pub fn new_process_group(cmd: &mut tokio::process::Command) {
+    #[cfg(unix)]
+    cmd.process_group(0);
+    #[cfg(windows)]
+    {
+        const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+        cmd.creation_flags(CREATE_NEW_PROCESS_GROUP);
+    }
+}
+
+/// Kill a child started with [`new_process_group`] and all its descendants.
+pub fn kill_process_tree(pid: u32) {
+    #[cfg(unix)]
+    // SAFETY: plain syscall; the group id equals the leader's pid.
+    unsafe {
+        libc::kill(-(pid as i32), libc::SIGKILL);
+    }
+    #[cfg(windows)]
+    {
+        let _ = std::process::Command::new("taskkill")
+            .args(["/T", "/F", "/PID", &pid.to_string()])
+            .status();
+    }
+}
@@ -0,0 +1,379 @@
+pub mod capabilities;
+pub mod exception;
+pub mod jobs;
+pub mod progress;
+pub mod protocol;
+pub mod resolve;
+pub mod resources;
+pub mod runner;
+pub mod scheduler;
+pub mod session;
+pub mod spill;
+pub mod transcript;
+pub mod watchdog;
+
+use std::path::PathBuf;
+use std::process::Stdio;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Emitter};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::process::Command;
+
+use jobs::{kill_process_tree, new_process_group, JobHandle};
+use resolve::resolve_sidecar;
+use resources::{JobResources, ProcessResources, Scratch};
+use spill::{SpillStore, SpoolGuard, StdoutBuffer};
+use scheduler::Priority;
+use watchdog::{Activity, Expired, Limits};
+
+use crate::error::FriedmanError;
+use crate::state::AppState;
+
+/// How we invoke the Friedman CLI.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SidecarMode {
+    /// A compiled standalone binary (PackageCompiler output).
+    Binary(PathBuf),
+    /// Dev-mode: invoke `julia --project=<cli_project> <bin_script>`.
+    Julia {
+        julia: PathBuf,
+        project_dir: PathBuf,
+        bin_script: PathBuf,
+    },
+}
+
+/// Captured result of one friedman-cli invocation.
+pub struct SidecarOutput {
+    pub code: i32,
+    pub stdout: String,
+    pub stderr: String,
+    /// Set when stdout outgrew [`spill::spill_threshold`] and was spooled to
+    /// this file instead; `stdout` is then empty. Removed once parsed.
+    pub stdout_file: Option<PathBuf>,
+}
+
+/// Observes the output of a running job: forwards stderr lines as progress
+/// events and records activity for the idle watchdog.
+#[derive(Clone)]
+pub struct OutputSink {
+    /// `None` for runners driven outside a Tauri app (tests, tools).
+    app: Option<AppHandle>,
+    job_id: String,
+    /// Whether the caller asked for `friedman://progress/{job_id}` events.
+    progress: bool,
+    activity: Activity,
+}
+
+impl OutputSink {
+    fn new(app: &AppHandle, job_id: &str, progress: bool) -> Self {
+        Self {
+            app: Some(app.clone()),
+            job_id: job_id.to_string(),
+            progress,
+            activity: Activity::default(),
+        }
+    }
+
+    /// A sink that only tracks activity, for running a
+    /// [`runner::SidecarRunner`] without an app.
+    pub fn detached(job_id: &str) -> Self {
+        Self {
+            app: None,
+            job_id: job_id.to_string(),
+            progress: false,
+            activity: Activity::default(),
+        }
+    }
+
+    pub fn app(&self) -> Option<&AppHandle> {
+        self.app.as_ref()
+    }
+
+    pub fn line(&self, line: &str) {
+        self.activity.touch();
+        let Some(app) = self.app.as_ref().filter(|_| self.progress) else {
+            return;
+        };
+        if let Some(event) = progress::parse_line(&self.job_id, line) {
+            let _ = app.emit(&format!("friedman://progress/{}", self.job_id), &event);
+        }
+    }
+
+    pub fn touch(&self) {
+        self.activity.touch();
+    }
+}
+
+/// Build the process invocation for `mode` with the given CLI args.
+fn sidecar_command(mode: &SidecarMode, args: &[String]) -> Command {
+    let mut cmd = match mode {
+        SidecarMode::Binary(bin) => {
+            let mut cmd = Command::new(bin);
+            cmd.args(args);
+            cmd
+        }
+        SidecarMode::Julia { julia, project_dir, bin_script } => {
+            let mut cmd = Command::new(julia);
+            cmd.arg(format!("--project={}", project_dir.display()))
+                .arg("--startup-file=no")
+                .arg(bin_script)
+                .args(args);
+            cmd
+        }
+    };
+    cmd.env(protocol::PROTOCOL_ENV, protocol::PROTOCOL);
+    new_process_group(&mut cmd);
+    cmd.kill_on_drop(true);
+    cmd
+}
+
+fn spawn_error(mode: &SidecarMode, e: std::io::Error) -> FriedmanError {
+    let not_found = e.kind() == std::io::ErrorKind::NotFound;
+    match mode {
+        SidecarMode::Binary(_) if not_found => FriedmanError::SidecarNotFound(format!("Failed to spawn sidecar: {e}")),
+        SidecarMode::Binary(_) => FriedmanError::SpawnFailed(format!("Failed to spawn sidecar: {e}")),
+        SidecarMode::Julia { .. } if not_found => FriedmanError::JuliaNotFound(format!("Failed to spawn julia: {e}")),
+        SidecarMode::Julia { .. } => FriedmanError::SpawnFailed(format!("Failed to spawn julia: {e}")),
+    }
+}
+
+/// Append `--format=json` so every invocation returns machine-readable output.
+fn json_args(args: &[&str]) -> Vec<String> {
+    let mut cmd_args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+    cmd_args.push("--format=json".to_string());
+    cmd_args
+}
+
+pub use protocol::CommandOutput;
+
+/// Turn a finished invocation into the decoded result, narrative text and
+/// warnings.
+pub fn parse_output(output: SidecarOutput) -> Result<CommandOutput, FriedmanError> {
+    decode_output(output, None)
+}
+
+/// [`parse_output`], writing arrays too large to send to the frontend in one
+/// piece to `spills`.
+pub fn parse_output_spilling(
+    output: SidecarOutput,
+    spills: &SpillStore,
+) -> Result<CommandOutput, FriedmanError> {
+    decode_output(output, Some(spills))
+}
+
+fn decode_output(
+    output: SidecarOutput,
+    spills: Option<&SpillStore>,
+) -> Result<CommandOutput, FriedmanError> {
+    let _spool = output.stdout_file.as_deref().map(SpoolGuard);
+    if output.code != 0 {
+        return Err(FriedmanError::SidecarExit {
+            code: output.code,
+            stderr: output.stderr,
+        });
+    }
+
+    let stderr_warnings = output
+        .stderr
+        .lines()
+        .filter_map(|line| progress::parse_line("", line))
+        .filter(|event| event.kind == progress::ProgressKind::Warning)
+        .map(|event| event.message)
+        .collect();
+    let decoded = match &output.stdout_file {
+        Some(path) => protocol::decode_file(path, spills)?,
+        None => protocol::decode(&output.stdout)?,
+    };
+    Ok(decoded.into_output(stderr_warnings))
+}
+
+/// Run a friedman-cli command, capture stdout, and parse the result as JSON.
+///
+/// `--format=json` is automatically appended so every invocation returns
+/// machine-readable output. The invocation is registered as a job under a
+/// fresh id so it shows up in `list_jobs` and can be cancelled, and waits in
+/// the [`scheduler::JobScheduler`] queue until a slot is free.
+pub async fn run_friedman_command(
+    app: &AppHandle,
+    args: Vec<&str>,
+) -> Result<CommandOutput, FriedmanError> {
+    let job_id = job_id_or_new(None);
+    run_job(app, &job_id, args, false).await
+}
+
+/// Run a friedman-cli command while streaming stderr progress as Tauri events.
+///
+/// Each line written to stderr by the sidecar is parsed into a
+/// [`progress::ProgressEvent`] and emitted as a global event named
+/// `friedman://progress/{job_id}` so the front-end can display a progress
+/// bar. `job_id` is also the id accepted by `cancel_job`.
+pub async fn run_friedman_command_with_progress(
+    app: &AppHandle,
+    job_id: &str,
+    args: Vec<&str>,
+) -> Result<CommandOutput, FriedmanError> {
+    run_job(app, job_id, args, true).await
+}
+
+/// The job id supplied by the front-end, or a fresh one.
+pub fn job_id_or_new(job_id: Option<String>) -> String {
+    job_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+}
+
+async fn run_job(
+    app: &AppHandle,
+    job_id: &str,
+    args: Vec<&str>,
+    progress: bool,
+) -> Result<CommandOutput, FriedmanError> {
+    let cmd_args = json_args(&args);
+
+    let state = app.state::<AppState>();
+    if let Some(info) = state.sidecar_info.lock().expect("sidecar info lock poisoned").as_ref() {
+        info.check(&cmd_args).map_err(|e| e.in_command(&cmd_args))?;
+    }
+    let priority = Priority::for_args(&cmd_args);
+    let mut job = state.jobs.register(job_id, &cmd_args, priority)?;
+    let limits = state
+        .timeouts
+        .lock()
+        .expect("timeouts lock poisoned")
+        .limits_for(&cmd_args);
+    let sink = OutputSink::new(app, job_id, progress);
+
+    let result = async {
+        let _slot = state.scheduler.acquire(app, &mut job, priority).await?;
+        state.jobs.set_running(job_id);
+        sink.touch();
+        state.runner.run(&mut job, &sink, limits, &cmd_args).await
+    }
+    .await
+    .and_then(|output| parse_output_spilling(output, &state.spills));
+    state.jobs.finish(job_id, &result);
+    result.map_err(|e| e.in_command(&cmd_args))
+}
+
+/// Run one invocation through the session when possible, else a fresh process.
+///
+/// The process tree is killed when the job is cancelled or exceeds `limits`.
+/// Resource settings are applied to the process (or the session it runs in)
+/// and the job gets its own scratch directory for temporary files.
+async fn execute(
+    app: &AppHandle,
+    mode: &SidecarMode,
+    job: &mut JobHandle,
+    sink: &OutputSink,
+    limits: Limits,
+    args: &[String],
+) -> Result<SidecarOutput, FriedmanError> {
+    let state = app.state::<AppState>();
+    let settings = state.settings.lock().expect("settings lock poisoned").resources.clone();
+    let resources = ProcessResources::effective(&settings);
+    let scratch = Scratch::create(&settings, &job.id)?;
+
+    if let SidecarMode::Julia { julia, project_dir, .. } = mode {
+        if let Some(result) = state
+            .session
+            .try_run(julia, project_dir, &resources, &scratch, &state.jobs, job, sink, limits, args)
+            .await
+        {
+            return result;
+        }
+    }
+
+    let mut cmd = sidecar_command(mode, args);
+    resources.apply(&mut cmd);
+    scratch.apply(&mut cmd);
+    state.jobs.set_resources(
+        &job.id,
+        JobResources {
+            process: resources,
+            scratch_dir: Some(scratch.path().to_path_buf()),
+            shared_session: false,
+        },
+    );
+    let mut child = cmd
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| spawn_error(mode, e))?;
+    let pid = child.id();
+    state.jobs.set_pid(&job.id, pid);
+
+    let mut stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| FriedmanError::SidecarExec("Failed to capture stdout".into()))?;
+    let stderr = child
+        .stderr
+        .take()
+        .ok_or_else(|| FriedmanError::SidecarExec("Failed to capture stderr".into()))?;
+
+    let stdout_sink = sink.clone();
+    let stdout_handle = tokio::spawn(async move {
+        let mut buf = StdoutBuffer::default();
+        let mut chunk = [0u8; 8192];
+        while let Ok(n @ 1..) = stdout.read(&mut chunk).await {
+            buf.extend(&chunk[..n])?;
+            stdout_sink.touch();
+        }
+        buf.finish()
+    });
+
+    // Stream stderr in the background
+    let stderr_sink = sink.clone();
+    let stderr_handle = tokio::spawn(async move {
+        let mut lines = BufReader::new(stderr).lines();
+        let mut captured = Vec::new();
+        while let Ok(Some(line)) = lines.next_line().await {
+            stderr_sink.line(&line);
+            captured.push(line);
+        }
+        captured.join("\n")
+    });
+
+    enum Outcome {
+        Exited(std::process::ExitStatus),
+        Cancelled,
+        Expired(Expired),
+    }
+
+    let outcome = tokio::select! {
+        status = child.wait() => Outcome::Exited(
+            status.map_err(|e| FriedmanError::SidecarExec(format!("Sidecar wait failed: {e}")))?,
+        ),
+        _ = job.cancelled() => Outcome::Cancelled,
+        expired = watchdog::expired(limits, &sink.activity) => Outcome::Expired(expired),
+    };
+
+    let killed = !matches!(outcome, Outcome::Exited(_));
+    if killed {
+        if let Some(pid) = pid {
+            kill_process_tree(pid);
+        }
+        let _ = child.wait().await;
+    }
+
+    // Ensure both readers finish
+    let (stdout, stdout_file) = stdout_handle
+        .await
+        .map_err(|e| FriedmanError::SidecarExec(format!("stdout reader failed: {e}")))??;
+    let stderr = stderr_handle.await.unwrap_or_default();
+    if let Some(path) = stdout_file.as_ref().filter(|_| killed) {
+        // A killed job's partial stdout is never parsed.
+        let _ = std::fs::remove_file(path);
+    }
+
+    match outcome {
+        Outcome::Exited(status) => Ok(SidecarOutput {
+            code: status.code().unwrap_or(-1),
+            stdout,
+            stderr,
+            stdout_file,
+        }),
+        Outcome::Cancelled => Err(FriedmanError::Cancelled(job.id.clone())),
+        Outcome::Expired(expired) => Err(expired.into_error(stderr)),
+    }
+}
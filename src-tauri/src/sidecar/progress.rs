@@ -0,0 +1,194 @@
+//! Typed progress events parsed from sidecar stderr.
+//!
+//! Every stderr line of a job started with a progress-aware call is emitted
+//! as a `friedman://progress/{job_id}` event carrying a [`ProgressEvent`]:
+//!
+//! ```json
+//! { "job_id": "…", "kind": "iteration", "message": "Chain 1: iteration 500/2000",
+//!   "stage": "Chain 1: iteration", "progress": 0.25, "iteration": 500, "total": 2000 }
+//! ```
+//!
+//! `kind` is one of:
+//! - `stage` — an `[ Info:` log line announcing a new step; `stage` holds it.
+//! - `progress` — a percentage bar (`Sampling: 45%|████ | ETA: 0:00:10`).
+//! - `iteration` — a counter such as `Bootstrap 50/1000` or `iteration 100 of 2000`.
+//! - `warning` — a Julia `Warning:` log record.
+//! - `log` — anything else, including log continuation lines.
+//!
+//! `progress` is a fraction in `[0, 1]` whenever it can be derived.
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressKind {
+    Stage,
+    Progress,
+    Iteration,
+    Warning,
+    Log,
+}
+
+/// Payload of `friedman://progress/{job_id}` events.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProgressEvent {
+    pub job_id: String,
+    pub kind: ProgressKind,
+    /// The cleaned stderr line.
+    pub message: String,
+    pub stage: Option<String>,
+    pub progress: Option<f64>,
+    pub iteration: Option<u64>,
+    pub total: Option<u64>,
+}
+
+/// Parse one stderr line. Returns `None` for lines with no visible text.
+pub fn parse_line(job_id: &str, raw: &str) -> Option<ProgressEvent> {
+    let message = clean(raw);
+    if message.is_empty() {
+        return None;
+    }
+
+    let mut event = ProgressEvent {
+        job_id: job_id.to_string(),
+        kind: ProgressKind::Log,
+        message: message.clone(),
+        stage: None,
+        progress: None,
+        iteration: None,
+        total: None,
+    };
+
+    // Julia logging: `┌ Warning: …` / `[ Info: …`; continuation lines stay `log`.
+    let record = message
+        .strip_prefix('┌')
+        .or_else(|| message.strip_prefix('['))
+        .map(str::trim_start);
+    if let Some(record) = record {
+        if let Some(text) = record.strip_prefix("Warning:") {
+            event.kind = ProgressKind::Warning;
+            event.message = text.trim().to_string();
+            return Some(event);
+        }
+        if let Some(text) = record.strip_prefix("Info:") {
+            event.kind = ProgressKind::Stage;
+            event.stage = Some(text.trim().to_string());
+            return Some(event);
+        }
+    }
+    if message.starts_with("Warning:") || message.starts_with("WARNING:") {
+        event.kind = ProgressKind::Warning;
+        return Some(event);
+    }
+
+    if let Some((label, percent)) = find_percent(&message) {
+        event.kind = ProgressKind::Progress;
+        event.stage = label;
+        event.progress = Some((percent / 100.0).clamp(0.0, 1.0));
+        return Some(event);
+    }
+
+    if let Some((label, done, total)) = find_counter(&message) {
+        event.kind = ProgressKind::Iteration;
+        event.stage = label;
+        event.iteration = Some(done);
+        event.total = Some(total);
+        if total > 0 {
+            event.progress = Some((done as f64 / total as f64).clamp(0.0, 1.0));
+        }
+        return Some(event);
+    }
+
+    Some(event)
+}
+
+/// Strip ANSI escapes and keep only the last `\r`-redrawn segment.
+fn clean(raw: &str) -> String {
+    let last = raw
+        .rsplit('\r')
+        .find(|s| !s.trim().is_empty())
+        .unwrap_or("");
+
+    let mut out = String::with_capacity(last.len());
+    let mut chars = last.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // CSI sequence: ESC [ params final-byte
+            if chars.peek() == Some(&'[') {
+                chars.next();
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            continue;
+        }
+        out.push(c);
+    }
+    out.trim().to_string()
+}
+
+/// Text before a progress indicator, without trailing separators.
+fn label(text: &str) -> Option<String> {
+    let label = text.trim().trim_end_matches([':', '|', '-', '(']).trim();
+    (!label.is_empty()).then(|| label.to_string())
+}
+
+/// `Sampling:  45%|████` → (`Sampling`, 45.0)
+///
+/// Only a percentage next to a bar, or on a line that is just a label and
+/// the number (`Sampling: 45%`), counts; `reject the null at 5%` does not.
+fn find_percent(line: &str) -> Option<(Option<String>, f64)> {
+    line.match_indices('%').find_map(|(pct, _)| {
+        let before = &line[..pct];
+        let after = line[pct + 1..].trim_start();
+        let start = before
+            .char_indices()
+            .rev()
+            .find(|&(_, c)| !(c.is_ascii_digit() || c == '.'))
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        let value: f64 = before[start..].parse().ok()?;
+        let prefix = before[..start].trim_end();
+
+        let bar_after = after.chars().next().is_some_and(is_bar);
+        let bar_before = prefix.chars().next_back().is_some_and(is_bar);
+        let only_label = after.is_empty() && (prefix.is_empty() || prefix.ends_with(':'));
+        (bar_after || bar_before || only_label).then(|| (label(prefix.trim_end_matches(is_bar)), value))
+    })
+}
+
+/// `|` and the box-drawing and block characters progress bars are drawn with.
+fn is_bar(c: char) -> bool {
+    c == '|' || ('\u{2500}'..='\u{259f}').contains(&c)
+}
+
+/// `Bootstrap 50/1000` or `iteration 100 of 2000` → (label, 50, 1000)
+fn find_counter(line: &str) -> Option<(Option<String>, u64, u64)> {
+    let tokens: Vec<(usize, &str)> = line
+        .split_whitespace()
+        .map(|t| (t.as_ptr() as usize - line.as_ptr() as usize, t))
+        .collect();
+
+    for (i, &(offset, token)) in tokens.iter().enumerate() {
+        let token = token.trim_end_matches([',', ')', ';']);
+        // `50/1000`
+        if let Some((a, b)) = token.split_once('/') {
+            if let (Ok(done), Ok(total)) = (a.parse(), b.parse()) {
+                return Some((label(&line[..offset]), done, total));
+            }
+        }
+        // `50 / 1000` and `50 of 1000`
+        if let (Ok(done), Some(&(_, sep)), Some(&(_, total))) =
+            (token.parse::<u64>(), tokens.get(i + 1), tokens.get(i + 2))
+        {
+            let total = total.trim_end_matches([',', ')', ';']);
+            if sep == "/" || sep == "of" {
+                if let Ok(total) = total.parse() {
+                    return Some((label(&line[..offset]), done, total));
+                }
+            }
+        }
+    }
+    None
+}
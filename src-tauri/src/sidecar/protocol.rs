@@ -0,0 +1,395 @@
+//! Decoding friedman-cli stdout.
+//!
+//! Every invocation is started with `FRIEDMAN_PROTOCOL=ndjson`. A CLI that
+//! supports it answers with a hello frame as its first line and then writes
+//! one JSON object per line:
+//!
+//! ```text
+//! {"type":"hello","protocol":"ndjson","version":1}
+//! {"type":"log","message":"Augmented Dickey-Fuller test"}
+//! {"type":"result","data":{...}}
+//! {"type":"warning","message":"series has fewer than 50 observations"}
+//! {"type":"result","data":{...}}
+//! ```
+//!
+//! A command may emit several `result` frames. Stray non-JSON lines (package
+//! loading noise) after the hello are kept as log text. Output that does not
+//! start with a hello frame comes from an older CLI and is decoded with the [`extract_json`] heuristic; the
+//! text around the JSON document (headers, test conclusions) is kept.
+//!
+//! Commands return the decoded output as a [`CommandOutput`]. Stdout too
+//! large to hold in memory is decoded from its spool file by
+//! [`decode_file`].
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::spill::{JsonReader, SpillStore};
+use crate::dataset::input::EffectiveSample;
+use crate::dataset::missing::MissingReport;
+use crate::error::FriedmanError;
+
+/// Environment variable offering the framed protocol to the sidecar.
+pub const PROTOCOL_ENV: &str = "FRIEDMAN_PROTOCOL";
+pub const PROTOCOL: &str = "ndjson";
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Frame {
+    Hello {
+        protocol: String,
+        #[serde(default)]
+        version: u32,
+    },
+    Result {
+        data: serde_json::Value,
+    },
+    Log {
+        message: String,
+    },
+    Warning {
+        message: String,
+    },
+    #[serde(other)]
+    Unknown,
+}
+
+/// Everything a command printed to stdout.
+#[derive(Debug, Clone, Default)]
+pub struct Decoded {
+    /// Result objects in the order they were emitted.
+    pub results: Vec<serde_json::Value>,
+    /// Narrative lines: log frames, or the text around the JSON document.
+    pub text: Vec<String>,
+    pub warnings: Vec<String>,
+    /// Whether the sidecar answered with the framed protocol.
+    pub framed: bool,
+}
+
+/// What a model command returns to the front-end: the parsed result plus
+/// the CLI's own narrative (e.g. "Reject H0 at 5%") and warnings.
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandOutput {
+    /// The only result object, or an array of all of them.
+    pub payload: serde_json::Value,
+    pub results: Vec<serde_json::Value>,
+    pub text: Vec<String>,
+    /// Warnings from stdout and from Julia `@warn` records on stderr.
+    pub warnings: Vec<String>,
+    /// Rows of the dataset the command ran on, when known.
+    pub sample: Option<EffectiveSample>,
+    /// What was done about missing values in the data.
+    pub missing: Option<MissingReport>,
+}
+
+impl Decoded {
+    /// Combine with warnings found on stderr into the command's return value.
+    pub fn into_output(self, stderr_warnings: Vec<String>) -> CommandOutput {
+        let payload = match self.results.as_slice() {
+            [single] => single.clone(),
+            all => serde_json::Value::Array(all.to_vec()),
+        };
+        let mut warnings = self.warnings;
+        warnings.extend(stderr_warnings);
+        CommandOutput {
+            payload,
+            results: self.results,
+            text: self.text,
+            warnings,
+            sample: None,
+            missing: None,
+        }
+    }
+}
+
+/// Decode stdout, using the framed protocol when the sidecar negotiated it.
+pub fn decode(stdout: &str) -> Result<Decoded, FriedmanError> {
+    let mut lines = stdout.lines().map(str::trim).filter(|l| !l.is_empty());
+    // Only the first line can be the hello frame; anything printed before it
+    // means an old CLI, decoded with every line kept. The hello is short, so
+    // don't try to parse a large payload line.
+    let hello = lines
+        .next()
+        .filter(|line| line.len() < 512 && line.starts_with('{'))
+        .and_then(|line| match serde_json::from_str(line) {
+            Ok(Frame::Hello { protocol, version }) => Some((protocol, version)),
+            _ => None,
+        });
+
+    match hello {
+        Some((protocol, version)) if protocol == PROTOCOL => {
+            log::debug!("Sidecar speaks {protocol} v{version}");
+            decode_frames(lines)
+        }
+        Some((protocol, _)) => Err(FriedmanError::JsonParse(format!(
+            "Sidecar answered with unsupported protocol `{protocol}`"
+        ))),
+        None => decode_legacy(stdout),
+    }
+}
+
+/// Decode stdout spooled to `path` without reading it into memory.
+///
+/// Follows [`decode`], except that a legacy JSON document must start on its
+/// own line and the hello frame must be the first thing on stdout. Arrays larger than the threshold of `spills` are written to
+/// disk and replaced by handles.
+pub fn decode_file(path: &Path, spills: Option<&SpillStore>) -> Result<Decoded, FriedmanError> {
+    let mut input = BufReader::new(File::open(path)?);
+    let mut decoded = Decoded::default();
+    let mut before_json = Vec::new();
+    let mut seen_json = false;
+    // Text after a JSON value on its line, pushed once the value is handled.
+    let mut pending: Option<String> = None;
+
+    loop {
+        if let Some(line) = pending.take() {
+            push_text(&mut decoded, &mut before_json, seen_json, &line);
+        }
+        let start = {
+            let buf = input.fill_buf()?;
+            let skip = buf.iter().take_while(|b| b.is_ascii_whitespace()).count();
+            if skip > 0 {
+                input.consume(skip);
+                continue;
+            }
+            match buf.first() {
+                None => break,
+                Some(_) => json_start(buf),
+            }
+        };
+
+        let (value, rest) = match start {
+            JsonStart::No => {
+                let mut line = String::new();
+                input.read_line(&mut line)?;
+                push_text(&mut decoded, &mut before_json, seen_json, line.trim());
+                continue;
+            }
+            JsonStart::Stream => {
+                let value = JsonReader::new(&mut input, spills).next_value()?;
+                let mut rest = String::new();
+                input.read_line(&mut rest)?;
+                (value, rest)
+            }
+            JsonStart::Maybe => {
+                // `[1/3] Fitting model` or `[1, 2]`: parse the line on its
+                // own and keep it as text unless it is a single JSON value.
+                let mut line = String::new();
+                input.read_line(&mut line)?;
+                let mut reader = JsonReader::new(line.as_bytes(), spills);
+                match reader.next_value() {
+                    Ok(value) if reader.into_inner().iter().all(u8::is_ascii_whitespace) => (value, String::new()),
+                    _ => {
+                        push_text(&mut decoded, &mut before_json, seen_json, line.trim());
+                        continue;
+                    }
+                }
+            }
+        };
+        let rest = rest.trim();
+        if !rest.is_empty() {
+            pending = Some(rest.to_string());
+        }
+
+        if !seen_json {
+            seen_json = true;
+            let hello = match serde_json::from_value::<Frame>(value.clone()) {
+                Ok(Frame::Hello { protocol, version }) if before_json.is_empty() => Some((protocol, version)),
+                _ => None,
+            };
+            match hello {
+                Some((protocol, version)) if protocol == PROTOCOL => {
+                    log::debug!("Sidecar speaks {protocol} v{version}");
+                    decoded.framed = true;
+                    continue;
+                }
+                Some((protocol, _)) => {
+                    return Err(FriedmanError::JsonParse(format!(
+                        "Sidecar answered with unsupported protocol `{protocol}`"
+                    )));
+                }
+                None => {
+                    for line in before_json.drain(..) {
+                        push_legacy_line(&mut decoded, &line);
+                    }
+                }
+            }
+        }
+
+        if !decoded.framed {
+            if decoded.results.is_empty() {
+                decoded.results.push(value);
+            } else {
+                decoded.text.push(value.to_string());
+            }
+            continue;
+        }
+        match serde_json::from_value::<Frame>(value) {
+            Ok(Frame::Result { data }) => decoded.results.push(data),
+            Ok(Frame::Log { message }) => decoded.text.push(message),
+            Ok(Frame::Warning { message }) => decoded.warnings.push(message),
+            Ok(Frame::Hello { .. }) | Ok(Frame::Unknown) => {}
+            Err(e) => decoded.text.push(e.to_string()),
+        }
+    }
+    if let Some(line) = pending {
+        push_text(&mut decoded, &mut before_json, seen_json, &line);
+    }
+
+    if decoded.results.is_empty() {
+        return Err(FriedmanError::JsonParse(if decoded.framed {
+            format!("Sidecar sent no result frame: {}", decoded.text.join("\n"))
+        } else {
+            format!("No JSON found in output: {}", before_json.join("\n"))
+        }));
+    }
+    Ok(decoded)
+}
+
+enum JsonStart {
+    No,
+    /// Certainly JSON; parsed straight from the input.
+    Stream,
+    /// An array on one line, or text such as `[1/3] Fitting model`.
+    Maybe,
+}
+
+/// Whether `buf` (at the start of a line) opens a JSON object or array, as
+/// opposed to text such as `[ Info: ...`.
+fn json_start(buf: &[u8]) -> JsonStart {
+    match buf.first() {
+        Some(b'{') => JsonStart::Stream,
+        Some(b'[') => match buf[1..].iter().find(|b| !matches!(b, b' ' | b'\t')) {
+            None | Some(b'\n' | b'\r' | b'{' | b'[') => JsonStart::Stream,
+            Some(b']' | b'"' | b'-' | b't' | b'f' | b'n' | b'0'..=b'9') => JsonStart::Maybe,
+            Some(_) => JsonStart::No,
+        },
+        _ => JsonStart::No,
+    }
+}
+
+/// A line of text, kept for later if no JSON has been seen yet.
+fn push_text(decoded: &mut Decoded, before_json: &mut Vec<String>, seen_json: bool, line: &str) {
+    if !seen_json {
+        before_json.push(line.to_string());
+    } else if decoded.framed {
+        decoded.text.push(line.to_string());
+    } else {
+        push_legacy_line(decoded, line);
+    }
+}
+
+fn push_legacy_line(decoded: &mut Decoded, line: &str) {
+    match warning_text(line) {
+        Some(warning) => decoded.warnings.push(warning.to_string()),
+        None => decoded.text.push(line.to_string()),
+    }
+}
+
+/// Old CLIs: one JSON document somewhere in stdout; every other non-empty
+/// line is narrative, and `Warning:` lines are warnings.
+fn decode_legacy(stdout: &str) -> Result<Decoded, FriedmanError> {
+    if let Ok(value) = serde_json::from_str(stdout) {
+        return Ok(Decoded {
+            results: vec![value],
+            ..Decoded::default()
+        });
+    }
+
+    let (start, end) = locate_json(stdout)?;
+    let value = parse_slice(&stdout[start..end])?;
+
+    let mut decoded = Decoded {
+        results: vec![value],
+        ..Decoded::default()
+    };
+    let around = stdout[..start].lines().chain(stdout[end..].lines());
+    for line in around.map(str::trim).filter(|l| !l.is_empty()) {
+        push_legacy_line(&mut decoded, line);
+    }
+    Ok(decoded)
+}
+
+/// The message of a `Warning:` / `┌ Warning:` line.
+fn warning_text(line: &str) -> Option<&str> {
+    let line = line.trim_start_matches(['┌', '[']).trim_start();
+    line.strip_prefix("Warning:")
+        .or_else(|| line.strip_prefix("WARNING:"))
+        .map(str::trim)
+}
+
+fn decode_frames<'a>(lines: impl Iterator<Item = &'a str>) -> Result<Decoded, FriedmanError> {
+    let mut decoded = Decoded {
+        framed: true,
+        ..Decoded::default()
+    };
+    for line in lines {
+        match serde_json::from_str::<Frame>(line) {
+            Ok(Frame::Result { data }) => decoded.results.push(data),
+            Ok(Frame::Log { message }) => decoded.text.push(message),
+            Ok(Frame::Warning { message }) => decoded.warnings.push(message),
+            Ok(Frame::Hello { .. }) | Ok(Frame::Unknown) => {}
+            Err(_) => decoded.text.push(line.to_string()),
+        }
+    }
+    if decoded.results.is_empty() {
+        return Err(FriedmanError::JsonParse(format!(
+            "Sidecar sent no result frame: {}",
+            decoded.text.join("\n")
+        )));
+    }
+    Ok(decoded)
+}
+
+/// Extract the first JSON object or array from mixed stdout.
+///
+/// The Julia sidecar prints diagnostic text (headers, conclusions) around the
+/// JSON payload.  We scan for a `{` or `[`, find its matching closing
+/// delimiter, and parse the first such slice that is valid JSON.
+pub fn extract_json(raw: &str) -> Result<serde_json::Value, FriedmanError> {
+    // Fast path: entire output is valid JSON
+    if let Ok(v) = serde_json::from_str(raw) {
+        return Ok(v);
+    }
+
+    let (start, end) = locate_json(raw)?;
+    parse_slice(&raw[start..end])
+}
+
+/// Byte range of the first complete JSON object or array in `raw`.
+///
+/// Candidates that are not valid JSON (e.g. `[constant]` in a header line)
+/// are skipped. Each candidate is parsed in place and text fails within a
+/// few bytes, so the output is scanned about once.
+fn locate_json(raw: &str) -> Result<(usize, usize), FriedmanError> {
+    let mut first_error = None;
+    // A value cut off by the end of the output is the likelier culprit than
+    // bracketed text.
+    let mut unmatched = false;
+    for (start, _) in raw.match_indices(['{', '[']) {
+        let mut values = serde_json::Deserializer::from_str(&raw[start..]).into_iter::<serde::de::IgnoredAny>();
+        match values.next() {
+            Some(Ok(_)) => return Ok((start, start + values.byte_offset())),
+            Some(Err(e)) if e.is_eof() => unmatched = true,
+            Some(Err(e)) => {
+                first_error.get_or_insert_with(|| {
+                    let line = raw[start..].lines().next().unwrap_or_default();
+                    FriedmanError::JsonParse(format!("{e}: {line}"))
+                });
+            }
+            None => {}
+        }
+    }
+    if unmatched {
+        return Err(FriedmanError::JsonParse(format!("Unmatched JSON delimiter in output: {raw}")));
+    }
+    Err(first_error
+        .unwrap_or_else(|| FriedmanError::JsonParse(format!("No JSON found in output: {raw}"))))
+}
+
+fn parse_slice(json: &str) -> Result<serde_json::Value, FriedmanError> {
+    serde_json::from_str(json).map_err(|e| FriedmanError::JsonParse(format!("{e}: {json}")))
+}
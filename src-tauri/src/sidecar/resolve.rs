@@ -0,0 +1,289 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use super::SidecarMode;
+use crate::error::FriedmanError;
+use crate::settings::SidecarSettings;
+use crate::state::AppState;
+
+const BINARY_NAMES: &[&str] = &["friedman-cli", "friedman-cli.exe"];
+const JULIA_NAME: &str = if cfg!(windows) { "julia.exe" } else { "julia" };
+const MAIN_SCRIPT: &str = "main.jl";
+
+/// One location considered while resolving the sidecar.
+#[derive(Debug, Clone, Serialize)]
+pub struct Candidate {
+    /// `binary`, `project` or `julia`.
+    pub kind: &'static str,
+    /// Where the path came from: an environment variable, `settings`,
+    /// `resources`, `dev`, `PATH` or a well-known install location.
+    pub source: String,
+    pub path: PathBuf,
+    pub accepted: bool,
+    /// Why the candidate was rejected.
+    pub reason: Option<String>,
+}
+
+/// Report returned by `diagnose_sidecar`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SidecarDiagnosis {
+    pub mode: Option<SidecarMode>,
+    pub error: Option<String>,
+    /// Every candidate in the order it was tried; resolution stops at the
+    /// first accepted `binary` or `project` (plus its `julia`).
+    pub candidates: Vec<Candidate>,
+    pub settings: SidecarSettings,
+}
+
+/// Resolve how to run friedman-cli.
+///
+/// Priority:
+/// 1. `FRIEDMAN_CLI_BINARY`, then `FRIEDMAN_SIDECAR_PROJECT`, so a single
+///    run can override anything saved in settings.
+/// 2. The `binary` setting, then the `project_dir` setting. Projects run
+///    with the Julia found by [`find_julia`].
+/// 3. Compiled binary in the Tauri resource directory (production builds).
+/// 4. Debug builds only: compiled binary in `src-tauri/binaries/`, then the
+///    `src-tauri/sidecar` Julia environment that pulls Friedman-cli from
+///    <https://github.com/chung9207/Friedman-cli>.
+///    Run `./scripts/setup-sidecar.sh` once to install the Julia deps.
+pub fn resolve_sidecar(app: &AppHandle) -> Result<SidecarMode, FriedmanError> {
+    let settings = current_settings(app);
+    Resolver::default().resolve(app, &settings)
+}
+
+/// Resolve the sidecar while recording every candidate and why it was
+/// rejected.
+pub fn diagnose(app: &AppHandle) -> SidecarDiagnosis {
+    let settings = current_settings(app);
+    let mut resolver = Resolver::default();
+    let result = resolver.resolve(app, &settings);
+    SidecarDiagnosis {
+        error: result.as_ref().err().map(|e| e.to_string()),
+        mode: result.ok(),
+        candidates: resolver.candidates,
+        settings,
+    }
+}
+
+/// The sidecar named by an environment variable or a setting (steps 1 and
+/// 2 of [`resolve_sidecar`]), if one of them is usable.
+pub fn resolve_explicit(settings: &SidecarSettings) -> Option<Result<SidecarMode, FriedmanError>> {
+    Resolver::default().explicit(settings)
+}
+
+fn current_settings(app: &AppHandle) -> SidecarSettings {
+    let state = app.state::<AppState>();
+    let settings = state.settings.lock().expect("settings lock poisoned");
+    settings.sidecar.clone()
+}
+
+#[derive(Default)]
+struct Resolver {
+    candidates: Vec<Candidate>,
+}
+
+impl Resolver {
+    fn resolve(
+        &mut self,
+        app: &AppHandle,
+        settings: &SidecarSettings,
+    ) -> Result<SidecarMode, FriedmanError> {
+        // 1-2. Environment variables, then settings
+        if let Some(mode) = self.explicit(settings) {
+            return mode;
+        }
+
+        // 3. Bundled resource directory (production)
+        if let Ok(resource_dir) = app.path().resource_dir() {
+            for name in BINARY_NAMES {
+                let candidate = resource_dir.join(name);
+                if self.check_binary("resources", &candidate) {
+                    return Ok(SidecarMode::Binary(candidate));
+                }
+            }
+        }
+
+        // 4. Source checkout; the manifest path is meaningless once installed.
+        if cfg!(debug_assertions) {
+            let manifest = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+            for name in BINARY_NAMES {
+                let candidate = manifest.join("binaries").join(name);
+                if self.check_binary("dev", &candidate) {
+                    return Ok(SidecarMode::Binary(candidate));
+                }
+            }
+
+            let sidecar_dir = manifest.join("sidecar");
+            if self.check_project("dev", &sidecar_dir) {
+                return self.julia_mode(sidecar_dir, settings);
+            }
+        }
+
+        Err(FriedmanError::SidecarNotFound(format!(
+            "friedman-cli binary not found ({}). Set FRIEDMAN_CLI_BINARY or \
+             FRIEDMAN_SIDECAR_PROJECT, configure the sidecar in settings, or for \
+             dev mode run ./scripts/setup-sidecar.sh first.",
+            self.rejections()
+        )))
+    }
+
+    fn explicit(&mut self, settings: &SidecarSettings) -> Option<Result<SidecarMode, FriedmanError>> {
+        let explicit = [
+            env_path("FRIEDMAN_CLI_BINARY").map(|p| ("binary", "FRIEDMAN_CLI_BINARY", p)),
+            env_path("FRIEDMAN_SIDECAR_PROJECT").map(|p| ("project", "FRIEDMAN_SIDECAR_PROJECT", p)),
+            settings.binary.clone().map(|p| ("binary", "settings", p)),
+            settings.project_dir.clone().map(|p| ("project", "settings", p)),
+        ];
+        for (kind, source, path) in explicit.into_iter().flatten() {
+            if kind == "binary" && self.check_binary(source, &path) {
+                return Some(Ok(SidecarMode::Binary(path)));
+            }
+            if kind == "project" && self.check_project(source, &path) {
+                return Some(self.julia_mode(path, settings));
+            }
+        }
+        None
+    }
+
+    fn julia_mode(
+        &mut self,
+        project_dir: PathBuf,
+        settings: &SidecarSettings,
+    ) -> Result<SidecarMode, FriedmanError> {
+        let julia = self.find_julia(settings).ok_or_else(|| {
+            FriedmanError::JuliaNotFound(format!(
+                "julia executable not found ({}). Set FRIEDMAN_JULIA or the \
+                 julia path in settings.",
+                self.rejections()
+            ))
+        })?;
+        Ok(SidecarMode::Julia {
+            julia,
+            bin_script: project_dir.join(MAIN_SCRIPT),
+            project_dir,
+        })
+    }
+
+    /// Find the `julia` executable: explicit overrides, `JULIA_BINDIR`,
+    /// common install locations, then every `PATH` entry (which covers
+    /// `module load julia` setups).
+    fn find_julia(&mut self, settings: &SidecarSettings) -> Option<PathBuf> {
+        let home = dirs::home_dir();
+        let mut candidates: Vec<(String, PathBuf)> = [
+            env_path("FRIEDMAN_JULIA").map(|p| ("FRIEDMAN_JULIA", p)),
+            settings.julia.clone().map(|p| ("settings", p)),
+            env_path("JULIA_BINDIR").map(|d| ("JULIA_BINDIR", d.join(JULIA_NAME))),
+            // juliaup (macOS/Linux/Windows)
+            home.as_ref().map(|h| ("juliaup", h.join(".juliaup").join("bin").join(JULIA_NAME))),
+            // Homebrew
+            Some(("homebrew", PathBuf::from("/opt/homebrew/bin/julia"))),
+            // Linux
+            Some(("system", PathBuf::from("/usr/local/bin/julia"))),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|(source, path)| (source.to_string(), path))
+        .collect();
+
+        if let Some(path) = std::env::var_os("PATH") {
+            for dir in std::env::split_paths(&path) {
+                candidates.push(("PATH".into(), dir.join(JULIA_NAME)));
+            }
+        }
+
+        let mut seen = Vec::new();
+        for (source, path) in candidates {
+            if seen.contains(&path) {
+                continue;
+            }
+            if self.check_executable("julia", &source, &path) {
+                return Some(path);
+            }
+            seen.push(path);
+        }
+        None
+    }
+
+    fn check_binary(&mut self, source: &str, path: &Path) -> bool {
+        self.check_executable("binary", source, path)
+    }
+
+    fn check_project(&mut self, source: &str, dir: &Path) -> bool {
+        let reason = if !dir.is_dir() {
+            Some("directory does not exist".to_string())
+        } else if !dir.join(MAIN_SCRIPT).is_file() {
+            Some(format!("no {MAIN_SCRIPT} in directory"))
+        } else if !dir.join("Project.toml").is_file() {
+            Some("no Project.toml in directory".to_string())
+        } else {
+            None
+        };
+        self.record("project", source, dir, reason)
+    }
+
+    fn check_executable(&mut self, kind: &'static str, source: &str, path: &Path) -> bool {
+        let reason = match std::fs::metadata(path) {
+            Err(_) => Some("does not exist".to_string()),
+            Ok(meta) if !meta.is_file() => Some("not a file".to_string()),
+            Ok(meta) if !is_executable(&meta) => Some("not executable".to_string()),
+            Ok(_) => None,
+        };
+        self.record(kind, source, path, reason)
+    }
+
+    fn record(&mut self, kind: &'static str, source: &str, path: &Path, reason: Option<String>) -> bool {
+        let accepted = reason.is_none();
+        self.candidates.push(Candidate {
+            kind,
+            source: source.to_string(),
+            path: path.to_path_buf(),
+            accepted,
+            reason,
+        });
+        accepted
+    }
+
+    /// Rejected explicit overrides, for error messages; built-in locations
+    /// are left to `diagnose_sidecar`.
+    fn rejections(&self) -> String {
+        let explicit: Vec<String> = self
+            .candidates
+            .iter()
+            .filter(|c| !c.accepted && (c.source == "settings" || c.source.starts_with("FRIEDMAN_")))
+            .map(|c| {
+                format!(
+                    "{} {} from {}: {}",
+                    c.kind,
+                    c.path.display(),
+                    c.source,
+                    c.reason.as_deref().unwrap_or("rejected")
+                )
+            })
+            .collect();
+        if explicit.is_empty() {
+            format!("{} locations tried", self.candidates.len())
+        } else {
+            explicit.join("; ")
+        }
+    }
+}
+
+fn env_path(var: &str) -> Option<PathBuf> {
+    std::env::var_os(var)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+#[cfg(unix)]
+fn is_executable(meta: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    meta.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_meta: &std::fs::Metadata) -> bool {
+    true
+}
@@ -0,0 +1,174 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tokio::process::Command;
+
+use crate::error::FriedmanError;
+use crate::settings::ResourceSettings;
+
+/// Environment variables pointed at a job's scratch directory.
+const SCRATCH_VARS: &[&str] = &["TMPDIR", "TMP", "TEMP", "FRIEDMAN_SCRATCH_DIR"];
+
+/// Process-wide limits as they will actually be applied, after clamping to
+/// what the OS permits. The session is restarted when these change.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ProcessResources {
+    pub threads: Option<u32>,
+    pub nice: Option<i32>,
+    pub memory_limit_mb: Option<u64>,
+}
+
+/// Resources in effect for one job, recorded in its `JobInfo`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct JobResources {
+    #[serde(flatten)]
+    pub process: ProcessResources,
+    pub scratch_dir: Option<PathBuf>,
+    /// The job ran in the shared session, which was started with `process`.
+    pub shared_session: bool,
+}
+
+impl ProcessResources {
+    /// The limits from `settings` that can be applied here.
+    ///
+    /// An unprivileged process cannot lower its nice level or raise its hard
+    /// memory limit, so the requested values are clamped to the current ones
+    /// instead of failing the spawn.
+    pub fn effective(settings: &ResourceSettings) -> Self {
+        Self {
+            threads: settings.threads,
+            nice: settings.nice.and_then(effective_nice),
+            memory_limit_mb: settings.memory_limit_mb.and_then(effective_memory_limit),
+        }
+    }
+
+    pub fn apply(&self, cmd: &mut Command) {
+        if let Some(threads) = self.threads {
+            cmd.env("JULIA_NUM_THREADS", threads.to_string())
+                .env("OPENBLAS_NUM_THREADS", threads.to_string());
+        }
+
+        #[cfg(unix)]
+        {
+            let nice = self.nice;
+            let memory = self.memory_limit_mb;
+            if nice.is_some() || memory.is_some() {
+                // SAFETY: only async-signal-safe syscalls run between fork and exec.
+                unsafe {
+                    cmd.pre_exec(move || limit_current_process(nice, memory));
+                }
+            }
+        }
+    }
+}
+
+/// A job's private temporary directory, removed when dropped unless the
+/// settings ask to keep it.
+pub struct Scratch {
+    path: PathBuf,
+    keep: bool,
+}
+
+impl Scratch {
+    /// Create `<scratch_dir or temp dir>/friedman-<job id>`.
+    pub fn create(settings: &ResourceSettings, job_id: &str) -> Result<Self, FriedmanError> {
+        let base = settings
+            .scratch_dir
+            .clone()
+            .unwrap_or_else(std::env::temp_dir);
+        let name: String = job_id
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+            .collect();
+        let path = base.join(format!("friedman-{name}"));
+        std::fs::create_dir_all(&path)?;
+        Ok(Self {
+            path,
+            keep: settings.keep_scratch,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// `(variable, value)` pairs that redirect temporary files here.
+    pub fn env(&self) -> Vec<(String, String)> {
+        let dir = self.path.display().to_string();
+        SCRATCH_VARS
+            .iter()
+            .map(|var| (var.to_string(), dir.clone()))
+            .collect()
+    }
+
+    pub fn apply(&self, cmd: &mut Command) {
+        cmd.envs(self.env());
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        if !self.keep {
+            let _ = std::fs::remove_dir_all(&self.path);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn effective_nice(requested: i32) -> Option<i32> {
+    // SAFETY: plain syscall on the current process.
+    let current = unsafe { libc::getpriority(libc::PRIO_PROCESS as _, 0) };
+    Some(requested.max(current))
+}
+
+#[cfg(not(unix))]
+fn effective_nice(_requested: i32) -> Option<i32> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn effective_memory_limit(requested_mb: u64) -> Option<u64> {
+    let mut limit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    // SAFETY: `limit` is a valid out-pointer.
+    if unsafe { libc::getrlimit(libc::RLIMIT_AS, &mut limit) } != 0
+        || limit.rlim_max == libc::RLIM_INFINITY
+    {
+        return Some(requested_mb);
+    }
+    Some(requested_mb.min(limit.rlim_max / (1024 * 1024)))
+}
+
+// macOS accepts `RLIMIT_AS` but does not enforce it.
+#[cfg(not(target_os = "linux"))]
+fn effective_memory_limit(_requested_mb: u64) -> Option<u64> {
+    None
+}
+
+/// Runs in the forked child before `exec`.
+#[cfg(unix)]
+fn limit_current_process(nice: Option<i32>, memory_mb: Option<u64>) -> std::io::Result<()> {
+    if let Some(nice) = nice {
+        // SAFETY: plain syscall on the current process.
+        if unsafe { libc::setpriority(libc::PRIO_PROCESS as _, 0, nice) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    #[cfg(target_os = "linux")]
+    if let Some(mb) = memory_mb {
+        let bytes = mb.saturating_mul(1024 * 1024);
+        let limit = libc::rlimit {
+            rlim_cur: bytes,
+            rlim_max: bytes,
+        };
+        // SAFETY: `limit` is a valid pointer for the duration of the call.
+        if unsafe { libc::setrlimit(libc::RLIMIT_AS, &limit) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = memory_mb;
+    Ok(())
+}
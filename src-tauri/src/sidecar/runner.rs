@@ -0,0 +1,432 @@
+//! How a queued job is turned into sidecar output.
+//!
+//! [`AppState`](crate::state::AppState) holds one [`SidecarRunner`], chosen
+//! with `FRIEDMAN_RUNNER`:
+//! - `process` (default) — run friedman-cli through the Julia session or a
+//!   fresh process.
+//! - `fake` — canned JSON per subcommand, optionally loaded from
+//!   `FRIEDMAN_FAKE_DIR` (`<group>_<subcommand>.json` or `<group>.json`).
+//! - `replay` — serve the transcripts recorded in `FRIEDMAN_REPLAY_DIR`;
+//!   with `FRIEDMAN_REPLAY_TIMING=realtime` each response takes as long as
+//!   the recorded run did, and with `FRIEDMAN_REPLAY_MATCH=lenient` a run
+//!   with no exact recording gets one of the same subcommand (for demos).
+//!
+//! Setting `FRIEDMAN_RECORD_DIR` additionally records every invocation of
+//! the chosen runner as a [`TranscriptEntry`] in that directory.
+
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+
+use super::jobs::JobHandle;
+use super::transcript::{self, TranscriptEntry};
+use super::watchdog::Limits;
+use super::{execute, resolve_sidecar, OutputSink, SidecarOutput};
+use crate::error::FriedmanError;
+
+pub type RunFuture<'a> = Pin<Box<dyn Future<Output = Result<SidecarOutput, FriedmanError>> + Send + 'a>>;
+
+/// Produces the output of one friedman-cli invocation.
+///
+/// `args` are the full CLI args (including `--format=json`). Scheduling,
+/// job bookkeeping and JSON parsing happen around the runner, so an
+/// implementation only has to return what the CLI would have printed.
+pub trait SidecarRunner: Send + Sync {
+    fn run<'a>(
+        &'a self,
+        job: &'a mut JobHandle,
+        sink: &'a OutputSink,
+        limits: Limits,
+        args: &'a [String],
+    ) -> RunFuture<'a>;
+
+    /// Whether this runner starts real Julia processes, i.e. whether the
+    /// session warm-up and capability probe are worth doing.
+    fn spawns_sidecar(&self) -> bool {
+        false
+    }
+}
+
+/// Runner selected by `FRIEDMAN_RUNNER` and `FRIEDMAN_RECORD_DIR`; see the
+/// module docs.
+pub fn from_env() -> Arc<dyn SidecarRunner> {
+    let runner = select_from_env();
+    match std::env::var_os("FRIEDMAN_RECORD_DIR").filter(|d| !d.is_empty()) {
+        Some(dir) => {
+            log::info!("Recording sidecar transcripts to {}", Path::new(&dir).display());
+            Arc::new(RecordingRunner::new(runner, dir.into()))
+        }
+        None => runner,
+    }
+}
+
+fn select_from_env() -> Arc<dyn SidecarRunner> {
+    match std::env::var("FRIEDMAN_RUNNER").as_deref() {
+        Ok("fake") => {
+            let runner = match std::env::var_os("FRIEDMAN_FAKE_DIR") {
+                Some(dir) => FakeRunner::from_dir(Path::new(&dir)).unwrap_or_else(|e| {
+                    log::error!("Could not load fake responses: {e}");
+                    FakeRunner::new()
+                }),
+                None => FakeRunner::new(),
+            };
+            Arc::new(runner)
+        }
+        Ok("replay") => {
+            let runner = std::env::var_os("FRIEDMAN_REPLAY_DIR")
+                .ok_or_else(|| FriedmanError::InvalidParams("FRIEDMAN_REPLAY_DIR is not set".into()))
+                .and_then(|dir| ReplayRunner::from_dir(Path::new(&dir)))
+                .unwrap_or_else(|e| {
+                    // Keep replay mode rather than silently spawning Julia.
+                    log::error!("Could not load transcripts: {e}");
+                    ReplayRunner::new(Vec::new())
+                });
+            let realtime = std::env::var("FRIEDMAN_REPLAY_TIMING").as_deref() == Ok("realtime");
+            let lenient = std::env::var("FRIEDMAN_REPLAY_MATCH").as_deref() == Ok("lenient");
+            Arc::new(runner.with_realtime(realtime).with_lenient(lenient))
+        }
+        Ok("process") | Err(_) => Arc::new(ProcessRunner),
+        Ok(other) => {
+            log::warn!("Unknown FRIEDMAN_RUNNER={other}, using the process runner");
+            Arc::new(ProcessRunner)
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Process runner
+// ---------------------------------------------------------------------------
+
+/// Runs friedman-cli for real: through the Julia session when possible,
+/// otherwise as a fresh process.
+pub struct ProcessRunner;
+
+impl SidecarRunner for ProcessRunner {
+    fn run<'a>(
+        &'a self,
+        job: &'a mut JobHandle,
+        sink: &'a OutputSink,
+        limits: Limits,
+        args: &'a [String],
+    ) -> RunFuture<'a> {
+        Box::pin(async move {
+            let app = sink.app().ok_or_else(|| {
+                FriedmanError::SidecarExec("the process runner needs a running app".into())
+            })?;
+            let mode = resolve_sidecar(app)?;
+            execute(app, &mode, job, sink, limits, args).await
+        })
+    }
+
+    fn spawns_sidecar(&self) -> bool {
+        true
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Fake runner
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+pub enum FakeResponse {
+    Json(serde_json::Value),
+    Failure { code: i32, stderr: String },
+}
+
+/// Returns canned responses keyed by `"<group> <subcommand>"` or `"<group>"`
+/// and records every call.
+///
+/// Commands without a canned response succeed with
+/// `{"fake": true, "command": ..., "args": [...]}`.
+#[derive(Default)]
+pub struct FakeRunner {
+    responses: Mutex<HashMap<String, FakeResponse>>,
+    calls: Mutex<Vec<Vec<String>>>,
+}
+
+impl FakeRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load `<group>_<subcommand>.json` and `<group>.json` files from `dir`.
+    pub fn from_dir(dir: &Path) -> Result<Self, FriedmanError> {
+        let runner = Self::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if !path.extension().is_some_and(|ext| ext == "json") {
+                continue;
+            }
+            let Some(stem) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+                continue;
+            };
+            let text = std::fs::read_to_string(&path)?;
+            let value = serde_json::from_str(&text)
+                .map_err(|e| FriedmanError::JsonParse(format!("{}: {e}", path.display())))?;
+            runner.respond(&stem.replacen('_', " ", 1), value);
+        }
+        Ok(runner)
+    }
+
+    pub fn respond(&self, command: &str, value: serde_json::Value) {
+        self.set(command, FakeResponse::Json(value));
+    }
+
+    /// Make `command` exit with `code` and `stderr`.
+    pub fn fail(&self, command: &str, code: i32, stderr: &str) {
+        self.set(command, FakeResponse::Failure { code, stderr: stderr.into() });
+    }
+
+    /// Args of every invocation so far, in order.
+    pub fn calls(&self) -> Vec<Vec<String>> {
+        self.calls.lock().expect("fake calls lock poisoned").clone()
+    }
+
+    fn set(&self, command: &str, response: FakeResponse) {
+        self.responses
+            .lock()
+            .expect("fake responses lock poisoned")
+            .insert(command.to_string(), response);
+    }
+
+    fn response_for(&self, args: &[String]) -> Option<FakeResponse> {
+        let responses = self.responses.lock().expect("fake responses lock poisoned");
+        let command = args.iter().take(2).cloned().collect::<Vec<_>>().join(" ");
+        responses
+            .get(&command)
+            .or_else(|| args.first().and_then(|group| responses.get(group)))
+            .cloned()
+    }
+}
+
+impl SidecarRunner for FakeRunner {
+    fn run<'a>(
+        &'a self,
+        _job: &'a mut JobHandle,
+        sink: &'a OutputSink,
+        _limits: Limits,
+        args: &'a [String],
+    ) -> RunFuture<'a> {
+        Box::pin(async move {
+            self.calls
+                .lock()
+                .expect("fake calls lock poisoned")
+                .push(args.to_vec());
+            sink.touch();
+
+            let output = match self.response_for(args) {
+                Some(FakeResponse::Json(value)) => SidecarOutput {
+                    code: 0,
+                    stdout: value.to_string(),
+                    stderr: String::new(),
+                    stdout_file: None,
+                },
+                Some(FakeResponse::Failure { code, stderr }) => SidecarOutput {
+                    code,
+                    stdout: String::new(),
+                    stderr,
+                    stdout_file: None,
+                },
+                None => SidecarOutput {
+                    code: 0,
+                    stdout: json!({
+                        "fake": true,
+                        "command": args.iter().take(2).cloned().collect::<Vec<_>>().join(" "),
+                        "args": args,
+                    })
+                    .to_string(),
+                    stderr: String::new(),
+                    stdout_file: None,
+                },
+            };
+            Ok(output)
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Replay runner
+// ---------------------------------------------------------------------------
+
+/// Serves recorded [`TranscriptEntry`]s instead of running the CLI.
+///
+/// An invocation matches a recording with identical args, then one with the
+/// same [`TranscriptEntry::portable_args`], then, only when lenient, any
+/// recording of the same subcommand. Repeated invocations walk through
+/// equally good matches in order and keep returning the last one.
+pub struct ReplayRunner {
+    entries: Vec<TranscriptEntry>,
+    served: Mutex<HashSet<usize>>,
+    realtime: bool,
+    lenient: bool,
+}
+
+impl ReplayRunner {
+    pub fn new(entries: Vec<TranscriptEntry>) -> Self {
+        Self {
+            entries,
+            served: Mutex::new(HashSet::new()),
+            realtime: false,
+            lenient: false,
+        }
+    }
+
+    /// Delay each response by its recorded `duration_ms` (for demos).
+    pub fn with_realtime(mut self, realtime: bool) -> Self {
+        self.realtime = realtime;
+        self
+    }
+
+    /// Fall back to any recording of the same subcommand, whatever its
+    /// other args (for demos; results may not match the parameters).
+    pub fn with_lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    pub fn from_dir(dir: &Path) -> Result<Self, FriedmanError> {
+        Ok(Self::new(transcript::load_dir(dir)?))
+    }
+
+    fn find(&self, args: &[String]) -> Option<&TranscriptEntry> {
+        let portable = TranscriptEntry::portable_args(args);
+        let tiers: [&dyn Fn(&TranscriptEntry) -> bool; 3] = [
+            &|e| e.args == args,
+            &|e| TranscriptEntry::portable_args(&e.args) == portable,
+            &|e| e.args.iter().take(2).eq(args.iter().take(2)),
+        ];
+        let tiers = if self.lenient { &tiers[..] } else { &tiers[..2] };
+
+        let mut served = self.served.lock().expect("replay lock poisoned");
+        for (tier, matches) in tiers.iter().enumerate() {
+            let candidates: Vec<usize> = (0..self.entries.len())
+                .filter(|&i| matches(&self.entries[i]))
+                .collect();
+            let Some(&last) = candidates.last() else { continue };
+            let index = candidates
+                .into_iter()
+                .find(|i| !served.contains(i))
+                .unwrap_or(last);
+            served.insert(index);
+            if tier == 2 {
+                log::warn!(
+                    "Replaying a recording of `{}` made with different args",
+                    args.iter().take(2).cloned().collect::<Vec<_>>().join(" ")
+                );
+            }
+            return Some(&self.entries[index]);
+        }
+        None
+    }
+}
+
+impl SidecarRunner for ReplayRunner {
+    fn run<'a>(
+        &'a self,
+        job: &'a mut JobHandle,
+        sink: &'a OutputSink,
+        _limits: Limits,
+        args: &'a [String],
+    ) -> RunFuture<'a> {
+        Box::pin(async move {
+            let entry = self.find(args).ok_or_else(|| {
+                FriedmanError::SidecarExec(format!(
+                    "no recorded transcript for `{}`",
+                    args.join(" ")
+                ))
+            })?;
+
+            let lines: Vec<&str> = entry.stderr.lines().collect();
+            let pause = if self.realtime {
+                Duration::from_millis(entry.duration_ms) / (lines.len() as u32 + 1)
+            } else {
+                Duration::ZERO
+            };
+            for line in lines {
+                sleep_or_cancel(job, pause).await?;
+                sink.line(line);
+            }
+            sleep_or_cancel(job, pause).await?;
+            Ok(SidecarOutput {
+                code: entry.code,
+                stdout: entry.stdout.clone(),
+                stderr: entry.stderr.clone(),
+                stdout_file: None,
+            })
+        })
+    }
+}
+
+/// Sleep for `pause` unless the job is cancelled first.
+async fn sleep_or_cancel(job: &mut JobHandle, pause: Duration) -> Result<(), FriedmanError> {
+    if pause.is_zero() {
+        return Ok(());
+    }
+    tokio::select! {
+        _ = tokio::time::sleep(pause) => Ok(()),
+        _ = job.cancelled() => Err(FriedmanError::Cancelled(job.id.clone())),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Recording runner
+// ---------------------------------------------------------------------------
+
+/// Wraps another runner and writes every invocation that produced output
+/// (including non-zero exits) to a transcript directory that
+/// [`ReplayRunner`] can serve later. Cancelled and timed-out runs are not
+/// recorded.
+pub struct RecordingRunner {
+    inner: Arc<dyn SidecarRunner>,
+    dir: PathBuf,
+}
+
+impl RecordingRunner {
+    pub fn new(inner: Arc<dyn SidecarRunner>, dir: PathBuf) -> Self {
+        Self { inner, dir }
+    }
+}
+
+impl SidecarRunner for RecordingRunner {
+    fn run<'a>(
+        &'a self,
+        job: &'a mut JobHandle,
+        sink: &'a OutputSink,
+        limits: Limits,
+        args: &'a [String],
+    ) -> RunFuture<'a> {
+        Box::pin(async move {
+            let started = Instant::now();
+            let output = self.inner.run(job, sink, limits, args).await?;
+            let entry = TranscriptEntry {
+                args: args.to_vec(),
+                code: output.code,
+                // Spooled stdout is copied from its file when writing.
+                stdout: if output.stdout_file.is_some() { String::new() } else { output.stdout.clone() },
+                stderr: output.stderr.clone(),
+                duration_ms: started.elapsed().as_millis() as u64,
+            };
+            let (dir, stdout_file) = (self.dir.clone(), output.stdout_file.clone());
+            let written = tokio::task::spawn_blocking(move || match stdout_file {
+                Some(file) => transcript::write_entry_from_file(&dir, &entry, &file),
+                None => transcript::write_entry(&dir, &entry),
+            })
+            .await;
+            match written {
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => log::warn!("Could not record transcript: {e}"),
+                Err(e) => log::warn!("Could not record transcript: {e}"),
+            }
+            Ok(output)
+        })
+    }
+
+    fn spawns_sidecar(&self) -> bool {
+        self.inner.spawns_sidecar()
+    }
+}
@@ -0,0 +1,196 @@
+use std::cmp::Reverse;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::oneshot;
+
+use super::jobs::JobHandle;
+use crate::error::FriedmanError;
+
+/// Concurrent sidecar processes allowed when `FRIEDMAN_MAX_JOBS` is unset.
+const DEFAULT_MAX_CONCURRENCY: usize = 2;
+
+/// Scheduling priority of a job; higher runs first, ties are FIFO.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    Low,
+    Normal,
+    High,
+}
+
+impl Priority {
+    /// Quick commands (unit-root tests) jump ahead of
+    /// long-running samplers.
+    pub fn for_args(args: &[String]) -> Self {
+        match args.first().map(String::as_str) {
+            Some("test") => Priority::High,
+            Some("bvar") => Priority::Low,
+            _ => Priority::Normal,
+        }
+    }
+}
+
+/// Payload of `friedman://queue/{job_id}` events.
+///
+/// `position` is 1-based while waiting and 0 once the job has started.
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueEvent {
+    pub job_id: String,
+    pub position: usize,
+    pub queued: usize,
+}
+
+struct Waiter {
+    job_id: String,
+    priority: Priority,
+    seq: u64,
+    wake: oneshot::Sender<()>,
+}
+
+struct Queue {
+    max_concurrency: usize,
+    running: usize,
+    next_seq: u64,
+    /// Sorted by priority (highest first), then arrival.
+    waiting: Vec<Waiter>,
+}
+
+/// Bounded queue between the command handlers and the sidecar processes.
+pub struct JobScheduler {
+    queue: Mutex<Queue>,
+}
+
+/// A running slot; dropping it hands the slot to the next waiting job.
+pub struct SlotGuard<'a> {
+    scheduler: &'a JobScheduler,
+    app: AppHandle,
+}
+
+impl Default for JobScheduler {
+    fn default() -> Self {
+        let max_concurrency = std::env::var("FRIEDMAN_MAX_JOBS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|n: &usize| *n > 0)
+            .unwrap_or(DEFAULT_MAX_CONCURRENCY);
+
+        Self {
+            queue: Mutex::new(Queue {
+                max_concurrency,
+                running: 0,
+                next_seq: 0,
+                waiting: Vec::new(),
+            }),
+        }
+    }
+}
+
+impl JobScheduler {
+    pub fn max_concurrency(&self) -> usize {
+        self.queue.lock().expect("queue lock poisoned").max_concurrency
+    }
+
+    /// Change the concurrency limit; raising it starts waiting jobs at once.
+    pub fn set_max_concurrency(&self, app: &AppHandle, max: usize) -> Result<usize, FriedmanError> {
+        if max == 0 {
+            return Err(FriedmanError::InvalidParams(
+                "max concurrency must be at least 1".into(),
+            ));
+        }
+        let mut queue = self.queue.lock().expect("queue lock poisoned");
+        queue.max_concurrency = max;
+        dispatch(app, &mut queue);
+        Ok(max)
+    }
+
+    /// Wait for a free slot. Cancelling the job while it waits removes it
+    /// from the queue and returns [`FriedmanError::Cancelled`].
+    pub async fn acquire(
+        &self,
+        app: &AppHandle,
+        job: &mut JobHandle,
+        priority: Priority,
+    ) -> Result<SlotGuard<'_>, FriedmanError> {
+        let mut rx = {
+            let mut queue = self.queue.lock().expect("queue lock poisoned");
+            if queue.running < queue.max_concurrency && queue.waiting.is_empty() {
+                queue.running += 1;
+                emit(app, &job.id, 0, queue.waiting.len());
+                return Ok(SlotGuard { scheduler: self, app: app.clone() });
+            }
+
+            let (tx, rx) = oneshot::channel();
+            let seq = queue.next_seq;
+            queue.next_seq += 1;
+            let index = queue
+                .waiting
+                .partition_point(|w| (Reverse(w.priority), w.seq) < (Reverse(priority), seq));
+            queue.waiting.insert(
+                index,
+                Waiter { job_id: job.id.clone(), priority, seq, wake: tx },
+            );
+            emit_positions(app, &queue);
+            rx
+        };
+
+        tokio::select! {
+            granted = &mut rx => match granted {
+                Ok(()) => Ok(SlotGuard { scheduler: self, app: app.clone() }),
+                Err(_) => Err(FriedmanError::SidecarExec("job queue closed".into())),
+            },
+            _ = job.cancelled() => {
+                let mut queue = self.queue.lock().expect("queue lock poisoned");
+                queue.waiting.retain(|w| w.job_id != job.id);
+                // The slot may have been handed over just as we were cancelled.
+                if rx.try_recv().is_ok() {
+                    queue.running = queue.running.saturating_sub(1);
+                    dispatch(app, &mut queue);
+                }
+                emit_positions(app, &queue);
+                Err(FriedmanError::Cancelled(job.id.clone()))
+            }
+        }
+    }
+}
+
+impl Drop for SlotGuard<'_> {
+    fn drop(&mut self) {
+        let mut queue = self.scheduler.queue.lock().expect("queue lock poisoned");
+        queue.running = queue.running.saturating_sub(1);
+        dispatch(&self.app, &mut queue);
+    }
+}
+
+/// Start waiting jobs while slots are free.
+fn dispatch(app: &AppHandle, queue: &mut Queue) {
+    let mut started = false;
+    while queue.running < queue.max_concurrency && !queue.waiting.is_empty() {
+        let waiter = queue.waiting.remove(0);
+        // A waiter whose receiver is gone was cancelled mid-handoff.
+        if waiter.wake.send(()).is_ok() {
+            queue.running += 1;
+            emit(app, &waiter.job_id, 0, queue.waiting.len());
+            started = true;
+        }
+    }
+    if started {
+        emit_positions(app, queue);
+    }
+}
+
+fn emit_positions(app: &AppHandle, queue: &Queue) {
+    for (i, waiter) in queue.waiting.iter().enumerate() {
+        emit(app, &waiter.job_id, i + 1, queue.waiting.len());
+    }
+}
+
+fn emit(app: &AppHandle, job_id: &str, position: usize, queued: usize) {
+    let event = QueueEvent {
+        job_id: job_id.to_string(),
+        position,
+        queued,
+    };
+    let _ = app.emit(&format!("friedman://queue/{job_id}"), &event);
+}
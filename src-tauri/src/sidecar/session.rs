@@ -0,0 +1,446 @@
+//! A persistent Julia session that saves the start-up cost of each command.
+//!
+//! Only dev installs that run friedman-cli from its Julia project
+//! ([`SidecarMode::Julia`]) get a session. A packaged [`SidecarMode::Binary`]
+//! cannot serve framed requests, so it always spawns one process per command.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::{mpsc, Mutex};
+
+use super::jobs::{kill_process_tree, new_process_group, JobHandle, JobRegistry};
+use super::protocol;
+use super::resources::{JobResources, ProcessResources, Scratch};
+use super::spill::StdoutBuffer;
+use super::watchdog::{self, Limits};
+use super::{resolve_sidecar, OutputSink, SidecarMode, SidecarOutput};
+use crate::error::FriedmanError;
+use crate::state::AppState;
+
+/// Julia script (inside the sidecar project) that serves framed requests.
+const SERVER_SCRIPT: &str = "server.jl";
+const READY: &str = "FRIEDMAN-READY";
+const RESPONSE: &str = "FRIEDMAN-RESP";
+const STDERR_END: &str = "\u{1e}FRIEDMAN-END";
+
+/// How long Julia may take to load packages and print the ready marker.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+/// Wait before retrying a session that failed to start, doubled for each
+/// further failure in a row up to `MAX_RETRY_DELAY`.
+const RETRY_DELAY: Duration = Duration::from_secs(30);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30 * 60);
+
+/// A long-lived `julia server.jl` process shared by all commands.
+///
+/// Only one request is in flight at a time; callers that find the session
+/// busy fall back to spawning a fresh process. A session that dies is
+/// restarted on the next request, and one that fails to start is retried
+/// with backoff, commands spawning their own process in the meantime.
+#[derive(Default)]
+pub struct SidecarSession {
+    process: Mutex<Option<SessionProcess>>,
+    starting: AtomicBool,
+    backoff: std::sync::Mutex<Backoff>,
+}
+
+/// Failed starts in a row, and when the next start may be tried.
+#[derive(Default)]
+struct Backoff {
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+struct SessionProcess {
+    julia: PathBuf,
+    project_dir: PathBuf,
+    resources: ProcessResources,
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    stderr: mpsc::UnboundedReceiver<String>,
+}
+
+impl SidecarSession {
+    /// Run `args` through the session.
+    ///
+    /// Returns `None` when the session is disabled, backing off after a
+    /// failed start, busy or cannot be started; the caller should then use
+    /// the spawn-per-call path.
+    /// Cancelling the job or exceeding `limits` kills the session, which
+    /// restarts on next use, as does a change of `resources`. The job's
+    /// `scratch` directory is set as its temporary directory.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn try_run(
+        &self,
+        julia: &Path,
+        project_dir: &Path,
+        resources: &ProcessResources,
+        scratch: &Scratch,
+        jobs: &JobRegistry,
+        job: &mut JobHandle,
+        sink: &OutputSink,
+        limits: Limits,
+        args: &[String],
+    ) -> Option<Result<SidecarOutput, FriedmanError>> {
+        if !self.available() {
+            return None;
+        }
+
+        // Wait behind a warm-up in progress, but never queue behind a request.
+        let mut guard = if self.starting.load(Ordering::Acquire) {
+            self.process.lock().await
+        } else {
+            self.process.try_lock().ok()?
+        };
+
+        let alive = match guard.as_mut() {
+            Some(p) => {
+                p.julia == julia
+                    && p.project_dir == project_dir
+                    && p.resources == *resources
+                    && p.is_running()
+            }
+            None => false,
+        };
+        if !alive {
+            *guard = None;
+            *guard = Some(self.start(julia, project_dir, resources).await?);
+        }
+
+        let process = guard.as_mut()?;
+        jobs.set_pid(&job.id, process.child.id());
+        jobs.set_resources(
+            &job.id,
+            JobResources {
+                process: resources.clone(),
+                scratch_dir: Some(scratch.path().to_path_buf()),
+                shared_session: true,
+            },
+        );
+
+        let env = scratch.env();
+        let mut stderr = Vec::new();
+        let interrupted = tokio::select! {
+            result = process.request(sink, &mut stderr, args, &env) => Ok(result),
+            _ = job.cancelled() => Err(None),
+            expired = watchdog::expired(limits, &sink.activity) => Err(Some(expired)),
+        };
+
+        let result = match interrupted {
+            Ok(result) => result,
+            Err(expired) => {
+                if let Some(pid) = process.child.id() {
+                    kill_process_tree(pid);
+                }
+                let process = guard.take()?;
+                let output = process.into_crash_output(stderr).await;
+                return Some(Err(match expired {
+                    Some(expired) => expired.into_error(output.stderr),
+                    None => FriedmanError::Cancelled(job.id.clone()),
+                }));
+            }
+        };
+
+        match result {
+            Ok(output) => Some(Ok(output)),
+            Err(e) => {
+                log::warn!("Sidecar session crashed, restarting on next request: {e}");
+                let process = guard.take()?;
+                Some(Ok(process.into_crash_output(stderr).await))
+            }
+        }
+    }
+
+    /// Start the session ahead of the first command.
+    async fn warm_up(&self, julia: &Path, project_dir: &Path, resources: &ProcessResources) {
+        if !self.available() {
+            return;
+        }
+
+        self.starting.store(true, Ordering::Release);
+        let mut guard = self.process.lock().await;
+        if guard.is_none() {
+            *guard = self.start(julia, project_dir, resources).await;
+        }
+        self.starting.store(false, Ordering::Release);
+    }
+
+    /// Whether the session is enabled and not waiting out a failed start.
+    fn available(&self) -> bool {
+        let backoff = self.backoff.lock().expect("session backoff lock poisoned");
+        session_enabled() && backoff.retry_at.map_or(true, |at| Instant::now() >= at)
+    }
+
+    /// Start a process, backing off after a failure.
+    async fn start(
+        &self,
+        julia: &Path,
+        project_dir: &Path,
+        resources: &ProcessResources,
+    ) -> Option<SessionProcess> {
+        let result = SessionProcess::start(julia, project_dir, resources).await;
+        let mut backoff = self.backoff.lock().expect("session backoff lock poisoned");
+        match result {
+            Ok(process) => {
+                *backoff = Backoff::default();
+                Some(process)
+            }
+            Err(e) => {
+                let delay = RETRY_DELAY
+                    .saturating_mul(1 << backoff.failures.min(16))
+                    .min(MAX_RETRY_DELAY);
+                backoff.failures += 1;
+                backoff.retry_at = Some(Instant::now() + delay);
+                log::warn!(
+                    "Sidecar session unavailable, spawning per command for {}s: {e}",
+                    delay.as_secs()
+                );
+                None
+            }
+        }
+    }
+}
+
+/// Start the Julia session in the background if running in dev (Julia) mode.
+pub fn warm_up(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Ok(SidecarMode::Julia { julia, project_dir, .. }) = resolve_sidecar(&app) {
+            let state = app.state::<AppState>();
+            let settings = state.settings.lock().expect("settings lock poisoned").resources.clone();
+            let resources = ProcessResources::effective(&settings);
+            state.session.warm_up(&julia, &project_dir, &resources).await;
+        }
+    });
+}
+
+/// `FRIEDMAN_SIDECAR_SESSION=0` forces the spawn-per-call path.
+fn session_enabled() -> bool {
+    !matches!(
+        std::env::var("FRIEDMAN_SIDECAR_SESSION").as_deref(),
+        Ok("0") | Ok("false") | Ok("off")
+    )
+}
+
+impl SessionProcess {
+    async fn start(
+        julia: &Path,
+        project_dir: &Path,
+        resources: &ProcessResources,
+    ) -> Result<Self, FriedmanError> {
+        let script = project_dir.join(SERVER_SCRIPT);
+        if !script.exists() {
+            return Err(FriedmanError::SidecarExec(format!(
+                "session script not found at {}",
+                script.display()
+            )));
+        }
+
+        let mut cmd = Command::new(julia);
+        cmd.arg(format!("--project={}", project_dir.display()))
+            .arg("--startup-file=no")
+            .arg(&script)
+            .env(protocol::PROTOCOL_ENV, protocol::PROTOCOL)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        new_process_group(&mut cmd);
+        resources.apply(&mut cmd);
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| FriedmanError::SpawnFailed(format!("Failed to spawn julia: {e}")))?;
+
+        let stdin = child.stdin.take();
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        let (Some(stdin), Some(stdout), Some(stderr)) = (stdin, stdout, stderr) else {
+            return Err(FriedmanError::SidecarExec("Failed to capture session pipes".into()));
+        };
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut process = Self {
+            julia: julia.to_path_buf(),
+            project_dir: project_dir.to_path_buf(),
+            resources: resources.clone(),
+            child,
+            stdin,
+            stdout: BufReader::new(stdout),
+            stderr: rx,
+        };
+
+        // Julia may print package loading noise before the ready marker.
+        let ready = async {
+            let mut line = String::new();
+            loop {
+                line.clear();
+                if process.stdout.read_line(&mut line).await? == 0 {
+                    return Ok(false);
+                }
+                if line.trim_end() == READY {
+                    return Ok::<_, FriedmanError>(true);
+                }
+            }
+        };
+        match tokio::time::timeout(STARTUP_TIMEOUT, ready).await {
+            Ok(Ok(true)) => {}
+            Ok(Ok(false)) => {
+                let output = process.into_crash_output(Vec::new()).await;
+                return Err(FriedmanError::SidecarExit {
+                    code: output.code,
+                    stderr: output.stderr,
+                });
+            }
+            Ok(Err(e)) => return Err(e),
+            Err(_) => {
+                if let Some(pid) = process.child.id() {
+                    kill_process_tree(pid);
+                }
+                return Err(FriedmanError::SidecarExec(format!(
+                    "session did not start within {}s",
+                    STARTUP_TIMEOUT.as_secs()
+                )));
+            }
+        }
+
+        log::info!("Sidecar session started ({})", julia.display());
+        Ok(process)
+    }
+
+    fn is_running(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    /// Send one request, run with the environment variables in `env` set;
+    /// stderr lines are collected into `stderr` as they arrive so they
+    /// survive the request being abandoned.
+    async fn request(
+        &mut self,
+        sink: &OutputSink,
+        stderr: &mut Vec<String>,
+        args: &[String],
+        env: &[(String, String)],
+    ) -> Result<SidecarOutput, FriedmanError> {
+        // Drop anything the session printed between requests.
+        while self.stderr.try_recv().is_ok() {}
+
+        let env: Vec<String> = env.iter().map(|(k, v)| format!("{k}={v}")).collect();
+        let mut frame = format!("FRIEDMAN-REQ {} {}\n", args.len(), env.len()).into_bytes();
+        for field in args.iter().chain(&env) {
+            frame.extend_from_slice(format!("{}\n", field.len()).as_bytes());
+            frame.extend_from_slice(field.as_bytes());
+            frame.push(b'\n');
+        }
+        self.stdin.write_all(&frame).await?;
+        self.stdin.flush().await?;
+
+        // Forward stderr while waiting for the framed response; the request
+        // is complete once both the response and the stderr marker arrived.
+        let response = read_response(&mut self.stdout);
+        tokio::pin!(response);
+        let mut stdout = None;
+        let mut stderr_done = false;
+
+        while stdout.is_none() || !stderr_done {
+            tokio::select! {
+                result = &mut response, if stdout.is_none() => {
+                    stdout = Some(result?);
+                    sink.touch();
+                }
+                line = self.stderr.recv(), if !stderr_done => match line {
+                    Some(line) if line == STDERR_END => stderr_done = true,
+                    Some(line) => {
+                        sink.line(&line);
+                        stderr.push(line);
+                    }
+                    None => stderr_done = true,
+                },
+            }
+        }
+
+        let (code, stdout, stdout_file) = stdout.unwrap_or_default();
+        Ok(SidecarOutput {
+            code,
+            stdout,
+            stderr: stderr.join("\n"),
+            stdout_file,
+        })
+    }
+
+    /// Reap a dead (or misbehaving) session and report what it left behind,
+    /// after the `stderr` lines already read for the current request.
+    async fn into_crash_output(mut self, mut stderr: Vec<String>) -> SidecarOutput {
+        let _ = self.child.start_kill();
+        let code = match self.child.wait().await {
+            Ok(status) => status.code().unwrap_or(-1),
+            Err(_) => -1,
+        };
+
+        while let Some(line) = self.stderr.recv().await {
+            if line != STDERR_END {
+                stderr.push(line);
+            }
+        }
+
+        SidecarOutput {
+            code: if code == 0 { -1 } else { code },
+            stdout: String::new(),
+            stderr: stderr.join("\n"),
+            stdout_file: None,
+        }
+    }
+}
+
+/// Read the next `FRIEDMAN-RESP` header and the stdout it frames, spooling
+/// large responses to disk.
+async fn read_response(
+    stdout: &mut BufReader<ChildStdout>,
+) -> Result<(i32, String, Option<PathBuf>), FriedmanError> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if stdout.read_line(&mut line).await? == 0 {
+            return Err(FriedmanError::SidecarExec("session closed stdout".into()));
+        }
+        let mut parts = line.split_whitespace();
+        if parts.next() != Some(RESPONSE) {
+            log::debug!("Ignoring sidecar session output: {}", line.trim_end());
+            continue;
+        }
+        let code = parts.next().and_then(|s| s.parse().ok());
+        let len = parts.next().and_then(|s| s.parse().ok());
+        let (Some(code), Some(len)) = (code, len) else {
+            return Err(FriedmanError::SidecarExec(format!(
+                "malformed session response: {}",
+                line.trim_end()
+            )));
+        };
+
+        let mut buf = StdoutBuffer::default();
+        let mut chunk = vec![0u8; 64 * 1024];
+        let mut remaining: usize = len;
+        while remaining > 0 {
+            let n = remaining.min(chunk.len());
+            stdout.read_exact(&mut chunk[..n]).await?;
+            buf.extend(&chunk[..n])?;
+            remaining -= n;
+        }
+        let (text, file) = buf.finish()?;
+        return Ok((code, text, file));
+    }
+}
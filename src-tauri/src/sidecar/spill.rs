@@ -0,0 +1,480 @@
+//! Large sidecar output.
+//!
+//! Stdout beyond [`spill_threshold`] bytes is spooled to a file instead of
+//! memory and decoded with an incremental JSON reader. Any array whose JSON
+//! text exceeds the threshold is written to disk, one element per line, and
+//! replaced in the result by a [`SpilledArray`] handle:
+//!
+//! ```text
+//! {"$spill": {"handle": "spill-…", "length": 250000, "bytes": 412000000}}
+//! ```
+//!
+//! The frontend pages through it with `read_result_page` and frees it with
+//! `release_result`.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::error::FriedmanError;
+
+/// Key marking a spilled array inside a result.
+pub const SPILL_KEY: &str = "$spill";
+
+/// Default for [`spill_threshold`]: 8 MiB of JSON text.
+const DEFAULT_THRESHOLD: u64 = 8 * 1024 * 1024;
+
+/// Spilled arrays kept before the oldest are deleted.
+const MAX_SPILLS: usize = 32;
+
+/// Size above which stdout is spooled to disk and arrays are spilled.
+/// Override with `FRIEDMAN_SPILL_THRESHOLD_MB`.
+pub fn spill_threshold() -> u64 {
+    static THRESHOLD: OnceLock<u64> = OnceLock::new();
+    *THRESHOLD.get_or_init(|| {
+        std::env::var("FRIEDMAN_SPILL_THRESHOLD_MB")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|mb| *mb > 0)
+            .map_or(DEFAULT_THRESHOLD, |mb| mb * 1024 * 1024)
+    })
+}
+
+/// Directory for spooled stdout and spilled arrays of this app instance.
+pub fn spill_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("friedman-spill-{}", std::process::id()))
+}
+
+// ---------------------------------------------------------------------------
+// Spill store
+// ---------------------------------------------------------------------------
+
+/// Placeholder for an array written to disk.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SpilledArray {
+    pub handle: String,
+    /// Number of elements.
+    pub length: usize,
+    /// Size of the array's JSON text on disk.
+    pub bytes: u64,
+}
+
+impl SpilledArray {
+    /// The `{"$spill": {...}}` value standing in for the array.
+    pub fn to_value(&self) -> Value {
+        let mut map = Map::new();
+        map.insert(SPILL_KEY.into(), serde_json::to_value(self).unwrap_or_default());
+        Value::Object(map)
+    }
+}
+
+/// One page of a spilled array, as returned by `read_result_page`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ResultPage {
+    pub handle: String,
+    pub offset: usize,
+    /// Total number of elements in the array.
+    pub length: usize,
+    pub items: Vec<Value>,
+}
+
+struct SpillEntry {
+    path: PathBuf,
+    /// Byte offset of every element's line.
+    offsets: Vec<u64>,
+    bytes: u64,
+    seq: u64,
+}
+
+/// Spilled arrays of finished commands, by handle.
+pub struct SpillStore {
+    dir: PathBuf,
+    threshold: u64,
+    entries: Mutex<HashMap<String, SpillEntry>>,
+}
+
+impl Default for SpillStore {
+    fn default() -> Self {
+        Self::new(spill_dir(), spill_threshold())
+    }
+}
+
+impl SpillStore {
+    pub fn new(dir: PathBuf, threshold: u64) -> Self {
+        Self {
+            dir,
+            threshold,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn threshold(&self) -> u64 {
+        self.threshold
+    }
+
+    /// Read `limit` elements starting at `offset`.
+    pub fn page(&self, handle: &str, offset: usize, limit: usize) -> Result<ResultPage, FriedmanError> {
+        let (path, start, length) = {
+            let entries = self.entries.lock().expect("spill lock poisoned");
+            let entry = entries
+                .get(handle)
+                .ok_or_else(|| FriedmanError::InvalidParams(format!("Unknown result handle: {handle}")))?;
+            (entry.path.clone(), entry.offsets.get(offset).copied(), entry.offsets.len())
+        };
+
+        let mut items = Vec::new();
+        if let Some(start) = start {
+            let mut file = BufReader::new(File::open(&path)?);
+            file.seek(SeekFrom::Start(start))?;
+            let mut line = String::new();
+            while items.len() < limit.min(length - offset) {
+                line.clear();
+                if file.read_line(&mut line)? == 0 {
+                    break;
+                }
+                items.push(
+                    serde_json::from_str(&line)
+                        .map_err(|e| FriedmanError::JsonParse(format!("spilled element: {e}")))?,
+                );
+            }
+        }
+        Ok(ResultPage {
+            handle: handle.to_string(),
+            offset,
+            length,
+            items,
+        })
+    }
+
+    /// Delete a spilled array. Unknown handles are ignored.
+    pub fn release(&self, handle: &str) {
+        let removed = self.entries.lock().expect("spill lock poisoned").remove(handle);
+        if let Some(entry) = removed {
+            let _ = fs::remove_file(entry.path);
+        }
+    }
+
+    /// Delete every spilled array (on app exit).
+    pub fn clear(&self) {
+        self.entries.lock().expect("spill lock poisoned").clear();
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+
+    fn writer(&self) -> Result<SpillWriter, FriedmanError> {
+        fs::create_dir_all(&self.dir)?;
+        let handle = format!("spill-{}", uuid::Uuid::new_v4());
+        let path = self.dir.join(format!("{handle}.ndjson"));
+        Ok(SpillWriter {
+            file: BufWriter::new(File::create(&path)?),
+            handle,
+            path,
+            offsets: Vec::new(),
+            bytes: 0,
+        })
+    }
+
+    fn register(&self, writer: SpillWriter) -> Result<SpilledArray, FriedmanError> {
+        let SpillWriter {
+            mut file,
+            handle,
+            path,
+            offsets,
+            bytes,
+        } = writer;
+        file.flush()?;
+        let spilled = SpilledArray {
+            handle: handle.clone(),
+            length: offsets.len(),
+            bytes,
+        };
+
+        let mut entries = self.entries.lock().expect("spill lock poisoned");
+        let seq = entries.values().map(|e| e.seq + 1).max().unwrap_or(0);
+        entries.insert(handle, SpillEntry { path, offsets, bytes, seq });
+        while entries.len() > MAX_SPILLS {
+            let Some(oldest) = entries.iter().min_by_key(|(_, e)| e.seq).map(|(k, _)| k.clone()) else {
+                break;
+            };
+            if let Some(entry) = entries.remove(&oldest) {
+                log::info!("Dropping spilled result {oldest} ({} bytes)", entry.bytes);
+                let _ = fs::remove_file(entry.path);
+            }
+        }
+        Ok(spilled)
+    }
+}
+
+struct SpillWriter {
+    file: BufWriter<File>,
+    handle: String,
+    path: PathBuf,
+    offsets: Vec<u64>,
+    bytes: u64,
+}
+
+impl SpillWriter {
+    fn push(&mut self, value: &Value) -> Result<(), FriedmanError> {
+        let line = serde_json::to_vec(value).map_err(|e| FriedmanError::JsonParse(e.to_string()))?;
+        self.offsets.push(self.bytes);
+        self.file.write_all(&line)?;
+        self.file.write_all(b"\n")?;
+        self.bytes += line.len() as u64 + 1;
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Stdout spooling
+// ---------------------------------------------------------------------------
+
+/// Collects a child's stdout in memory, moving it to a file in `dir` once
+/// it grows beyond `limit` bytes.
+pub struct StdoutBuffer {
+    memory: Vec<u8>,
+    file: Option<(PathBuf, BufWriter<File>)>,
+    dir: PathBuf,
+    limit: u64,
+}
+
+impl Default for StdoutBuffer {
+    fn default() -> Self {
+        Self::new(spill_dir(), spill_threshold())
+    }
+}
+
+impl StdoutBuffer {
+    pub fn new(dir: PathBuf, limit: u64) -> Self {
+        Self {
+            memory: Vec::new(),
+            file: None,
+            dir,
+            limit,
+        }
+    }
+
+    pub fn extend(&mut self, chunk: &[u8]) -> io::Result<()> {
+        if let Some((_, file)) = self.file.as_mut() {
+            return file.write_all(chunk);
+        }
+        self.memory.extend_from_slice(chunk);
+        if self.memory.len() as u64 > self.limit {
+            fs::create_dir_all(&self.dir)?;
+            let path = self.dir.join(format!("stdout-{}.txt", uuid::Uuid::new_v4()));
+            let mut file = BufWriter::new(File::create(&path)?);
+            file.write_all(&self.memory)?;
+            self.memory = Vec::new();
+            self.file = Some((path, file));
+        }
+        Ok(())
+    }
+
+    /// The collected text, or the spool file holding it.
+    pub fn finish(mut self) -> io::Result<(String, Option<PathBuf>)> {
+        match self.file.take() {
+            Some((path, mut file)) => {
+                file.flush()?;
+                Ok((String::new(), Some(path)))
+            }
+            None => Ok((String::from_utf8_lossy(&self.memory).to_string(), None)),
+        }
+    }
+}
+
+impl Drop for StdoutBuffer {
+    /// Output abandoned before [`StdoutBuffer::finish`] is never parsed.
+    fn drop(&mut self) {
+        if let Some((path, _)) = self.file.take() {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Incremental JSON reader
+// ---------------------------------------------------------------------------
+
+/// Reads one JSON value at a time from a byte stream without buffering the
+/// whole document, spilling arrays larger than the store's threshold.
+pub struct JsonReader<'a, R> {
+    input: R,
+    /// Bytes consumed so far.
+    pos: u64,
+    spills: Option<&'a SpillStore>,
+}
+
+impl<'a, R: BufRead> JsonReader<'a, R> {
+    /// Without `spills`, every array is kept in memory.
+    pub fn new(input: R, spills: Option<&'a SpillStore>) -> Self {
+        Self { input, pos: 0, spills }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.input
+    }
+
+    /// Parse the next value, leaving the reader right after it.
+    pub fn next_value(&mut self) -> Result<Value, FriedmanError> {
+        self.skip_whitespace()?;
+        match self.peek()? {
+            Some(b'{') => self.object(),
+            Some(b'[') => self.array(),
+            Some(b'"') => Ok(Value::String(self.string()?)),
+            Some(_) => self.scalar(),
+            None => Err(self.error("unexpected end of output")),
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, FriedmanError> {
+        self.bump();
+        let mut map = Map::new();
+        self.skip_whitespace()?;
+        if self.peek()? == Some(b'}') {
+            self.bump();
+            return Ok(Value::Object(map));
+        }
+        loop {
+            self.skip_whitespace()?;
+            if self.peek()? != Some(b'"') {
+                return Err(self.error("expected an object key"));
+            }
+            let key = self.string()?;
+            self.skip_whitespace()?;
+            self.expect(b':')?;
+            let value = self.next_value()?;
+            map.insert(key, value);
+            self.skip_whitespace()?;
+            match self.next_byte()? {
+                Some(b',') => continue,
+                Some(b'}') => return Ok(Value::Object(map)),
+                _ => return Err(self.error("expected `,` or `}`")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, FriedmanError> {
+        self.bump();
+        let start = self.pos;
+        let mut items = Vec::new();
+        let mut spill: Option<SpillWriter> = None;
+
+        self.skip_whitespace()?;
+        if self.peek()? == Some(b']') {
+            self.bump();
+            return Ok(Value::Array(items));
+        }
+        loop {
+            let value = self.next_value()?;
+            match spill.as_mut() {
+                Some(writer) => writer.push(&value)?,
+                None => {
+                    items.push(value);
+                    if let Some(store) = self.spills.filter(|s| self.pos - start > s.threshold) {
+                        let mut writer = store.writer()?;
+                        for item in items.drain(..) {
+                            writer.push(&item)?;
+                        }
+                        spill = Some(writer);
+                    }
+                }
+            }
+            self.skip_whitespace()?;
+            match self.next_byte()? {
+                Some(b',') => continue,
+                Some(b']') => break,
+                _ => return Err(self.error("expected `,` or `]`")),
+            }
+        }
+
+        match (spill, self.spills) {
+            (Some(writer), Some(store)) => Ok(store.register(writer)?.to_value()),
+            _ => Ok(Value::Array(items)),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, FriedmanError> {
+        let mut raw = Vec::new();
+        raw.push(self.next_byte()?.unwrap_or(b'"'));
+        let mut escape = false;
+        loop {
+            let Some(b) = self.next_byte()? else {
+                return Err(self.error("unterminated string"));
+            };
+            raw.push(b);
+            match b {
+                _ if escape => escape = false,
+                b'\\' => escape = true,
+                b'"' => break,
+                _ => {}
+            }
+        }
+        serde_json::from_slice(&raw).map_err(|e| self.error(&e.to_string()))
+    }
+
+    /// Numbers, `true`, `false` and `null`.
+    fn scalar(&mut self) -> Result<Value, FriedmanError> {
+        let mut raw = Vec::new();
+        while let Some(b) = self.peek()? {
+            if !(b.is_ascii_alphanumeric() || matches!(b, b'-' | b'+' | b'.')) {
+                break;
+            }
+            raw.push(b);
+            self.bump();
+        }
+        if raw.is_empty() {
+            return Err(self.error("expected a JSON value"));
+        }
+        serde_json::from_slice(&raw).map_err(|e| self.error(&e.to_string()))
+    }
+
+    fn skip_whitespace(&mut self) -> Result<(), FriedmanError> {
+        while let Some(b) = self.peek()? {
+            if !b.is_ascii_whitespace() {
+                break;
+            }
+            self.bump();
+        }
+        Ok(())
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), FriedmanError> {
+        match self.next_byte()? {
+            Some(b) if b == byte => Ok(()),
+            _ => Err(self.error(&format!("expected `{}`", byte as char))),
+        }
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>, FriedmanError> {
+        Ok(self.input.fill_buf()?.first().copied())
+    }
+
+    fn bump(&mut self) {
+        self.input.consume(1);
+        self.pos += 1;
+    }
+
+    fn next_byte(&mut self) -> Result<Option<u8>, FriedmanError> {
+        let byte = self.peek()?;
+        if byte.is_some() {
+            self.bump();
+        }
+        Ok(byte)
+    }
+
+    fn error(&self, message: &str) -> FriedmanError {
+        FriedmanError::JsonParse(format!("{message} at byte {}", self.pos))
+    }
+}
+
+/// Remove a spool file once it has been decoded.
+pub struct SpoolGuard<'a>(pub &'a Path);
+
+impl Drop for SpoolGuard<'_> {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(self.0);
+    }
+}
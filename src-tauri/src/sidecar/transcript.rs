@@ -0,0 +1,159 @@
+use std::fs::{self, File};
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::FriedmanError;
+
+/// One recorded sidecar invocation, stored as a JSON file in a transcript
+/// directory. Files are replayed in file-name order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptEntry {
+    /// Full CLI args, including `--format=json`.
+    pub args: Vec<String>,
+    pub code: i32,
+    pub stdout: String,
+    pub stderr: String,
+    #[serde(default)]
+    pub duration_ms: u64,
+}
+
+impl TranscriptEntry {
+    /// `args` with path-like arguments reduced to their file name, so a
+    /// transcript recorded on one machine matches on another.
+    pub fn portable_args(args: &[String]) -> Vec<String> {
+        args.iter()
+            .map(|arg| {
+                if arg.contains(['/', '\\']) && !arg.starts_with("--") {
+                    Path::new(arg)
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_else(|| arg.clone())
+                } else {
+                    arg.clone()
+                }
+            })
+            .collect()
+    }
+}
+
+/// Write `entry` to `dir` as `<unix-millis>-<seq>-<group>-<subcommand>.json`,
+/// so file-name order is recording order.
+pub fn write_entry(dir: &Path, entry: &TranscriptEntry) -> Result<PathBuf, FriedmanError> {
+    let path = entry_path(dir, entry)?;
+    let text = serde_json::to_string_pretty(entry)
+        .map_err(|e| FriedmanError::JsonParse(e.to_string()))?;
+    fs::write(&path, text)?;
+    Ok(path)
+}
+
+/// Like [`write_entry`], but with stdout copied from the spool file
+/// `stdout` in chunks instead of `entry.stdout`, so large outputs are never
+/// held in memory. Invalid UTF-8 is replaced, as in the decoded output.
+pub fn write_entry_from_file(dir: &Path, entry: &TranscriptEntry, stdout: &Path) -> Result<PathBuf, FriedmanError> {
+    let path = entry_path(dir, entry)?;
+    let args = serde_json::to_string(&entry.args).map_err(|e| FriedmanError::JsonParse(e.to_string()))?;
+    let stderr = serde_json::to_string(&entry.stderr).map_err(|e| FriedmanError::JsonParse(e.to_string()))?;
+    let mut out = BufWriter::new(File::create(&path)?);
+    write!(
+        out,
+        "{{\n  \"args\": {args},\n  \"code\": {},\n  \"stderr\": {stderr},\n  \"duration_ms\": {},\n  \"stdout\": \"",
+        entry.code, entry.duration_ms
+    )?;
+    copy_escaped(File::open(stdout)?, &mut out)?;
+    out.write_all(b"\"\n}\n")?;
+    out.flush()?;
+    Ok(path)
+}
+
+fn entry_path(dir: &Path, entry: &TranscriptEntry) -> Result<PathBuf, FriedmanError> {
+    static SEQ: AtomicU64 = AtomicU64::new(0);
+
+    fs::create_dir_all(dir)?;
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let command: Vec<&str> = entry
+        .args
+        .iter()
+        .take(2)
+        .map(String::as_str)
+        .filter(|a| !a.starts_with('-'))
+        .collect();
+    let name = format!(
+        "{millis:013}-{:04}-{}.json",
+        SEQ.fetch_add(1, Ordering::Relaxed),
+        command.join("-")
+    );
+
+    Ok(dir.join(name))
+}
+
+/// Copy `input` into `out` as the body of a JSON string.
+fn copy_escaped(mut input: impl Read, out: &mut impl Write) -> Result<(), FriedmanError> {
+    let mut buf = vec![0u8; 64 * 1024];
+    // Bytes of a UTF-8 sequence cut off by the previous read.
+    let mut carry = 0;
+    loop {
+        let n = input.read(&mut buf[carry..])?;
+        let end = carry + n;
+        let mut chunk = &buf[..end];
+        carry = 0;
+        while !chunk.is_empty() {
+            match std::str::from_utf8(chunk) {
+                Ok(text) => {
+                    escape_into(text, out)?;
+                    break;
+                }
+                Err(e) => {
+                    let (valid, rest) = chunk.split_at(e.valid_up_to());
+                    escape_into(std::str::from_utf8(valid).unwrap_or_default(), out)?;
+                    match e.error_len() {
+                        None if n > 0 => {
+                            carry = rest.len();
+                            break;
+                        }
+                        len => {
+                            out.write_all("\u{fffd}".as_bytes())?;
+                            chunk = &rest[len.unwrap_or(rest.len())..];
+                        }
+                    }
+                }
+            }
+        }
+        if n == 0 {
+            return Ok(());
+        }
+        buf.copy_within(end - carry..end, 0);
+    }
+}
+
+fn escape_into(text: &str, out: &mut impl Write) -> Result<(), FriedmanError> {
+    let quoted = serde_json::to_string(text).map_err(|e| FriedmanError::JsonParse(e.to_string()))?;
+    out.write_all(&quoted.as_bytes()[1..quoted.len() - 1])?;
+    Ok(())
+}
+
+/// Read every `*.json` entry in `dir`, sorted by file name.
+pub fn load_dir(dir: &Path) -> Result<Vec<TranscriptEntry>, FriedmanError> {
+    let mut files: Vec<_> = fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+
+    files
+        .iter()
+        .map(|path| {
+            let text = fs::read_to_string(path)?;
+            serde_json::from_str(&text).map_err(|e| {
+                FriedmanError::JsonParse(format!("transcript {}: {e}", path.display()))
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::error::FriedmanError;
+
+/// Wall-clock and inactivity limits, in seconds. `0` disables a limit.
+///
+/// Defaults can be overridden with `FRIEDMAN_TIMEOUT_<FAMILY>` (e.g.
+/// `FRIEDMAN_TIMEOUT_BVAR=7200`), `FRIEDMAN_TIMEOUT_DEFAULT` and
+/// `FRIEDMAN_IDLE_TIMEOUT`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeoutSettings {
+    /// Per command group (`test`, `bvar`, ...).
+    pub families: BTreeMap<String, u64>,
+    /// Used for groups without an entry in `families`.
+    pub default_secs: u64,
+    /// Kill a sidecar that has written nothing to stdout/stderr for this long.
+    pub idle_secs: u64,
+}
+
+impl Default for TimeoutSettings {
+    fn default() -> Self {
+        let families = [
+            ("test", 10 * 60),
+            ("arima", 30 * 60),
+            ("bvar", 6 * 60 * 60),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect();
+
+        Self {
+            families,
+            default_secs: 60 * 60,
+            idle_secs: 15 * 60,
+        }
+    }
+}
+
+impl TimeoutSettings {
+    /// Defaults with environment overrides applied.
+    pub fn from_env() -> Self {
+        let mut settings = Self::default();
+        for (key, value) in std::env::vars() {
+            let Some(family) = key.strip_prefix("FRIEDMAN_TIMEOUT_") else {
+                continue;
+            };
+            let Ok(secs) = value.parse() else {
+                log::warn!("Ignoring non-numeric {key}={value}");
+                continue;
+            };
+            match family {
+                "DEFAULT" => settings.default_secs = secs,
+                _ => {
+                    settings.families.insert(family.to_lowercase(), secs);
+                }
+            }
+        }
+        if let Some(secs) = std::env::var("FRIEDMAN_IDLE_TIMEOUT")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            settings.idle_secs = secs;
+        }
+        settings
+    }
+
+    pub fn limits_for(&self, args: &[String]) -> Limits {
+        let total = args
+            .first()
+            .and_then(|family| self.families.get(family))
+            .copied()
+            .unwrap_or(self.default_secs);
+        Limits {
+            total: (total > 0).then(|| Duration::from_secs(total)),
+            idle: (self.idle_secs > 0).then(|| Duration::from_secs(self.idle_secs)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Limits {
+    pub total: Option<Duration>,
+    pub idle: Option<Duration>,
+}
+
+/// Last time a job produced output.
+#[derive(Clone)]
+pub struct Activity(Arc<Mutex<Instant>>);
+
+impl Default for Activity {
+    fn default() -> Self {
+        Self(Arc::new(Mutex::new(Instant::now())))
+    }
+}
+
+impl Activity {
+    pub fn touch(&self) {
+        *self.0.lock().expect("activity lock poisoned") = Instant::now();
+    }
+
+    fn last(&self) -> Instant {
+        *self.0.lock().expect("activity lock poisoned")
+    }
+}
+
+/// Which limit a job ran into.
+#[derive(Debug, Clone, Copy)]
+pub struct Expired {
+    pub idle: bool,
+    pub seconds: u64,
+}
+
+impl Expired {
+    pub fn into_error(self, stderr: String) -> FriedmanError {
+        FriedmanError::Timeout {
+            seconds: self.seconds,
+            reason: if self.idle {
+                "no output from sidecar".into()
+            } else {
+                "command time limit reached".into()
+            },
+            stderr,
+        }
+    }
+}
+
+/// Completes when `limits` are exceeded; never completes if both are disabled.
+pub async fn expired(limits: Limits, activity: &Activity) -> Expired {
+    let started = Instant::now();
+    loop {
+        let deadline = limits.total.map(|t| started + t);
+        let idle_deadline = limits.idle.map(|t| activity.last() + t);
+
+        let next = match (deadline, idle_deadline) {
+            (Some(a), Some(b)) => a.min(b),
+            (Some(a), None) | (None, Some(a)) => a,
+            (None, None) => std::future::pending().await,
+        };
+        tokio::time::sleep_until(next).await;
+
+        let now = Instant::now();
+        if let (Some(deadline), Some(total)) = (deadline, limits.total) {
+            if now >= deadline {
+                return Expired { idle: false, seconds: total.as_secs() };
+            }
+        }
+        if let Some(idle) = limits.idle {
+            // Output may have arrived while we slept; re-check against it.
+            if now >= activity.last() + idle {
+                return Expired { idle: true, seconds: idle.as_secs() };
+            }
+        }
+    }
+}
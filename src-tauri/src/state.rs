@@ -1,24 +1,93 @@
 use std::collections::HashMap;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
+
+use crate::dataset::columnar::FieldInfo;
+use crate::dataset::delimited::CsvDialect;
+use crate::dataset::period::TimeIndex;
+use crate::dataset::preview::TableCache;
+use crate::dataset::profile::ColumnProfile;
+use crate::dataset::spreadsheet::SheetSelection;
+use crate::dataset::transform::Lineage;
+use crate::dataset::ColumnMeta;
+use crate::settings::Settings;
+use crate::sidecar::capabilities::SidecarInfo;
+use crate::sidecar::jobs::JobRegistry;
+use crate::sidecar::runner::{self, SidecarRunner};
+use crate::sidecar::scheduler::JobScheduler;
+use crate::sidecar::watchdog::TimeoutSettings;
+use crate::sidecar::session::SidecarSession;
+use crate::sidecar::spill::SpillStore;
 
 pub struct AppState {
     /// Loaded datasets keyed by an ID
     pub datasets: Mutex<HashMap<String, DatasetInfo>>,
+    /// Parsed rows of recently used datasets, for previews and exports
+    pub tables: TableCache,
+    /// Long-lived Julia process used in dev mode
+    pub session: SidecarSession,
+    /// Running and recently finished sidecar invocations
+    pub jobs: JobRegistry,
+    /// Queue limiting how many sidecar processes run at once
+    pub scheduler: JobScheduler,
+    /// Per-command-family time limits and the idle watchdog
+    pub timeouts: Mutex<TimeoutSettings>,
+    /// Version and supported commands of the resolved friedman-cli, once probed
+    pub sidecar_info: Mutex<Option<SidecarInfo>>,
+    /// Persisted user settings, loaded during app setup
+    pub settings: Mutex<Settings>,
+    /// Executes sidecar invocations (real process, fake or replay)
+    pub runner: Arc<dyn SidecarRunner>,
+    /// Result arrays too large to return in one piece, paged from disk
+    pub spills: SpillStore,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct DatasetInfo {
     pub id: String,
     pub name: String,
+    /// File handed to the sidecar: the original, or a normalized CSV copy.
     pub path: String,
     pub columns: Vec<String>,
     pub row_count: usize,
+    /// The file the user loaded, when `path` is a normalized copy of it.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Detected dialect of a delimited text file.
+    #[serde(default)]
+    pub dialect: Option<CsvDialect>,
+    /// Sheet, cell range and header row read from a spreadsheet.
+    #[serde(default)]
+    pub sheet: Option<SheetSelection>,
+    /// Schema of a Parquet or Arrow file, including the columns left out.
+    #[serde(default)]
+    pub schema: Option<Vec<FieldInfo>>,
+    /// Variable labels, value labels and formats from Stata or SPSS files.
+    #[serde(default)]
+    pub column_meta: Vec<ColumnMeta>,
+    /// Inferred type and summary statistics of each column, in column order.
+    #[serde(default)]
+    pub column_profiles: Vec<ColumnProfile>,
+    /// The date or period column, when one was detected.
+    #[serde(default)]
+    pub time_index: Option<TimeIndex>,
+    /// Parent dataset and transforms of a derived dataset.
+    #[serde(default)]
+    pub lineage: Option<Lineage>,
 }
 
 impl Default for AppState {
     fn default() -> Self {
         Self {
             datasets: Mutex::new(HashMap::new()),
+            tables: TableCache::default(),
+            session: SidecarSession::default(),
+            jobs: JobRegistry::default(),
+            scheduler: JobScheduler::default(),
+            timeouts: Mutex::new(TimeoutSettings::from_env()),
+            sidecar_info: Mutex::new(None),
+            settings: Mutex::new(Settings::default()),
+            runner: runner::from_env(),
+            spills: SpillStore::default(),
         }
     }
 }
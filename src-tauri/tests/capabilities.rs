@@ -0,0 +1,52 @@
+//! Tests for refusing commands the probed friedman-cli does not have.
+//!
+//!   cargo test -p friedman-app --test capabilities
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use app_lib::error::FriedmanError;
+use app_lib::sidecar::capabilities::{CommandGroup, SidecarInfo};
+use app_lib::sidecar::SidecarMode;
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+fn info(groups: &[&str], groups_parsed: bool) -> SidecarInfo {
+    let mut commands = BTreeMap::new();
+    commands.insert(
+        "var".to_string(),
+        CommandGroup { subcommands: args(&["estimate", "irf"]), flags: Vec::new() },
+    );
+    SidecarInfo {
+        mode: SidecarMode::Binary(PathBuf::from("friedman-cli")),
+        cli_version: Some("0.1.3".into()),
+        julia_version: None,
+        expected_cli_version: "0.1.3".into(),
+        groups: args(groups),
+        groups_parsed,
+        commands,
+        compatible: true,
+        issues: Vec::new(),
+    }
+}
+
+#[test]
+fn test_check_only_gates_on_a_parsed_command_list() {
+    let parsed = info(&["var", "bvar", "lp"], true);
+    assert!(parsed.check(&args(&["var", "estimate"])).is_ok());
+    assert!(matches!(
+        parsed.check(&args(&["gmm", "estimate"])),
+        Err(FriedmanError::IncompatibleSidecar(msg)) if msg.starts_with("command group `gmm` is not available")
+    ));
+    assert!(matches!(
+        parsed.check(&args(&["var", "fevd"])),
+        Err(FriedmanError::IncompatibleSidecar(_))
+    ));
+
+    // A help page that only yielded stray words does not block anything.
+    let misparsed = info(&["usage", "options"], false);
+    assert!(misparsed.check(&args(&["gmm", "estimate"])).is_ok());
+    assert!(info(&[], false).check(&args(&["gmm", "estimate"])).is_ok());
+}
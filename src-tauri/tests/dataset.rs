@@ -0,0 +1,661 @@
+//! Tests for native dataset loading.
+//!
+//!   cargo test -p friedman-app --test dataset
+
+use std::path::PathBuf;
+
+use app_lib::dataset::columnar::{read_columnar, write_columnar, ColumnarFormat};
+use app_lib::dataset::delimited::{parse_csv, read_csv};
+use app_lib::dataset::input::{
+    content_hash, sample_rows, selected_column, slice_table, EffectiveSample, SampleBound,
+};
+use app_lib::dataset::period::{detect_time_index, parse_period, Frequency, IndexGap};
+use app_lib::dataset::preview::{preview, PreviewFilter, PreviewQuery, SortKey, TableCache};
+use app_lib::dataset::spreadsheet::{list_sheets, read_sheet, SheetSelection};
+use app_lib::dataset::profile::{profile_table, ColumnKind};
+use app_lib::dataset::Table;
+use app_lib::error::FriedmanError;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+}
+
+fn invalid_message(result: Result<impl std::fmt::Debug, FriedmanError>) -> String {
+    match result {
+        Err(FriedmanError::InvalidParams(msg)) => msg,
+        other => panic!("expected InvalidParams, got {other:?}"),
+    }
+}
+
+// ===========================================================================
+// CSV dialects
+// ===========================================================================
+
+#[test]
+fn test_plain_fixture() {
+    let (table, dialect) = read_csv(&fixture("test_macro.csv")).unwrap();
+
+    assert!(dialect.is_plain());
+    assert_eq!(
+        table.columns,
+        vec!["date", "gdp_growth", "inflation", "interest_rate", "unemployment"]
+    );
+    assert_eq!(table.rows[0], vec!["2000Q1", "1.2", "2.1", "5.5", "4.0"]);
+    assert!(table.rows.iter().all(|r| r.len() == 5));
+}
+
+#[test]
+fn test_quoted_header_with_comma_and_newline() {
+    let text = "date,\"GDP, real\",\"CPI\nindex\"\n2000Q1,1.5,\"100\"\n2000Q2,1.7,101\n";
+    let (table, dialect) = parse_csv(text.as_bytes()).unwrap();
+
+    assert_eq!(dialect.delimiter, ',');
+    assert_eq!(table.columns, vec!["date", "GDP, real", "CPI\nindex"]);
+    assert_eq!(table.rows.len(), 2);
+    assert_eq!(table.rows[0], vec!["2000Q1", "1.5", "100"]);
+}
+
+#[test]
+fn test_bom_is_stripped() {
+    let (table, dialect) = parse_csv(b"\xEF\xBB\xBFdate,y\n2000Q1,1\n").unwrap();
+
+    assert!(dialect.bom);
+    assert_eq!(dialect.encoding, "utf-8");
+    assert!(!dialect.is_plain());
+    assert_eq!(table.columns, vec!["date", "y"]);
+}
+
+#[test]
+fn test_semicolon_with_decimal_comma() {
+    let text = "date;gdp;cpi\n2000Q1;1,25;1.234,5\n2000Q2;-0,5;1.240,0\n";
+    let (table, dialect) = parse_csv(text.as_bytes()).unwrap();
+
+    assert_eq!(dialect.delimiter, ';');
+    assert_eq!(dialect.decimal, ',');
+    assert_eq!(table.rows[0], vec!["2000Q1", "1.25", "1234.5"]);
+    assert_eq!(table.rows[1], vec!["2000Q2", "-0.5", "1240.0"]);
+
+    // `.` only groups thousands once the file uses decimal commas.
+    let text = "x;y;z\n1.234;0,5;1.5\n-12.345.678;1,5;2\n";
+    let (table, dialect) = parse_csv(text.as_bytes()).unwrap();
+    assert_eq!(dialect.decimal, ',');
+    assert_eq!(table.rows[0], vec!["1234", "0.5", "1.5"]);
+    assert_eq!(table.rows[1], vec!["-12345678", "1.5", "2"]);
+    let (table, _) = parse_csv(b"x;y\n1.234;1.5\n").unwrap();
+    assert_eq!(table.rows[0], vec!["1.234", "1.5"]);
+}
+
+#[test]
+fn test_repeated_header_names() {
+    let (table, _) = parse_csv(b"x,y,x,x_2,x\n1,2,3,4,5\n").unwrap();
+    assert_eq!(table.columns, vec!["x", "y", "x_3", "x_2", "x_4"]);
+}
+
+#[test]
+fn test_tab_delimited_windows_1252() {
+    // "Préis" in Windows-1252
+    let bytes = b"date\tPr\xe9is\n2000Q1\t1.5\n2000Q2\t1.6\n";
+    let (table, dialect) = parse_csv(bytes).unwrap();
+
+    assert_eq!(dialect.delimiter, '\t');
+    assert_eq!(dialect.encoding, "windows-1252");
+    assert_eq!(dialect.decimal, '.');
+    assert_eq!(table.columns, vec!["date", "Préis"]);
+}
+
+#[test]
+fn test_single_quotes() {
+    let text = "'name','value'\n'a, b',1\n'c',2\n";
+    let (table, dialect) = parse_csv(text.as_bytes()).unwrap();
+
+    assert_eq!(dialect.quote, '\'');
+    assert_eq!(table.rows[0], vec!["a, b", "1"]);
+}
+
+#[test]
+fn test_ragged_rows_are_rejected() {
+    let short = invalid_message(parse_csv(b"date,y,z\n2000Q1,1,2\n2000Q2,3\n"));
+    assert!(short.contains("row 2 (line 3)"), "{short}");
+    assert!(short.contains("column `z` is missing"), "{short}");
+
+    let long = invalid_message(parse_csv(b"date,y\n2000Q1,1,99\n"));
+    assert!(long.contains("row 1 (line 2) has 3 fields but the header has 2"), "{long}");
+    assert!(long.contains("`99` in column 3"), "{long}");
+
+    let empty = invalid_message(parse_csv(b"  \n"));
+    assert_eq!(empty, "CSV file is empty");
+}
+
+#[test]
+fn test_normalized_copy_round_trips() {
+    let (table, _) = parse_csv("a;b\n\"x;y\";1,5\n".as_bytes()).unwrap();
+    let path = std::env::temp_dir().join(format!("friedman-dataset-test-{}.csv", std::process::id()));
+    table.write_csv(&path).unwrap();
+
+    let (reread, dialect): (Table, _) = read_csv(&path).unwrap();
+    assert!(dialect.is_plain());
+    assert_eq!(reread, table);
+    let _ = std::fs::remove_file(path);
+}
+
+// ===========================================================================
+// Column profiles
+// ===========================================================================
+
+#[test]
+fn test_profile_kinds_and_statistics() {
+    let text = "date,gdp,region,blank\n2000Q1,NA,north,\n2000Q2,1.0,south,.\n2000Q3,3.0,north,\n2000Q4,,south,NaN\n";
+    let (table, _) = parse_csv(text.as_bytes()).unwrap();
+    let profiles = profile_table(&table);
+
+    let kinds: Vec<ColumnKind> = profiles.iter().map(|p| p.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![ColumnKind::Date, ColumnKind::Numeric, ColumnKind::Categorical, ColumnKind::Empty]
+    );
+
+    let gdp = &profiles[1];
+    assert_eq!((gdp.count, gdp.missing), (2, 2));
+    assert_eq!((gdp.min, gdp.max, gdp.mean), (Some(1.0), Some(3.0), Some(2.0)));
+    assert!((gdp.std.unwrap() - 2f64.sqrt()).abs() < 1e-12);
+    assert_eq!((gdp.first_valid, gdp.last_valid), (Some(1), Some(2)));
+    assert_eq!(gdp.missing_share(), 0.5);
+
+    assert_eq!(profiles[2].count, 4);
+    assert_eq!(profiles[2].mean, None);
+    assert_eq!((profiles[3].first_valid, profiles[3].missing), (None, 4));
+}
+
+#[test]
+fn test_profile_fixture_and_date_forms() {
+    let (table, _) = read_csv(&fixture("test_macro.csv")).unwrap();
+    let profiles = profile_table(&table);
+
+    assert_eq!(profiles[0].kind, ColumnKind::Date);
+    assert!(profiles[1..].iter().all(|p| p.kind == ColumnKind::Numeric && p.missing == 0));
+
+    for (first, second) in [("2000:1", "2000:2"), ("2000M01", "2000M02"), ("2000-01", "2000-02"), ("2000-01-31", "2000-02-29")] {
+        let (table, _) = parse_csv(format!("t,y\n{first},1\n{second},2\n").as_bytes()).unwrap();
+        assert_eq!(profile_table(&table)[0].kind, ColumnKind::Date, "{first}");
+    }
+    let (table, _) = parse_csv(b"t,y\n2000,1\n2001,2\n").unwrap();
+    assert_eq!(profile_table(&table)[0].kind, ColumnKind::Numeric);
+}
+
+// ===========================================================================
+// Time index
+// ===========================================================================
+
+fn index_of(text: &str) -> Option<app_lib::dataset::period::TimeIndex> {
+    let (table, _) = parse_csv(text.as_bytes()).unwrap();
+    detect_time_index(&table)
+}
+
+#[test]
+fn test_parse_period_forms() {
+    let label = |cell: &str| parse_period(cell).map(|p| (p.frequency, p.label()));
+
+    assert_eq!(label("2000Q3"), Some((Frequency::Quarterly, "2000Q3".into())));
+    assert_eq!(label("2000:4"), Some((Frequency::Quarterly, "2000Q4".into())));
+    assert_eq!(label("2000:11"), Some((Frequency::Monthly, "2000-11".into())));
+    assert_eq!(label("2000M1"), Some((Frequency::Monthly, "2000-01".into())));
+    assert_eq!(label("2000-02"), Some((Frequency::Monthly, "2000-02".into())));
+    assert_eq!(label("2000/02/29"), Some((Frequency::Daily, "2000-02-29".into())));
+    assert_eq!(label("1987"), Some((Frequency::Annual, "1987".into())));
+    for bad in ["2000Q5", "2000-13", "1999-02-29", "2000-1-01", "abc", "20001"] {
+        assert_eq!(parse_period(bad), None, "{bad}");
+    }
+}
+
+#[test]
+fn test_fixture_quarterly_index() {
+    let (table, _) = read_csv(&fixture("test_macro.csv")).unwrap();
+    let index = detect_time_index(&table).unwrap();
+
+    assert_eq!(index.column, "date");
+    assert_eq!(index.frequency, Frequency::Quarterly);
+    assert_eq!(index.start, "2000Q1");
+    assert_eq!(index.labels.len(), table.rows.len());
+    assert!(index.is_regular(), "{index:?}");
+}
+
+#[test]
+fn test_gaps_duplicates_and_order() {
+    let index = index_of("y,period\n1,2000:1\n2,2000:2\n3,2000:2\n4,2001:1\n5,\n").unwrap();
+
+    assert_eq!(index.column, "period");
+    assert_eq!(index.labels, vec!["2000Q1", "2000Q2", "2000Q2", "2001Q1", ""]);
+    assert_eq!(index.duplicates, vec!["2000Q2"]);
+    assert_eq!(
+        index.gaps,
+        vec![IndexGap { after: "2000Q2".into(), before: "2001Q1".into(), periods: 2 }]
+    );
+    assert_eq!((index.missing, index.sorted), (1, true));
+
+    let unsorted = index_of("date,y\n2000-03,1\n2000-01,2\n2000-02,3\n").unwrap();
+    assert!(!unsorted.sorted);
+    assert!(unsorted.gaps.is_empty());
+    assert_eq!((unsorted.start.as_str(), unsorted.end.as_str()), ("2000-01", "2000-03"));
+}
+
+#[test]
+fn test_frequency_inferred_from_dates() {
+    let month_ends = index_of("date,y\n2000-01-31,1\n2000-02-29,2\n2000-04-30,3\n").unwrap();
+    assert_eq!(month_ends.frequency, Frequency::Monthly);
+    assert_eq!(month_ends.labels, vec!["2000-01", "2000-02", "2000-04"]);
+    assert_eq!(month_ends.gaps[0].periods, 1);
+
+    // Thursday, Friday, Monday: no gap over the weekend
+    let business = index_of("date,y\n2024-01-04,1\n2024-01-05,2\n2024-01-08,3\n").unwrap();
+    assert_eq!(business.frequency, Frequency::BusinessDaily);
+    assert!(business.gaps.is_empty());
+    assert_eq!(business.labels[2], "2024-01-08");
+
+    let daily = index_of("date,y\n2024-01-05,1\n2024-01-06,2\n2024-01-08,3\n").unwrap();
+    assert_eq!(daily.frequency, Frequency::Daily);
+    assert_eq!(daily.gaps[0].periods, 1);
+}
+
+#[test]
+fn test_plain_numbers_are_not_years() {
+    assert_eq!(index_of("year,y\n1990,1\n1992,2\n").unwrap().gaps.len(), 1);
+    assert_eq!(index_of("obs_y,gdp\n1990,1\n1991,2\n").unwrap().frequency, Frequency::Annual);
+    assert!(index_of("gdp,cpi\n1234,1\n1250,2\n").is_none());
+    assert!(index_of("x,y\n1,1990\n2,1991\n").is_none());
+    assert_eq!(index_of("y,x\n1990,1\n1991,2\n").unwrap().column, "y");
+}
+
+// ===========================================================================
+// Spreadsheets
+// ===========================================================================
+
+/// A workbook with a notes sheet and a data sheet whose table starts at B3
+/// under a title row.
+fn workbook(name: &str) -> PathBuf {
+    use rust_xlsxwriter::{ExcelDateTime, Format, Workbook};
+
+    let path = std::env::temp_dir().join(format!("friedman-{name}-{}.xlsx", std::process::id()));
+    let mut workbook = Workbook::new();
+    workbook.add_worksheet().set_name("Notes").unwrap().write_string(0, 0, "Source: FRED").unwrap();
+
+    let date = Format::new().set_num_format("yyyy-mm-dd");
+    let sheet = workbook.add_worksheet().set_name("Data").unwrap();
+    sheet.write_string(0, 1, "Quarterly macro data").unwrap();
+    for (col, header) in ["date", "gdp", "", "label"].iter().enumerate() {
+        sheet.write_string(2, col as u16 + 1, *header).unwrap();
+    }
+    let rows = [((2000, 3, 31), 1.25, "a"), ((2000, 6, 30), -0.5, "b"), ((2000, 9, 30), 2.0, "c")];
+    for (i, ((y, m, d), gdp, label)) in rows.iter().enumerate() {
+        let row = 3 + i as u32;
+        let dt = ExcelDateTime::from_ymd(*y, *m, *d).unwrap();
+        sheet.write_datetime_with_format(row, 1, &dt, &date).unwrap();
+        sheet.write_number(row, 2, *gdp).unwrap();
+        sheet.write_string(row, 4, *label).unwrap();
+    }
+    sheet.write_string(10, 1, "").unwrap();
+    workbook.save(&path).unwrap();
+    path
+}
+
+#[test]
+fn test_list_sheets() {
+    let path = workbook("list");
+    let sheets = list_sheets(&path).unwrap();
+
+    let names: Vec<&str> = sheets.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["Notes", "Data"]);
+    assert_eq!(sheets[0].range.as_deref(), Some("A1:A1"));
+    assert_eq!(sheets[1].range.as_deref(), Some("B1:E6"));
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn test_read_sheet_detects_header_and_converts_cells() {
+    let path = workbook("read");
+    let selection = SheetSelection { sheet: Some("Data".into()), range: Some("B3".into()), header_row: None };
+    let (table, resolved) = read_sheet(&path, &selection).unwrap();
+
+    assert_eq!(table.columns, vec!["date", "gdp", "label"]);
+    assert_eq!(table.rows[0], vec!["2000-03-31", "1.25", "a"]);
+    assert_eq!(table.rows[1], vec!["2000-06-30", "-0.5", "b"]);
+    assert_eq!(table.rows.len(), 3);
+    assert_eq!(resolved.range.as_deref(), Some("B3:E6"));
+    assert_eq!(resolved.header_row, Some(3));
+    assert_eq!(detect_time_index(&table).unwrap().frequency, Frequency::Quarterly);
+
+    // An explicit header row skips the title; the first sheet is the default
+    let (data, _) = read_sheet(&path, &SheetSelection { sheet: Some("Data".into()), header_row: Some(3), ..Default::default() }).unwrap();
+    assert_eq!(data, table);
+    let (notes, resolved) = read_sheet(&path, &SheetSelection::default()).unwrap();
+    assert_eq!((notes.columns, notes.rows.len()), (vec!["Source: FRED".to_string()], 0));
+    assert_eq!(resolved.sheet.as_deref(), Some("Notes"));
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn test_read_sheet_range_and_errors() {
+    let path = workbook("range");
+    let data = |range: &str, header_row: Option<u32>| {
+        read_sheet(&path, &SheetSelection { sheet: Some("Data".into()), range: Some(range.into()), header_row })
+    };
+
+    let (table, _) = data("b3:c4", None).unwrap();
+    assert_eq!(table.columns, vec!["date", "gdp"]);
+    assert_eq!(table.rows, vec![vec!["2000-03-31", "1.25"]]);
+
+    let (unnamed, _) = data("C4:D6", None).unwrap();
+    assert_eq!(unnamed.columns, vec!["1.25"]);
+
+    assert!(invalid_message(data("3B", None)).contains("Invalid cell range"));
+    assert!(invalid_message(data("B3:E6", Some(1))).contains("Header row 1 is outside"));
+    assert!(invalid_message(data("Z50", None)).contains("outside the used cells"));
+    let missing = invalid_message(read_sheet(&path, &SheetSelection { sheet: Some("GDP".into()), ..Default::default() }));
+    assert!(missing.contains("sheets are: Notes, Data"), "{missing}");
+    let _ = std::fs::remove_file(path);
+}
+
+// ===========================================================================
+// Parquet and Arrow
+// ===========================================================================
+
+fn temp_file(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("friedman-{}-{name}", std::process::id()))
+}
+
+#[test]
+fn test_columnar_round_trip() {
+    let (table, _) = parse_csv(b"date,gdp,count,region\n2000Q1,1.5,3,north\n2000Q2,NA,4,\n2000Q3,-0.25,,south\n").unwrap();
+
+    for (format, name) in [(ColumnarFormat::Parquet, "rt.parquet"), (ColumnarFormat::Arrow, "rt.arrow")] {
+        let path = temp_file(name);
+        assert_eq!(ColumnarFormat::from_path(&path), Some(format));
+        write_columnar(&table, &path, format).unwrap();
+
+        let (read, schema) = read_columnar(&path, format).unwrap();
+        let types: Vec<(&str, &str, bool)> =
+            schema.iter().map(|f| (f.name.as_str(), f.data_type.as_str(), f.included)).collect();
+        assert_eq!(
+            types,
+            vec![("date", "Utf8", true), ("gdp", "Float64", true), ("count", "Int64", true), ("region", "Utf8", false)]
+        );
+        assert_eq!(read.columns, vec!["date", "gdp", "count"]);
+        assert_eq!(read.rows[1], vec!["2000Q2", "", "4"]);
+        assert_eq!(read.rows[2], vec!["2000Q3", "-0.25", ""]);
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[test]
+fn test_read_arrow_stream_with_dates() {
+    use std::sync::Arc;
+
+    use arrow_array::{Date32Array, Float32Array, RecordBatch, StringArray};
+    use arrow_schema::{DataType, Field, Schema};
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("country", DataType::Utf8, false),
+        Field::new("day", DataType::Date32, false),
+        Field::new("rate", DataType::Float32, true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(StringArray::from(vec!["DE", "DE"])),
+            Arc::new(Date32Array::from(vec![10957, 11048])),
+            Arc::new(Float32Array::from(vec![Some(0.5), None])),
+        ],
+    )
+    .unwrap();
+    let path = temp_file("stream.arrows");
+    let mut writer = arrow_ipc::writer::StreamWriter::try_new(std::fs::File::create(&path).unwrap(), &schema).unwrap();
+    writer.write(&batch).unwrap();
+    writer.finish().unwrap();
+
+    let (table, schema) = read_columnar(&path, ColumnarFormat::Arrow).unwrap();
+    assert_eq!(table.columns, vec!["day", "rate"]);
+    assert_eq!(table.rows, vec![vec!["2000-01-01", "0.5"], vec!["2000-04-01", ""]]);
+    assert!(!schema[0].included);
+    assert_eq!(detect_time_index(&table).unwrap().frequency, Frequency::Quarterly);
+
+    let garbage = temp_file("garbage.parquet");
+    std::fs::write(&garbage, b"not parquet").unwrap();
+    assert!(invalid_message(read_columnar(&garbage, ColumnarFormat::Parquet)).starts_with("Cannot read Parquet file"));
+    let _ = std::fs::remove_file(path);
+    let _ = std::fs::remove_file(garbage);
+}
+
+// ===========================================================================
+// Preview
+// ===========================================================================
+
+fn preview_table() -> Table {
+    parse_csv(
+        b"date,region,gdp\n2000Q1,North,1.5\n2000Q2,south,NA\n2000Q3,North,-0.2\n2000Q4,West,10\n2001Q1,South,2\n",
+    )
+    .unwrap()
+    .0
+}
+
+fn filter(column: Option<&str>, text: &str) -> Option<PreviewFilter> {
+    Some(PreviewFilter { column: column.map(String::from), text: text.into() })
+}
+
+#[test]
+fn test_preview_pages_and_columns() {
+    let table = preview_table();
+    let query = PreviewQuery { offset: 3, limit: Some(10), columns: vec!["gdp".into(), "date".into()], ..Default::default() };
+    let page = preview(&table, &query).unwrap();
+
+    assert_eq!(page.columns, vec!["gdp", "date"]);
+    assert_eq!(page.rows, vec![vec!["10", "2000Q4"], vec!["2", "2001Q1"]]);
+    assert_eq!(page.row_numbers, vec![3, 4]);
+    assert_eq!((page.total_rows, page.dataset_rows), (5, 5));
+
+    let beyond = preview(&table, &PreviewQuery { offset: 9, ..Default::default() }).unwrap();
+    assert!(beyond.rows.is_empty());
+    let unknown = PreviewQuery { columns: vec!["cpi".into()], ..Default::default() };
+    assert_eq!(invalid_message(preview(&table, &unknown)), "Unknown column `cpi`");
+}
+
+#[test]
+fn test_preview_sort_and_filter() {
+    let table = preview_table();
+    let sorted = |descending| {
+        let sort = Some(SortKey { column: "gdp".into(), descending });
+        preview(&table, &PreviewQuery { sort, ..Default::default() }).unwrap().row_numbers
+    };
+    // Numeric order, not text order, with the missing cell last either way.
+    assert_eq!(sorted(false), vec![2, 0, 4, 3, 1]);
+    assert_eq!(sorted(true), vec![3, 4, 0, 2, 1]);
+
+    let matching = |filter| preview(&table, &PreviewQuery { filter, ..Default::default() }).unwrap().row_numbers;
+    assert_eq!(matching(filter(None, "south")), vec![1, 4]);
+    assert_eq!(matching(filter(Some("gdp"), "> 1")), vec![0, 3, 4]);
+    assert_eq!(matching(filter(Some("gdp"), "!=2")), vec![0, 2, 3]);
+    assert_eq!(matching(filter(Some("date"), ">=2000Q3")), vec![2, 3, 4]);
+    assert_eq!(matching(filter(Some("region"), "=north")), vec![0, 2]);
+    assert_eq!(matching(filter(None, "  ")), vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn test_table_cache_pages_sorted_view() {
+    let cache = TableCache::default();
+    let mut table = Table { columns: vec!["t".into(), "x".into()], rows: Vec::new() };
+    table.rows = (0..200_000).map(|i| vec![i.to_string(), ((i * 7919) % 200_000).to_string()]).collect();
+    let expected = preview(&table, &PreviewQuery { sort: Some(SortKey { column: "x".into(), descending: true }), ..Default::default() }).unwrap();
+    cache.insert("big", table);
+
+    let path = PathBuf::from("unused.csv");
+    let mut query = PreviewQuery { sort: Some(SortKey { column: "x".into(), descending: true }), ..Default::default() };
+    assert_eq!(cache.preview("big", &path, &query).unwrap(), expected);
+    query.offset = 150_000;
+    query.limit = Some(2);
+    let page = cache.preview("big", &path, &query).unwrap();
+    assert_eq!(page.rows, vec![vec!["132321", "49999"], vec!["114642", "49998"]]);
+    assert_eq!(page.total_rows, 200_000);
+
+    // Datasets not in the cache are read from their CSV.
+    let loaded = cache.preview("macro", &fixture("test_macro.csv"), &PreviewQuery::default()).unwrap();
+    assert_eq!(loaded.columns[0], "date");
+    assert!(cache.preview("missing", &fixture("missing.csv"), &PreviewQuery::default()).is_err());
+}
+
+// ===========================================================================
+// Analysis columns
+// ===========================================================================
+
+#[test]
+fn test_slice_columns_in_given_order() {
+    let table = preview_table();
+    let slice = slice_table(&table, &["gdp".into()], 0..table.rows.len()).unwrap();
+    assert_eq!(slice.columns, vec!["gdp"]);
+    assert_eq!(slice.rows[1], vec!["NA"]);
+
+    let (macro_table, _) = read_csv(&fixture("test_macro.csv")).unwrap();
+    let order = ["interest_rate".to_string(), "inflation".into(), "gdp_growth".into()];
+    let slice = slice_table(&macro_table, &order, 0..macro_table.rows.len()).unwrap();
+    assert_eq!(slice.columns, order);
+    assert_eq!(slice.rows[0], vec!["5.5", "2.1", "1.2"]);
+    assert_eq!(slice.rows.len(), macro_table.rows.len());
+
+    let err = |columns: &[&str]| {
+        let columns: Vec<String> = columns.iter().map(|c| c.to_string()).collect();
+        invalid_message(slice_table(&table, &columns, 0..table.rows.len()))
+    };
+    assert_eq!(err(&["gdp", "cpi"]), "Unknown column `cpi`");
+    assert_eq!(err(&["gdp", "gdp"]), "Column `gdp` is selected twice");
+    assert_eq!(err(&["region"]), "Column `region` is not numeric (found `North`) and cannot be analyzed");
+}
+
+#[test]
+fn test_column_option_follows_the_selection() {
+    let header = ["date", "gdp_growth", "inflation", "interest_rate"].map(String::from);
+    let order = ["interest_rate", "gdp_growth"].map(String::from);
+
+    // Column 2 of the dataset is `gdp_growth`, second in the written file.
+    assert_eq!(selected_column(&header, &order, 2).unwrap(), 2);
+    assert_eq!(selected_column(&header, &order, 4).unwrap(), 1);
+    assert_eq!(
+        invalid_message(selected_column(&header, &order, 3)),
+        "Column 3 (`inflation`) is not among the selected columns"
+    );
+    assert_eq!(invalid_message(selected_column(&header, &order, 0)), "Column 0 is not between 1 and 4");
+}
+
+#[test]
+fn test_sample_window_by_label_and_observation() {
+    let (table, _) = read_csv(&fixture("test_macro.csv")).unwrap();
+    let index = detect_time_index(&table).unwrap();
+    let len = table.rows.len();
+    let label = |s: &str| Some(SampleBound::Label(s.into()));
+    let rows = |start: Option<SampleBound>, end: Option<SampleBound>| {
+        sample_rows(Some(&index), len, start.as_ref(), end.as_ref())
+    };
+
+    assert_eq!(rows(None, None).unwrap(), 0..40);
+    assert_eq!(rows(label("2001Q2"), label("2007Q4")).unwrap(), 5..32);
+    // An annual bound covers its whole year, a date the quarter holding it.
+    assert_eq!(rows(label("2008"), label("2008")).unwrap(), 32..36);
+    assert_eq!(rows(label("2003-05-15"), None).unwrap(), 13..40);
+    assert_eq!(rows(label("1990"), label("2030")).unwrap(), 0..40);
+    assert_eq!(rows(Some(SampleBound::Observation(3)), label("2000Q4")).unwrap(), 2..4);
+
+    let slice = slice_table(&table, &[], 32..36).unwrap();
+    assert_eq!(slice.columns, table.columns);
+    assert_eq!(slice.rows[0][0], "2008Q1");
+    assert_eq!(slice.rows.len(), 4);
+
+    assert_eq!(
+        EffectiveSample::new(32..36, Some(&index)),
+        Some(EffectiveSample {
+            first: 33,
+            last: 36,
+            observations: 4,
+            start: Some("2008Q1".into()),
+            end: Some("2008Q4".into()),
+        })
+    );
+    assert_eq!(EffectiveSample::new(2..2, None), None);
+
+    // Slices are named by content, so the same selection gets the same name.
+    assert_eq!(content_hash(&slice), content_hash(&slice_table(&table, &[], 32..36).unwrap()));
+    assert_ne!(content_hash(&slice), content_hash(&slice_table(&table, &[], 32..35).unwrap()));
+}
+
+#[test]
+fn test_sample_window_errors() {
+    let (table, _) = read_csv(&fixture("test_macro.csv")).unwrap();
+    let index = detect_time_index(&table).unwrap();
+    let err = |index, start: SampleBound, end: Option<SampleBound>| {
+        invalid_message(sample_rows(index, 40, Some(&start), end.as_ref()))
+    };
+    let label = |s: &str| SampleBound::Label(s.into());
+
+    assert_eq!(
+        err(Some(&index), SampleBound::Observation(41), None),
+        "Sample start 41 is not an observation number between 1 and 40"
+    );
+    assert_eq!(
+        err(Some(&index), label("2011Q1"), None),
+        "Sample start `2011Q1` is outside the data, which runs from 2000Q1 to 2009Q4"
+    );
+    assert_eq!(err(Some(&index), label("late"), None), "Sample start `late` is not a date or period");
+    assert!(err(None, label("2001"), None).contains("needs a date column"));
+    assert_eq!(
+        err(Some(&index), label("2005Q1"), Some(label("2004Q4"))),
+        "The sample window contains no observations"
+    );
+
+    let unsorted = index_of("date,y
+2000-03,1
+2000-01,2
+2000-02,3
+").unwrap();
+    assert!(invalid_message(sample_rows(Some(&unsorted), 3, Some(&label("2000-02")), None))
+        .contains("not in time order"));
+}
+
+#[test]
+fn test_params_accept_dataset_and_columns() {
+    use app_lib::commands::var::{VarEstimateParams, VarIrfParams};
+
+    let params: VarIrfParams = serde_json::from_value(serde_json::json!({
+        "dataset_id": "d1",
+        "columns": ["rate", "inflation", "gdp"],
+        "horizons": 12,
+    }))
+    .unwrap();
+    assert_eq!(params.input.dataset_id.as_deref(), Some("d1"));
+    assert_eq!(params.input.columns, vec!["rate", "inflation", "gdp"]);
+    assert_eq!((params.horizons, params.id.as_str()), (12, "cholesky"));
+
+    // A plain file path still works on its own.
+    let params: VarEstimateParams = serde_json::from_value(serde_json::json!({"data": "macro.csv", "lags": 2})).unwrap();
+    assert_eq!(params.input.data, "macro.csv");
+    assert!(params.input.dataset_id.is_none() && params.input.columns.is_empty());
+
+    let params: VarEstimateParams = serde_json::from_value(serde_json::json!({
+        "data": "macro.csv",
+        "sample_start": "2000Q2",
+        "sample_end": 12,
+    }))
+    .unwrap();
+    assert_eq!(params.input.sample_start, Some(SampleBound::Label("2000Q2".into())));
+    assert_eq!(params.input.sample_end, Some(SampleBound::Observation(12)));
+
+    // Only numbers are observation numbers; a numeric string is a label.
+    let params: VarEstimateParams =
+        serde_json::from_value(serde_json::json!({"data": "macro.csv", "sample_start": 5, "sample_end": "5"})).unwrap();
+    assert_eq!(params.input.sample_start, Some(SampleBound::Observation(5)));
+    assert_eq!(params.input.sample_end, Some(SampleBound::Label("5".into())));
+    assert_eq!(sample_rows(None, 8, params.input.sample_start.as_ref(), None).unwrap(), 4..8);
+    assert!(sample_rows(None, 8, None, params.input.sample_end.as_ref()).is_err());
+
+    let params: VarEstimateParams = serde_json::from_value(serde_json::json!({
+        "dataset_id": "d1",
+        "missing": {"treatment": "spline", "tokens": ["-999"]},
+    }))
+    .unwrap();
+    assert_eq!(params.input.missing.treatment, app_lib::dataset::missing::MissingTreatment::Spline);
+    assert_eq!(params.input.missing.tokens, vec!["-999"]);
+}
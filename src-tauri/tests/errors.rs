@@ -0,0 +1,147 @@
+//! Tests for the structured error objects returned to the frontend and the
+//! Julia exception parser behind them.
+//!
+//!   cargo test -p friedman-app --test errors
+
+use app_lib::error::FriedmanError;
+use app_lib::sidecar::exception::{parse, StackFrame};
+use serde_json::json;
+
+// ===========================================================================
+// Julia exception parsing
+// ===========================================================================
+
+#[test]
+fn test_parse_uncaught_cli_error() {
+    let stderr = r#"[ Info: Estimating VAR(2)
+ERROR: LoadError: ArgumentError: invalid trend "quadratic", expected one of: none, constant, trend
+Stacktrace:
+  [1] parse_trend(s::String)
+    @ Friedman ~/.julia/packages/Friedman/abc12/src/var.jl:41
+  [2] main(args::Vector{String})
+    @ Main ~/sidecar/main.jl:12
+in expression starting at /home/user/sidecar/main.jl:15
+"#;
+    let e = parse(stderr).unwrap();
+
+    assert_eq!(e.exception_type, "ArgumentError");
+    assert_eq!(
+        e.message,
+        r#"ArgumentError: invalid trend "quadratic", expected one of: none, constant, trend"#
+    );
+    assert_eq!(
+        e.frame,
+        Some(StackFrame {
+            function: "parse_trend(s::String)".into(),
+            location: Some("Friedman ~/.julia/packages/Friedman/abc12/src/var.jl:41".into()),
+        })
+    );
+}
+
+#[test]
+fn test_parse_session_showerror() {
+    // The session prints `showerror(stderr, e, bt)`, with no `ERROR:` prefix.
+    let stderr = "┌ Warning: near-unit root\n└ @ Friedman var.jl:88\n\
+                  LinearAlgebra.SingularException(3)\n\
+                  Stacktrace:\n  [1] checknonsingular\n    @ LinearAlgebra /opt/julia/lu.jl:19 [inlined]\n";
+    let e = parse(stderr).unwrap();
+
+    assert_eq!(e.exception_type, "LinearAlgebra.SingularException");
+    assert_eq!(e.short_type(), "SingularException");
+    assert_eq!(e.message, "LinearAlgebra.SingularException(3)");
+    let frame = e.frame.unwrap();
+    assert_eq!(frame.function, "checknonsingular");
+    assert_eq!(frame.location.as_deref(), Some("LinearAlgebra /opt/julia/lu.jl:19"));
+}
+
+#[test]
+fn test_parse_plain_error_call() {
+    let e = parse("ERROR: not enough observations for 8 lags\n").unwrap();
+
+    assert_eq!(e.exception_type, "ErrorException");
+    assert_eq!(e.message, "not enough observations for 8 lags");
+    assert_eq!(e.frame, None);
+}
+
+#[test]
+fn test_parse_ignores_log_output() {
+    assert_eq!(parse("[ Info: loading data\nsome other text\n"), None);
+    assert_eq!(parse(""), None);
+}
+
+// ===========================================================================
+// Serialization
+// ===========================================================================
+
+#[test]
+fn test_sidecar_exit_serializes_structured() {
+    let args: Vec<String> = ["var", "estimate", "data.csv", "--format=json"]
+        .map(String::from)
+        .to_vec();
+    let err = FriedmanError::SidecarExit {
+        code: 1,
+        stderr: "ERROR: LoadError: PosDefException: matrix is not positive definite; \
+                 Cholesky factorization failed.\nStacktrace:\n [1] cholesky!(A::Matrix{Float64}) at cholesky.jl:253\n"
+            .into(),
+    }
+    .in_command(&args);
+    let value = serde_json::to_value(&err).unwrap();
+
+    assert_eq!(value["kind"], "sidecar_exit");
+    assert_eq!(value["code"], "not_positive_definite");
+    assert_eq!(value["exit_code"], 1);
+    assert_eq!(value["args"], json!(args));
+    assert_eq!(value["exception"]["type"], "PosDefException");
+    assert_eq!(value["exception"]["frame"]["function"], "cholesky!(A::Matrix{Float64})");
+    assert_eq!(value["exception"]["frame"]["location"], "cholesky.jl:253");
+    assert!(value["message"].as_str().unwrap().starts_with("PosDefException: matrix"));
+    assert!(value["stderr"].as_str().unwrap().contains("Stacktrace:"));
+    assert!(value["hint"].is_string());
+}
+
+#[test]
+fn test_sidecar_exit_without_exception_uses_last_line() {
+    let err = FriedmanError::SidecarExit {
+        code: 2,
+        stderr: "usage: friedman var estimate <data>\nUnknown option: --trendd\n".into(),
+    };
+    let report = err.report();
+
+    assert_eq!(report.code, "unknown_option");
+    assert_eq!(report.message, "Unknown option: --trendd");
+    assert!(report.exception.is_none());
+    assert!(report.args.is_empty());
+}
+
+#[test]
+fn test_exec_errors_are_classified() {
+    let julia = FriedmanError::JuliaNotFound("julia executable not found (12 locations tried)".into());
+    assert_eq!(julia.report().code, "julia_not_found");
+    assert!(julia.report().hint.is_some());
+
+    let cli = FriedmanError::SidecarNotFound("friedman-cli binary not found (3 locations tried)".into());
+    assert_eq!(cli.report().code, "sidecar_not_found");
+
+    // The code follows the variant, not the wording of the message.
+    let spawn = FriedmanError::SpawnFailed("Permission denied".into()).in_command(&["var".into()]);
+    assert_eq!((spawn.report().code, spawn.report().kind), ("spawn_failed", "spawn_failed"));
+    assert_eq!(FriedmanError::SidecarExec("julia executable not found".into()).report().code, "sidecar_exec");
+
+    let value = serde_json::to_value(FriedmanError::InvalidParams("Dataset not found: d1".into())).unwrap();
+    assert_eq!(value["kind"], "invalid_params");
+    assert_eq!(value["message"], "Invalid parameters: Dataset not found: d1");
+    assert_eq!(value["hint"], serde_json::Value::Null);
+}
+
+#[test]
+fn test_command_wrapper_is_transparent() {
+    let args = vec!["bvar".to_string(), "estimate".to_string()];
+    let err = FriedmanError::Cancelled("job-1".into())
+        .in_command(&args)
+        .in_command(&["ignored".to_string()]);
+
+    assert_eq!(err.to_string(), "Job cancelled: job-1");
+    assert!(matches!(err.inner(), FriedmanError::Cancelled(_)));
+    assert_eq!(err.report().args, args);
+    assert_eq!(err.report().kind, "cancelled");
+}
@@ -0,0 +1,148 @@
+//! Tests for missing-value treatments.
+//!
+//!   cargo test -p friedman-app --test missing
+
+use app_lib::dataset::delimited::parse_csv;
+use app_lib::dataset::missing::{
+    reject_remaining, treat_missing, ColumnMissing, MissingPolicy, MissingReport, MissingTreatment,
+};
+use app_lib::dataset::Table;
+use app_lib::error::FriedmanError;
+
+fn table() -> Table {
+    parse_csv(b"date,x,y\n2000Q1,NA,1\n2000Q2,2,2\n2000Q3,.,3\n2000Q4,,9\n2001Q1,8,5\n2001Q2,10,NaN\n")
+        .unwrap()
+        .0
+}
+
+/// Column `c` of the treated table, as numbers.
+fn column(table: &Table, c: usize) -> Vec<Option<f64>> {
+    table.rows.iter().map(|row| row[c].parse().ok()).collect()
+}
+
+fn treat(treatment: MissingTreatment) -> (Table, Vec<usize>, MissingReport) {
+    treat_missing(table(), treatment, Some("date")).unwrap()
+}
+
+#[test]
+fn test_detection_and_no_treatment() {
+    let (out, kept, report) = treat(MissingTreatment::None);
+
+    assert_eq!(out, table());
+    assert_eq!(kept, (0..6).collect::<Vec<_>>());
+    assert_eq!(
+        report.columns,
+        vec![
+            ColumnMissing { column: "x".into(), missing: 3, filled: 0, remaining: 3 },
+            ColumnMissing { column: "y".into(), missing: 1, filled: 0, remaining: 1 },
+        ]
+    );
+    assert_eq!(report.rows_dropped, 0);
+    assert_eq!(report.warnings().len(), 2);
+    assert!(report.warnings()[0].starts_with("Column `x` still has 3 missing values"));
+}
+
+#[test]
+fn test_trim_and_listwise() {
+    let (out, kept, report) = treat(MissingTreatment::Trim);
+    assert_eq!(kept, vec![1, 2, 3, 4]);
+    assert_eq!(out.rows[0][0], "2000Q2");
+    assert_eq!((report.rows_dropped, report.columns[0].remaining), (2, 2));
+
+    let (out, kept, report) = treat(MissingTreatment::Listwise);
+    assert_eq!(kept, vec![1, 4]);
+    assert_eq!(column(&out, 1), vec![Some(2.0), Some(8.0)]);
+    assert_eq!(report.rows_dropped, 4);
+    assert!(report.warnings().is_empty());
+}
+
+#[test]
+fn test_interpolation_and_locf() {
+    let (out, kept, report) = treat(MissingTreatment::Linear);
+    assert_eq!(kept, vec![1, 2, 3, 4]);
+    assert_eq!(column(&out, 1), vec![Some(2.0), Some(4.0), Some(6.0), Some(8.0)]);
+    assert_eq!(column(&out, 2), vec![Some(2.0), Some(3.0), Some(9.0), Some(5.0)]);
+    assert_eq!((report.columns[0].filled, report.columns[0].remaining), (2, 0));
+
+    let (out, _, _) = treat(MissingTreatment::Locf);
+    assert_eq!(column(&out, 1), vec![Some(2.0), Some(2.0), Some(2.0), Some(8.0)]);
+
+    // Knots (0, 0), (1, 1), (4, 16): the natural spline's second
+    // derivative at t = 1 is 6 * (15 / 3 - 1) / (2 * 4) = 3.
+    let quadratic = parse_csv(b"x\n0\n1\nNA\nNA\n16\n").unwrap().0;
+    let (out, _, _) = treat_missing(quadratic, MissingTreatment::Spline, None).unwrap();
+    let spline = |t: f64| {
+        let (a, b) = ((4.0 - t) / 3.0, (t - 1.0) / 3.0);
+        a + 16.0 * b + (a * a * a - a) * 3.0 * 9.0 / 6.0
+    };
+    let filled = column(&out, 0);
+    assert!((filled[2].unwrap() - spline(2.0)).abs() < 1e-9, "{filled:?}");
+    assert!((filled[3].unwrap() - spline(3.0)).abs() < 1e-9, "{filled:?}");
+}
+
+#[test]
+fn test_gaps_at_the_window_edges() {
+    let treated = |csv: &[u8], treatment| {
+        let (out, kept, report) = treat_missing(parse_csv(csv).unwrap().0, treatment, None).unwrap();
+        (column(&out, 0), kept, report.columns[0].clone())
+    };
+
+    // y limits the sample to rows 1 and 2; x is filled from row 3 outside it.
+    let (x, kept, report) = treated(b"x,y\n1,NA\n2,1\nNA,2\n4,NA\n", MissingTreatment::Linear);
+    assert_eq!((x, kept), (vec![Some(2.0), Some(3.0)], vec![1, 2]));
+    assert_eq!((report.filled, report.remaining), (1, 0));
+
+    // A gap at the start of the sample is carried forward from before it.
+    let (x, _, _) = treated(b"x,y\n1,NA\nNA,1\n3,2\n4,NA\n", MissingTreatment::Locf);
+    assert_eq!(x, vec![Some(1.0), Some(3.0)]);
+
+    // x has no values inside the sample at all.
+    let (x, _, report) = treated(b"x,y\n1,NA\nNA,5\nNA,6\n4,NA\n", MissingTreatment::Spline);
+    assert_eq!(x, vec![Some(2.0), Some(3.0)]);
+    assert_eq!((report.missing, report.filled, report.remaining), (2, 2, 0));
+
+    // Listwise deletion leaves nothing to fill; trim leaves interior gaps.
+    let (_, _, report) = treated(b"x,y\n1,1\nNA,2\n3,3\n", MissingTreatment::Trim);
+    assert_eq!((report.filled, report.remaining), (0, 1));
+}
+
+#[test]
+fn test_custom_tokens_and_errors() {
+    let policy: MissingPolicy = serde_json::from_str(r#"{"treatment": "listwise", "tokens": ["-999"]}"#).unwrap();
+    let mut coded = parse_csv(b"x,y\n1,-999\n2,3\n").unwrap().0;
+    policy.blank_tokens(&mut coded);
+    assert_eq!(coded.rows[0], vec!["1", ""]);
+    let (_, kept, _) = treat_missing(coded, policy.treatment, None).unwrap();
+    assert_eq!(kept, vec![1]);
+    assert_eq!(MissingPolicy::default().treatment, MissingTreatment::None);
+
+    let message = |csv: &[u8], treatment| match treat_missing(parse_csv(csv).unwrap().0, treatment, None) {
+        Err(FriedmanError::InvalidParams(msg)) => msg,
+        other => panic!("expected InvalidParams, got {other:?}"),
+    };
+    assert_eq!(message(b"x,y\n1,NA\n2,NA\n", MissingTreatment::Trim), "Column `y` has no values");
+    assert_eq!(
+        message(b"x,y\n1,NA\nNA,2\n", MissingTreatment::Listwise),
+        "No observations are left after treating missing values"
+    );
+    assert_eq!(
+        message(b"x,y\n1,2\n-inf,3\n", MissingTreatment::Linear),
+        "Column `x` has an infinite value (`-inf`) in row 2"
+    );
+}
+
+#[test]
+fn test_untreated_missing_values_are_refused() {
+    let (out, kept, report) = treat(MissingTreatment::None);
+    let kept: Vec<usize> = kept.iter().map(|r| r + 10).collect();
+    match reject_remaining(&out, &kept, &report) {
+        Err(FriedmanError::InvalidParams(msg)) => assert_eq!(
+            msg,
+            "Column `x` has a missing value (`NA`) at observation 11; choose a missing-value treatment"
+        ),
+        other => panic!("expected InvalidParams, got {other:?}"),
+    }
+
+    let (out, kept, report) = treat(MissingTreatment::Linear);
+    assert!(reject_remaining(&out, &kept, &report).is_ok());
+}
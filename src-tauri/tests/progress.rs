@@ -0,0 +1,34 @@
+//! Tests for parsing sidecar stderr into progress events.
+//!
+//!   cargo test -p friedman-app --test progress
+
+use app_lib::sidecar::progress::{parse_line, ProgressKind};
+
+fn progress(line: &str) -> (ProgressKind, Option<String>, Option<f64>) {
+    let event = parse_line("job", line).unwrap();
+    (event.kind, event.stage, event.progress)
+}
+
+#[test]
+fn test_percent_bars() {
+    assert_eq!(
+        progress("Sampling:  45%|████      |  ETA: 0:00:10"),
+        (ProgressKind::Progress, Some("Sampling".into()), Some(0.45))
+    );
+    assert_eq!(progress("Sampling: 80%"), (ProgressKind::Progress, Some("Sampling".into()), Some(0.8)));
+    assert_eq!(progress("\r  12%\r  50%"), (ProgressKind::Progress, None, Some(0.5)));
+    // A multibyte bar character right before the number.
+    assert_eq!(
+        progress("Progress: ▕45%▏"),
+        (ProgressKind::Progress, Some("Progress".into()), Some(0.45))
+    );
+    assert_eq!(progress("Progress: ██▌ 100%"), (ProgressKind::Progress, Some("Progress".into()), Some(1.0)));
+}
+
+#[test]
+fn test_percent_in_prose_is_not_progress() {
+    assert_eq!(progress("We reject the null at 5%").0, ProgressKind::Log);
+    assert_eq!(progress("Coverage 95% of the posterior mass").0, ProgressKind::Log);
+    assert_eq!(progress("α ≈ 5% level").0, ProgressKind::Log);
+    assert_eq!(progress("Bootstrap 50/1000").0, ProgressKind::Iteration);
+}
@@ -0,0 +1,113 @@
+//! Tests for decoding sidecar stdout: the framed NDJSON protocol and the
+//! `extract_json` fallback used for older CLIs.
+//!
+//!   cargo test -p friedman-app --test protocol
+
+use app_lib::error::FriedmanError;
+use app_lib::sidecar::protocol::decode;
+use app_lib::sidecar::{parse_output, SidecarOutput};
+use serde_json::json;
+
+// ===========================================================================
+// Framed protocol
+// ===========================================================================
+
+#[test]
+fn test_framed_multiple_results() {
+    let stdout = r#"{"type":"hello","protocol":"ndjson","version":1}
+{"type":"log","message":"Johansen cointegration test [trace]"}
+{"type":"result","data":{"rank":1}}
+{"type":"warning","message":"small sample"}
+{"type":"result","data":[1,2,3]}
+"#;
+    let decoded = decode(stdout).unwrap();
+
+    assert!(decoded.framed);
+    assert_eq!(decoded.results, vec![json!({ "rank": 1 }), json!([1, 2, 3])]);
+    assert_eq!(decoded.text, vec!["Johansen cointegration test [trace]"]);
+    assert_eq!(decoded.warnings, vec!["small sample"]);
+    assert_eq!(decoded.into_output(Vec::new()).payload, json!([{ "rank": 1 }, [1, 2, 3]]));
+}
+
+#[test]
+fn test_framed_single_result_and_noise() {
+    let stdout = r#"
+{"type":"hello","protocol":"ndjson","version":1}
+  Activating project at `sidecar`
+{"type":"result","data":{"statistic":-3.1}}
+"#;
+    let decoded = decode(stdout).unwrap();
+
+    assert_eq!(decoded.text, vec!["Activating project at `sidecar`"]);
+    assert_eq!(decoded.into_output(Vec::new()).payload, json!({ "statistic": -3.1 }));
+}
+
+#[test]
+fn test_hello_after_other_output_is_not_framed() {
+    let stdout = r#"Precompiling Friedman...
+{"type":"hello","protocol":"ndjson","version":1}
+Warning: slow mixing
+"#;
+    let decoded = decode(stdout).unwrap();
+
+    assert!(!decoded.framed);
+    assert_eq!(decoded.text, vec!["Precompiling Friedman..."]);
+    assert_eq!(decoded.warnings, vec!["slow mixing"]);
+}
+
+#[test]
+fn test_framed_without_result_is_an_error() {
+    let stdout = "{\"type\":\"hello\",\"protocol\":\"ndjson\"}\n{\"type\":\"log\",\"message\":\"done\"}\n";
+    assert!(matches!(decode(stdout), Err(FriedmanError::JsonParse(_))));
+}
+
+// ===========================================================================
+// Legacy fallback
+// ===========================================================================
+
+#[test]
+fn test_legacy_output_keeps_narrative() {
+    let stdout = "Augmented Dickey-Fuller test [constant]\n\
+                  {\"statistic\": -2.5, \"lags\": [1, 2]}\n\
+                  Warning: only 40 observations\n\
+                  Conclusion: reject the null at 5%\n";
+    let decoded = decode(stdout).unwrap();
+
+    assert!(!decoded.framed);
+    assert_eq!(
+        decoded.text,
+        vec!["Augmented Dickey-Fuller test [constant]", "Conclusion: reject the null at 5%"]
+    );
+    assert_eq!(decoded.warnings, vec!["only 40 observations"]);
+    assert_eq!(decoded.results, vec![json!({ "statistic": -2.5, "lags": [1, 2] })]);
+}
+
+#[test]
+fn test_legacy_skips_bracketed_text_before_json() {
+    let header = "[ Info: loading [constant] {trend}\n".repeat(200);
+    let stdout = format!("{header}{{\"rank\": [1, 2]}} done\n");
+    let decoded = decode(&stdout).unwrap();
+
+    assert_eq!(decoded.results, vec![json!({ "rank": [1, 2] })]);
+    assert_eq!(decoded.text.len(), 201);
+    assert_eq!(decoded.text[200], "done");
+    assert!(matches!(
+        decode("[ Info: loading\n{\"rank\": 1"),
+        Err(FriedmanError::JsonParse(msg)) if msg.starts_with("Unmatched")
+    ));
+}
+
+#[test]
+fn test_stderr_warnings_join_the_output() {
+    let output = parse_output(SidecarOutput {
+        code: 0,
+        stdout: "{\"rank\": 2}".into(),
+        stderr: "┌ Warning: near-singular covariance\n│ cond = 1e12\n└ @ Friedman".into(),
+        stdout_file: None,
+    })
+    .unwrap();
+
+    assert_eq!(output.payload, json!({ "rank": 2 }));
+    assert!(output.text.is_empty());
+    assert_eq!(output.warnings, vec!["near-singular covariance"]);
+}
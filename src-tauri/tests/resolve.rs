@@ -0,0 +1,81 @@
+//! Tests for the order in which explicit sidecar locations are tried.
+//!
+//!   cargo test -p friedman-app --test resolve
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use app_lib::settings::SidecarSettings;
+use app_lib::sidecar::resolve::resolve_explicit;
+use app_lib::sidecar::SidecarMode;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("friedman-resolve-test-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn executable(path: &Path) -> PathBuf {
+    fs::write(path, "#!/bin/sh\n").unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    path.to_path_buf()
+}
+
+fn project(dir: &Path) -> PathBuf {
+    fs::create_dir_all(dir).unwrap();
+    fs::write(dir.join("main.jl"), "").unwrap();
+    fs::write(dir.join("Project.toml"), "").unwrap();
+    dir.to_path_buf()
+}
+
+fn resolved(settings: &SidecarSettings) -> SidecarMode {
+    resolve_explicit(settings).expect("a location should be usable").unwrap()
+}
+
+/// Environment variables are process-wide, so every case runs in this one
+/// test.
+#[test]
+fn test_environment_overrides_settings() {
+    let dir = temp_dir("order");
+    let settings = SidecarSettings {
+        julia: Some(executable(&dir.join("julia"))),
+        project_dir: Some(project(&dir.join("settings-project"))),
+        binary: Some(executable(&dir.join("settings-cli"))),
+    };
+    let env_project = project(&dir.join("env-project"));
+    let env_binary = executable(&dir.join("env-cli"));
+    for var in ["FRIEDMAN_CLI_BINARY", "FRIEDMAN_SIDECAR_PROJECT", "FRIEDMAN_JULIA"] {
+        std::env::remove_var(var);
+    }
+
+    // Settings alone: the binary wins over the project.
+    assert!(matches!(resolved(&settings), SidecarMode::Binary(path) if path == dir.join("settings-cli")));
+
+    // A project from the environment beats a binary from settings.
+    std::env::set_var("FRIEDMAN_SIDECAR_PROJECT", &env_project);
+    match resolved(&settings) {
+        SidecarMode::Julia { project_dir, julia, .. } => {
+            assert_eq!((project_dir, julia), (env_project.clone(), dir.join("julia")));
+        }
+        other => panic!("expected the environment project, got {other:?}"),
+    }
+
+    // Between environment variables the binary still comes first.
+    std::env::set_var("FRIEDMAN_CLI_BINARY", &env_binary);
+    assert!(matches!(resolved(&settings), SidecarMode::Binary(path) if path == env_binary));
+
+    // An unusable override falls through to settings.
+    std::env::set_var("FRIEDMAN_CLI_BINARY", dir.join("missing"));
+    std::env::set_var("FRIEDMAN_SIDECAR_PROJECT", dir.join("missing"));
+    assert!(matches!(resolved(&settings), SidecarMode::Binary(path) if path == dir.join("settings-cli")));
+
+    std::env::remove_var("FRIEDMAN_CLI_BINARY");
+    std::env::remove_var("FRIEDMAN_SIDECAR_PROJECT");
+    assert!(resolve_explicit(&SidecarSettings::default()).is_none());
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,95 @@
+//! Tests for the resource limits and scratch directories applied to sidecar
+//! processes. The spawn tests use `sh` in place of Julia.
+//!
+//!   cargo test -p friedman-app --test resources
+
+use app_lib::settings::ResourceSettings;
+use app_lib::sidecar::resources::{ProcessResources, Scratch};
+
+fn scratch_base(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("friedman-resources-test-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn test_validate_rejects_out_of_range_values() {
+    assert!(ResourceSettings::default().validate().is_ok());
+    for settings in [
+        ResourceSettings { threads: Some(0), ..Default::default() },
+        ResourceSettings { nice: Some(-5), ..Default::default() },
+        ResourceSettings { nice: Some(20), ..Default::default() },
+        ResourceSettings { memory_limit_mb: Some(0), ..Default::default() },
+    ] {
+        assert!(settings.validate().is_err(), "{settings:?} should be rejected");
+    }
+}
+
+#[test]
+fn test_scratch_is_per_job_and_removed_on_drop() {
+    let base = scratch_base("drop");
+    let settings = ResourceSettings {
+        scratch_dir: Some(base.clone()),
+        ..Default::default()
+    };
+
+    let scratch = Scratch::create(&settings, "job/1").unwrap();
+    let path = scratch.path().to_path_buf();
+    assert_eq!(path, base.join("friedman-job_1"));
+    assert!(path.is_dir());
+    assert!(scratch
+        .env()
+        .iter()
+        .any(|(k, v)| k == "TMPDIR" && v == &path.display().to_string()));
+
+    drop(scratch);
+    assert!(!path.exists());
+    let _ = std::fs::remove_dir_all(&base);
+}
+
+#[test]
+fn test_scratch_kept_when_requested() {
+    let base = scratch_base("keep");
+    let settings = ResourceSettings {
+        scratch_dir: Some(base.clone()),
+        keep_scratch: true,
+        ..Default::default()
+    };
+
+    let path = Scratch::create(&settings, "job-2").unwrap().path().to_path_buf();
+    assert!(path.is_dir());
+    let _ = std::fs::remove_dir_all(&base);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_limits_applied_to_spawned_process() {
+    let base = scratch_base("spawn");
+    let settings = ResourceSettings {
+        threads: Some(3),
+        nice: Some(19),
+        scratch_dir: Some(base.clone()),
+        ..Default::default()
+    };
+    let resources = ProcessResources::effective(&settings);
+    assert_eq!(resources.threads, Some(3));
+    assert_eq!(resources.nice, Some(19));
+
+    let scratch = Scratch::create(&settings, "job-3").unwrap();
+    let mut cmd = tokio::process::Command::new("sh");
+    cmd.arg("-c")
+        .arg("echo \"$JULIA_NUM_THREADS $OPENBLAS_NUM_THREADS $TMPDIR\"; nice");
+    resources.apply(&mut cmd);
+    scratch.apply(&mut cmd);
+    let output = cmd.output().await.unwrap();
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut lines = text.lines();
+
+    assert_eq!(
+        lines.next(),
+        Some(format!("3 3 {}", scratch.path().display()).as_str())
+    );
+    assert_eq!(lines.next(), Some("19"));
+    drop(scratch);
+    let _ = std::fs::remove_dir_all(&base);
+}
@@ -0,0 +1,216 @@
+//! Offline tests for the sidecar runners.
+//!
+//! These exercise `FakeRunner` and `ReplayRunner` the way `run_job` drives
+//! them (full args including `--format=json`, output parsed with
+//! `parse_output`), so they need neither Julia nor a running app.
+//!
+//!   cargo test -p friedman-app --test runner
+
+use std::fs;
+use std::sync::Arc;
+
+use app_lib::error::FriedmanError;
+use app_lib::sidecar::jobs::JobRegistry;
+use app_lib::sidecar::parse_output;
+use app_lib::sidecar::runner::{FakeRunner, RecordingRunner, ReplayRunner, SidecarRunner};
+use app_lib::sidecar::scheduler::Priority;
+use app_lib::sidecar::transcript::{self, TranscriptEntry};
+use app_lib::sidecar::watchdog::Limits;
+use app_lib::sidecar::OutputSink;
+use serde_json::json;
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+/// Run `args` through `runner` and parse the result like `run_job` does,
+/// returning the payload.
+async fn run(runner: &dyn SidecarRunner, args: &[String]) -> Result<serde_json::Value, FriedmanError> {
+    let jobs = JobRegistry::default();
+    let mut job = jobs.register("test-job", args, Priority::for_args(args))?;
+    let sink = OutputSink::detached(&job.id.clone());
+    runner
+        .run(&mut job, &sink, Limits::default(), args)
+        .await
+        .and_then(parse_output)
+        .map(|output| output.payload)
+}
+
+fn entry(list: &[&str], stdout: &str) -> TranscriptEntry {
+    TranscriptEntry {
+        args: args(list),
+        code: 0,
+        stdout: stdout.into(),
+        stderr: String::new(),
+        duration_ms: 0,
+    }
+}
+
+// ===========================================================================
+// FakeRunner
+// ===========================================================================
+
+#[tokio::test]
+async fn test_fake_runner_canned_response() {
+    let runner = FakeRunner::new();
+    runner.respond("test adf", json!({ "statistic": -3.2, "pvalue": 0.02 }));
+
+    let call = args(&["test", "adf", "data.csv", "--column", "1", "--format=json"]);
+    let value = run(&runner, &call).await.unwrap();
+
+    assert_eq!(value["pvalue"], json!(0.02));
+    assert_eq!(runner.calls(), vec![call]);
+}
+
+#[tokio::test]
+async fn test_fake_runner_group_fallback_and_default() {
+    let runner = FakeRunner::new();
+    runner.respond("var", json!({ "group": "var" }));
+
+    let value = run(&runner, &args(&["var", "irf", "d.csv", "--format=json"])).await.unwrap();
+    assert_eq!(value["group"], json!("var"));
+
+    let value = run(&runner, &args(&["lp", "estimate", "d.csv", "--format=json"])).await.unwrap();
+    assert_eq!(value["fake"], json!(true));
+    assert_eq!(value["command"], json!("lp estimate"));
+}
+
+#[tokio::test]
+async fn test_fake_runner_failure() {
+    let runner = FakeRunner::new();
+    runner.fail("bvar estimate", 1, "ERROR: PosDefException");
+
+    let err = run(&runner, &args(&["bvar", "estimate", "d.csv", "--format=json"]))
+        .await
+        .unwrap_err();
+    match err {
+        FriedmanError::SidecarExit { code, stderr } => {
+            assert_eq!(code, 1);
+            assert!(stderr.contains("PosDefException"));
+        }
+        other => panic!("expected SidecarExit, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_fake_runner_from_dir() {
+    let dir = std::env::temp_dir().join(format!("friedman-fake-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("arima_estimate.json"), r#"{"aic": 101.5}"#).unwrap();
+
+    let runner = FakeRunner::from_dir(&dir).unwrap();
+    let value = run(&runner, &args(&["arima", "estimate", "d.csv", "--format=json"])).await.unwrap();
+    assert_eq!(value["aic"], json!(101.5));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// ===========================================================================
+// ReplayRunner
+// ===========================================================================
+
+#[tokio::test]
+async fn test_replay_runner_matches_portable_args() {
+    let runner = ReplayRunner::new(vec![entry(
+        &["test", "kpss", "/home/lab/data/gdp.csv", "--format=json"],
+        r#"{"statistic": 0.3}"#,
+    )]);
+
+    // Same file name, different machine.
+    let value = run(&runner, &args(&["test", "kpss", "/tmp/x/gdp.csv", "--format=json"]))
+        .await
+        .unwrap();
+    assert_eq!(value["statistic"], json!(0.3));
+}
+
+#[tokio::test]
+async fn test_replay_runner_walks_repeated_recordings() {
+    let call = ["var", "estimate", "d.csv", "--format=json"];
+    let runner = ReplayRunner::new(vec![
+        entry(&call, r#"{"run": 1}"#),
+        entry(&call, r#"{"run": 2}"#),
+    ]);
+
+    let mut seen = Vec::new();
+    for _ in 0..3 {
+        seen.push(run(&runner, &args(&call)).await.unwrap()["run"].clone());
+    }
+    assert_eq!(seen, vec![json!(1), json!(2), json!(2)]);
+}
+
+#[tokio::test]
+async fn test_replay_runner_other_args_only_when_lenient() {
+    let recorded = entry(&["var", "irf", "d.csv", "--horizons=12", "--format=json"], r#"{"horizons": 12}"#);
+    let call = args(&["var", "irf", "d.csv", "--horizons=24", "--format=json"]);
+
+    let strict = ReplayRunner::new(vec![recorded.clone()]);
+    assert!(matches!(run(&strict, &call).await, Err(FriedmanError::SidecarExec(_))));
+
+    let lenient = ReplayRunner::new(vec![recorded]).with_lenient(true);
+    assert_eq!(run(&lenient, &call).await.unwrap()["horizons"], json!(12));
+}
+
+#[tokio::test]
+async fn test_replay_runner_missing_transcript() {
+    let runner = ReplayRunner::new(vec![entry(&["var", "estimate", "d.csv"], "{}")]);
+
+    let err = run(&runner, &args(&["gmm", "estimate", "d.csv", "--format=json"]))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, FriedmanError::SidecarExec(msg) if msg.contains("gmm estimate")));
+}
+
+// ===========================================================================
+// RecordingRunner
+// ===========================================================================
+
+#[tokio::test]
+async fn test_recorded_transcripts_replay() {
+    let dir = std::env::temp_dir().join(format!("friedman-record-{}", std::process::id()));
+    let fake = Arc::new(FakeRunner::new());
+    fake.respond("test adf", json!({ "statistic": -2.9 }));
+    fake.fail("test kpss", 1, "ERROR: ArgumentError");
+
+    let recorder = RecordingRunner::new(fake, dir.clone());
+    run(&recorder, &args(&["test", "adf", "/a/gdp.csv", "--format=json"])).await.unwrap();
+    run(&recorder, &args(&["test", "kpss", "/a/gdp.csv", "--format=json"])).await.unwrap_err();
+
+    let replay = ReplayRunner::from_dir(&dir).unwrap();
+    let value = run(&replay, &args(&["test", "adf", "/b/gdp.csv", "--format=json"])).await.unwrap();
+    assert_eq!(value["statistic"], json!(-2.9));
+    let err = run(&replay, &args(&["test", "kpss", "/b/gdp.csv", "--format=json"]))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, FriedmanError::SidecarExit { code: 1, .. }));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_spooled_stdout_is_streamed_into_the_transcript() {
+    let dir = std::env::temp_dir().join(format!("friedman-record-spool-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let spool = dir.join("stdout.spool");
+    // Longer than one 64 KiB read, with `é` split across the first two.
+    let mut stdout = "a\"b\\c\n\t\u{1}".repeat(8191);
+    stdout.push_str("1234567é ∑ 🎲 done");
+    assert_eq!(stdout.find('é'), Some(65535));
+    let mut bytes = stdout.clone().into_bytes();
+    bytes.extend_from_slice(b" \xff end");
+    fs::write(&spool, &bytes).unwrap();
+
+    let mut recorded = entry(&["var", "estimate", "data.csv", "--format=json"], "");
+    recorded.stderr = "ERROR: \"x\"".into();
+    let path = transcript::write_entry_from_file(&dir.join("out"), &recorded, &spool).unwrap();
+
+    let loaded = transcript::load_dir(&dir.join("out")).unwrap();
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].stdout, format!("{stdout} \u{fffd} end"));
+    assert_eq!((loaded[0].args.clone(), loaded[0].stderr.clone()), (recorded.args, recorded.stderr));
+    assert!(path.exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
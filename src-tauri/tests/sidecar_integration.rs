@@ -1580,6 +1580,87 @@ fn test_nongaussian_identifiability_strict() {
     assert_success("nongaussian identifiability (all)", &output);
 }
 
+// ===========================================================================
+// Persistent session (server.jl)
+// ===========================================================================
+
+/// Write one `FRIEDMAN-REQ` frame, mirroring `SessionProcess::request` in
+/// `sidecar/session.rs`.
+fn write_session_request(stdin: &mut impl std::io::Write, args: &[&str]) {
+    writeln!(stdin, "FRIEDMAN-REQ {}", args.len()).unwrap();
+    for arg in args {
+        writeln!(stdin, "{}\n{}", arg.len(), arg).unwrap();
+    }
+    stdin.flush().unwrap();
+}
+
+/// Read lines until the `FRIEDMAN-RESP` header, then the framed stdout.
+fn read_session_response(stdout: &mut impl std::io::BufRead) -> (i32, String) {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        assert!(stdout.read_line(&mut line).unwrap() > 0, "session closed stdout");
+        if let Some(rest) = line.strip_prefix("FRIEDMAN-RESP ") {
+            let mut parts = rest.split_whitespace();
+            let code: i32 = parts.next().unwrap().parse().unwrap();
+            let len: usize = parts.next().unwrap().parse().unwrap();
+            let mut buf = vec![0u8; len];
+            stdout.read_exact(&mut buf).unwrap();
+            return (code, String::from_utf8_lossy(&buf).to_string());
+        }
+    }
+}
+
+/// Two requests through one session: both must come back framed, with JSON
+/// in the captured stdout.
+#[test]
+#[ignore]
+fn test_session_roundtrip() {
+    use std::io::BufRead;
+    use std::process::Stdio;
+
+    let script = sidecar_project_dir().join("server.jl");
+    assert!(script.exists(), "Session script not found at {}", script.display());
+
+    let mut child = Command::new(julia_path())
+        .arg(format!("--project={}", sidecar_project_dir().display()))
+        .arg("--startup-file=no")
+        .arg(&script)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .expect("Failed to spawn Julia session");
+
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stdout = std::io::BufReader::new(child.stdout.take().unwrap());
+
+    let mut line = String::new();
+    loop {
+        line.clear();
+        assert!(stdout.read_line(&mut line).unwrap() > 0, "session exited before ready");
+        if line.trim_end() == "FRIEDMAN-READY" {
+            break;
+        }
+    }
+
+    let csv = test_csv();
+    let c = csv.to_str().unwrap();
+    for args in [
+        vec!["var", "estimate", c, "--lags", "1", "--trend", "constant", "--format=json"],
+        vec!["test", "adf", c, "--column", "2", "--trend", "constant", "--format=json"],
+    ] {
+        write_session_request(&mut stdin, &args);
+        let (code, out) = read_session_response(&mut stdout);
+        assert_eq!(code, 0, "session request {args:?} failed");
+        assert!(extract_json_from_stdout(&out).is_some(), "no JSON in session output: {out}");
+    }
+
+    drop(stdin);
+    let status = child.wait().unwrap();
+    assert!(status.success(), "session did not exit cleanly on EOF");
+}
+
 // ===========================================================================
 // Smoke tests
 // ===========================================================================
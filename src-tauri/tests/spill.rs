@@ -0,0 +1,178 @@
+//! Tests for spooling large stdout to disk and spilling large arrays out of
+//! results, using a tiny threshold.
+//!
+//!   cargo test -p friedman-app --test spill
+
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use app_lib::sidecar::protocol::decode_file;
+use app_lib::sidecar::spill::{JsonReader, SpillStore, StdoutBuffer, SPILL_KEY};
+use app_lib::sidecar::{parse_output_spilling, SidecarOutput};
+use serde_json::{json, Value};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("friedman-spill-test-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn rows(n: usize) -> Value {
+    Value::Array((0..n).map(|i| json!([i, i as f64 * 0.5, "x"])).collect())
+}
+
+fn handle(value: &Value) -> &str {
+    value[SPILL_KEY]["handle"].as_str().expect("value should be spilled")
+}
+
+#[test]
+fn test_reader_matches_serde_without_spilling() {
+    let doc = json!({
+        "a": [1, -2.5, 3e-4, true, null, "q\"uo\\te \u{e9}"],
+        "nested": { "empty": [], "obj": {}, "rows": rows(50) },
+    });
+    let text = serde_json::to_string_pretty(&doc).unwrap();
+    let mut reader = JsonReader::new(Cursor::new(text.as_bytes()), None);
+
+    assert_eq!(reader.next_value().unwrap(), doc);
+}
+
+#[test]
+fn test_large_array_is_spilled_and_paged() {
+    let dir = temp_dir("page");
+    let store = SpillStore::new(dir.clone(), 256);
+    let doc = json!({ "hd": rows(200), "meta": { "n": 200, "small": [1, 2, 3] } });
+    let text = doc.to_string();
+
+    let value = JsonReader::new(Cursor::new(text.as_bytes()), Some(&store))
+        .next_value()
+        .unwrap();
+
+    assert_eq!(value["meta"], doc["meta"]);
+    assert_eq!(value["hd"][SPILL_KEY]["length"], 200);
+    let handle = handle(&value["hd"]).to_string();
+
+    let page = store.page(&handle, 10, 5).unwrap();
+    assert_eq!(page.length, 200);
+    assert_eq!(page.items, doc["hd"].as_array().unwrap()[10..15].to_vec());
+    let tail = store.page(&handle, 198, 50).unwrap();
+    assert_eq!(tail.items.len(), 2);
+    assert!(store.page(&handle, 500, 5).unwrap().items.is_empty());
+
+    store.release(&handle);
+    assert!(store.page(&handle, 0, 1).is_err());
+    store.clear();
+    assert!(!dir.exists());
+}
+
+#[test]
+fn test_decode_file_framed() {
+    let dir = temp_dir("framed");
+    let store = SpillStore::new(dir.join("spills"), 256);
+    let path = dir.join("stdout.txt");
+    let result = json!({ "type": "result", "data": { "draws": rows(100), "accept": 0.3 } });
+    fs::write(
+        &path,
+        format!(
+            "{}\n  Activating project\n{}\n[ Info: not a frame\n{}\n{}\n",
+            json!({ "type": "hello", "protocol": "ndjson", "version": 1 }),
+            json!({ "type": "log", "message": "BVAR posterior" }),
+            result,
+            json!({ "type": "warning", "message": "slow mixing" }),
+        ),
+    )
+    .unwrap();
+
+    let decoded = decode_file(&path, Some(&store)).unwrap();
+
+    assert!(decoded.framed);
+    assert_eq!(decoded.text, vec!["Activating project", "BVAR posterior", "[ Info: not a frame"]);
+    assert_eq!(decoded.warnings, vec!["slow mixing"]);
+    assert_eq!(decoded.results[0]["accept"], 0.3);
+    let handle = handle(&decoded.results[0]["draws"]);
+    assert_eq!(store.page(handle, 0, 1).unwrap().items, vec![json!([0, 0.0, "x"])]);
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_decode_file_legacy() {
+    let dir = temp_dir("legacy");
+    let path = dir.join("stdout.txt");
+    fs::write(
+        &path,
+        "Augmented Dickey-Fuller test [constant]\n[ Info: loading\nWarning: only 40 observations\n\
+         {\n  \"statistic\": -2.5,\n  \"lags\": [1, 2]\n}\nConclusion: reject\n",
+    )
+    .unwrap();
+
+    let decoded = decode_file(&path, None).unwrap();
+
+    assert!(!decoded.framed);
+    assert_eq!(decoded.results, vec![json!({ "statistic": -2.5, "lags": [1, 2] })]);
+    assert_eq!(
+        decoded.text,
+        vec!["Augmented Dickey-Fuller test [constant]", "[ Info: loading", "Conclusion: reject"]
+    );
+    assert_eq!(decoded.warnings, vec!["only 40 observations"]);
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_decode_file_status_lines_and_trailing_text() {
+    let dir = temp_dir("status");
+    let path = dir.join("stdout.txt");
+    fs::write(
+        &path,
+        "[1/3] Fitting model\n[2/3] Bootstrap\n{\"statistic\": -2.5} (p = 0.12)\n[3/3] Done\n[1, 2]\n",
+    )
+    .unwrap();
+
+    let decoded = decode_file(&path, None).unwrap();
+    let stdout = fs::read_to_string(&path).unwrap();
+    let in_memory = app_lib::sidecar::protocol::decode(&stdout).unwrap();
+
+    assert_eq!(decoded.results, vec![json!({ "statistic": -2.5 })]);
+    assert_eq!(
+        decoded.text,
+        vec!["[1/3] Fitting model", "[2/3] Bootstrap", "(p = 0.12)", "[3/3] Done", "[1,2]"]
+    );
+    assert_eq!(in_memory.results, decoded.results);
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_stdout_buffer_spools_and_output_file_is_removed() {
+    let dir = temp_dir("spool");
+    let text = json!({ "irf": rows(40) }).to_string();
+
+    let mut small = StdoutBuffer::new(dir.clone(), 1 << 20);
+    small.extend(text.as_bytes()).unwrap();
+    assert_eq!(small.finish().unwrap(), (text.clone(), None));
+
+    let mut buf = StdoutBuffer::new(dir.clone(), 64);
+    for chunk in text.as_bytes().chunks(50) {
+        buf.extend(chunk).unwrap();
+    }
+    let (stdout, file) = buf.finish().unwrap();
+    let file = file.expect("stdout should be spooled");
+    assert!(stdout.is_empty());
+    assert_eq!(fs::read_to_string(&file).unwrap(), text);
+
+    let store = SpillStore::new(dir.join("spills"), 64);
+    let output = parse_output_spilling(
+        SidecarOutput {
+            code: 0,
+            stdout,
+            stderr: String::new(),
+            stdout_file: Some(file.clone()),
+        },
+        &store,
+    )
+    .unwrap();
+
+    assert!(!file.exists());
+    assert_eq!(output.payload["irf"][SPILL_KEY]["length"], 40);
+    let _ = fs::remove_dir_all(&dir);
+}
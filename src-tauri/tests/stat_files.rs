@@ -0,0 +1,346 @@
+//! Tests for reading Stata and SPSS files, built byte by byte.
+//!
+//!   cargo test -p friedman-app --test stat_files
+
+use app_lib::dataset::period::{days_from_civil, detect_time_index, Frequency};
+use app_lib::dataset::spss::parse_sav;
+use app_lib::dataset::stata::parse_dta;
+use app_lib::dataset::ValueLabel;
+use app_lib::error::FriedmanError;
+
+fn padded(text: &str, width: usize) -> Vec<u8> {
+    let mut bytes = text.as_bytes().to_vec();
+    bytes.resize(width, 0);
+    bytes
+}
+
+fn labels(pairs: &[(&str, &str)]) -> Vec<ValueLabel> {
+    pairs
+        .iter()
+        .map(|(value, label)| ValueLabel { value: value.to_string(), label: label.to_string() })
+        .collect()
+}
+
+/// Stata value label table body for `entries`.
+fn label_table(entries: &[(i32, &str)], big_endian: bool) -> Vec<u8> {
+    let u32b = |v: u32| if big_endian { v.to_be_bytes() } else { v.to_le_bytes() };
+    let mut text = Vec::new();
+    let mut offsets = Vec::new();
+    for (_, label) in entries {
+        offsets.push(text.len() as u32);
+        text.extend_from_slice(label.as_bytes());
+        text.push(0);
+    }
+    let mut out = Vec::new();
+    out.extend(u32b(entries.len() as u32));
+    out.extend(u32b(text.len() as u32));
+    offsets.iter().for_each(|o| out.extend(u32b(*o)));
+    entries.iter().for_each(|(v, _)| out.extend(u32b(*v as u32)));
+    out.extend(text);
+    out
+}
+
+// ===========================================================================
+// Stata
+// ===========================================================================
+
+/// A format 118 file: quarterly date, float with a missing value, labelled
+/// byte, fixed string and strL.
+fn dta118() -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut map = vec![0u64; 14];
+    let tag = |out: &mut Vec<u8>, map: &mut Vec<u64>, index: usize, text: &str| {
+        map[index] = out.len() as u64;
+        out.extend_from_slice(text.as_bytes());
+    };
+
+    out.extend_from_slice(b"<stata_dta><header><release>118</release><byteorder>LSF</byteorder><K>");
+    out.extend(5u16.to_le_bytes());
+    out.extend_from_slice(b"</K><N>");
+    out.extend(3u64.to_le_bytes());
+    out.extend_from_slice(b"</N><label>");
+    out.extend(4u16.to_le_bytes());
+    out.extend_from_slice(b"Test</label><timestamp>");
+    out.push(17);
+    out.extend_from_slice(b" 1 Jan 2024 12:00</timestamp></header>");
+    tag(&mut out, &mut map, 1, "<map>");
+    let map_at = out.len();
+    out.extend(vec![0u8; 14 * 8]);
+    out.extend_from_slice(b"</map>");
+
+    tag(&mut out, &mut map, 2, "<variable_types>");
+    for t in [65526u16, 65527, 65530, 8, 32768] {
+        out.extend(t.to_le_bytes());
+    }
+    out.extend_from_slice(b"</variable_types>");
+    tag(&mut out, &mut map, 3, "<varnames>");
+    for name in ["qdate", "gdp", "region", "note", "memo"] {
+        out.extend(padded(name, 129));
+    }
+    out.extend_from_slice(b"</varnames>");
+    tag(&mut out, &mut map, 4, "<sortlist>");
+    out.extend(vec![0u8; 6 * 2]);
+    out.extend_from_slice(b"</sortlist>");
+    tag(&mut out, &mut map, 5, "<formats>");
+    for format in ["%tq", "%9.0g", "%8.0g", "%9s", "%9s"] {
+        out.extend(padded(format, 57));
+    }
+    out.extend_from_slice(b"</formats>");
+    tag(&mut out, &mut map, 6, "<value_label_names>");
+    for name in ["", "", "regionlbl", "", ""] {
+        out.extend(padded(name, 129));
+    }
+    out.extend_from_slice(b"</value_label_names>");
+    tag(&mut out, &mut map, 7, "<variable_labels>");
+    for label in ["", "Real GDP growth", "", "", ""] {
+        out.extend(padded(label, 321));
+    }
+    out.extend_from_slice(b"</variable_labels>");
+    tag(&mut out, &mut map, 8, "<characteristics>");
+    out.extend_from_slice(b"</characteristics>");
+
+    tag(&mut out, &mut map, 9, "<data>");
+    let rows: [(f64, u32, i8, &str, u64); 3] = [
+        (160.0, 1.5f32.to_bits(), 1, "a", 1),
+        (161.0, 0x7f00_0000, 2, "b\u{e9}", 0),
+        (162.0, (-0.25f32).to_bits(), 101, "c", 0),
+    ];
+    for (obs, (q, gdp, region, note, strl)) in rows.iter().enumerate() {
+        out.extend(q.to_le_bytes());
+        out.extend(gdp.to_le_bytes());
+        out.push(*region as u8);
+        out.extend(padded(note, 8));
+        // (v, o): 2 bytes of variable, 6 bytes of observation
+        let key = if *strl == 1 { 5u64 | ((obs as u64 + 1) << 16) } else { 0 };
+        out.extend(key.to_le_bytes());
+    }
+    out.extend_from_slice(b"</data>");
+
+    tag(&mut out, &mut map, 10, "<strls>");
+    out.extend_from_slice(b"GSO");
+    out.extend(5u32.to_le_bytes());
+    out.extend(1u64.to_le_bytes());
+    out.push(130);
+    out.extend(12u32.to_le_bytes());
+    out.extend_from_slice(b"long memo \xc3\xa9\0");
+    out.extend_from_slice(b"</strls>");
+
+    tag(&mut out, &mut map, 11, "<value_labels>");
+    let table = label_table(&[(1, "North"), (2, "South")], false);
+    out.extend_from_slice(b"<lbl>");
+    out.extend((table.len() as u32).to_le_bytes());
+    out.extend(padded("regionlbl", 129));
+    out.extend([0u8; 3]);
+    out.extend(table);
+    out.extend_from_slice(b"</lbl></value_labels>");
+    tag(&mut out, &mut map, 12, "</stata_dta>");
+    map[13] = out.len() as u64;
+
+    for (i, offset) in map.iter().enumerate() {
+        out[map_at + i * 8..map_at + i * 8 + 8].copy_from_slice(&offset.to_le_bytes());
+    }
+    out
+}
+
+/// A big-endian format 114 file: `%ty` year and a double with a label.
+fn dta114() -> Vec<u8> {
+    let mut out = vec![114, 1, 1, 0];
+    out.extend(2u16.to_be_bytes());
+    out.extend(3u32.to_be_bytes());
+    out.extend(padded("Prices", 81));
+    out.extend(padded(" 1 Jan 2024 12:00", 18));
+    out.extend([252u8, 255]);
+    out.extend(padded("year", 33));
+    out.extend(padded("cpi", 33));
+    out.extend(vec![0u8; 2 * 3]);
+    out.extend(padded("%ty", 49));
+    out.extend(padded("%10.0g", 49));
+    out.extend(padded("", 33));
+    out.extend(padded("pricelbl", 33));
+    out.extend(padded("", 81));
+    out.extend(padded("Consumer prices", 81));
+    out.extend([0u8; 5]);
+    for (year, cpi) in [(1990i16, 100.0f64), (1991, 8.988e307 * 2.0), (32_741, 102.5)] {
+        out.extend(year.to_be_bytes());
+        out.extend(cpi.to_be_bytes());
+    }
+    let table = label_table(&[(100, "Base year")], true);
+    out.extend((table.len() as u32).to_be_bytes());
+    out.extend(padded("pricelbl", 33));
+    out.extend([0u8; 3]);
+    out.extend(table);
+    out
+}
+
+#[test]
+fn test_dta_118() {
+    let (table, meta) = parse_dta(&dta118()).unwrap();
+
+    assert_eq!(table.columns, vec!["qdate", "gdp", "region", "note", "memo"]);
+    assert_eq!(table.rows[0], vec!["2000Q1", "1.5", "1", "a", "long memo é"]);
+    assert_eq!(table.rows[1], vec!["2000Q2", "", "2", "bé", ""]);
+    assert_eq!(table.rows[2], vec!["2000Q3", "-0.25", "", "c", ""]);
+
+    assert_eq!(meta[0].format.as_deref(), Some("%tq"));
+    assert_eq!(meta[1].label.as_deref(), Some("Real GDP growth"));
+    assert_eq!(meta[2].value_labels, labels(&[("1", "North"), ("2", "South")]));
+    assert!(meta[3].value_labels.is_empty());
+
+    let index = detect_time_index(&table).unwrap();
+    assert_eq!((index.column.as_str(), index.frequency), ("qdate", Frequency::Quarterly));
+}
+
+#[test]
+fn test_dta_114_big_endian() {
+    let (table, meta) = parse_dta(&dta114()).unwrap();
+
+    assert_eq!(table.columns, vec!["year", "cpi"]);
+    assert_eq!(table.rows, vec![vec!["1990", "100"], vec!["1991", ""], vec!["", "102.5"]]);
+    assert_eq!(meta[1].label.as_deref(), Some("Consumer prices"));
+    assert_eq!(meta[1].value_labels, labels(&[("100", "Base year")]));
+}
+
+#[test]
+fn test_dta_errors() {
+    let mut unsupported = dta114();
+    unsupported[0] = 113;
+    match parse_dta(&unsupported) {
+        Err(FriedmanError::InvalidParams(msg)) => assert!(msg.contains("versions 114-119"), "{msg}"),
+        other => panic!("expected InvalidParams, got {other:?}"),
+    }
+    let truncated = &dta118()[..400];
+    match parse_dta(truncated) {
+        Err(FriedmanError::InvalidParams(msg)) => assert!(msg.starts_with("Invalid Stata file"), "{msg}"),
+        other => panic!("expected InvalidParams, got {other:?}"),
+    }
+}
+
+// ===========================================================================
+// SPSS
+// ===========================================================================
+
+fn spss_seconds(year: i64, month: i64, day: i64) -> f64 {
+    ((days_from_civil(year, month, day) - days_from_civil(1582, 10, 14)) * 86_400) as f64
+}
+
+fn variable(out: &mut Vec<u8>, width: i32, name: &str, label: Option<&str>, missing: &[f64], format: u32) {
+    for v in [2, width, i32::from(label.is_some()), missing.len() as i32] {
+        out.extend(v.to_le_bytes());
+    }
+    out.extend(format.to_le_bytes());
+    out.extend(format.to_le_bytes());
+    out.extend(format!("{name:<8}").as_bytes());
+    if let Some(label) = label {
+        out.extend((label.len() as u32).to_le_bytes());
+        out.extend(label.as_bytes());
+        out.extend(vec![0u8; (4 - label.len() % 4) % 4]);
+    }
+    missing.iter().for_each(|m| out.extend(m.to_le_bytes()));
+}
+
+/// A unit of case data, before compression.
+enum Unit {
+    Number(f64),
+    Text(&'static [u8; 8]),
+    Sysmis,
+}
+
+/// A `.sav` file with a `QYR` date, a labelled and user-missing numeric
+/// with a long name, a value-labelled code and a two-slot string.
+fn sav(compressed: bool) -> Vec<u8> {
+    let mut out = b"$FL2".to_vec();
+    out.extend(format!("{:<60}", "@(#) SPSS DATA FILE test").as_bytes());
+    for v in [2i32, 5, i32::from(compressed), 0, 3] {
+        out.extend(v.to_le_bytes());
+    }
+    out.extend(100f64.to_le_bytes());
+    out.extend(format!("{:<9}{:<8}{:<64}", "01 Jan 24", "12:00:00", "Macro").as_bytes());
+    out.extend([0u8; 3]);
+
+    variable(&mut out, 0, "DATE", None, &[], 29 << 16 | 6 << 8);
+    variable(&mut out, 0, "GDPGRW", Some("GDP growth"), &[-99.0], 5 << 16 | 8 << 8 | 2);
+    variable(&mut out, 0, "REGION", None, &[], 5 << 16 | 1 << 8);
+    variable(&mut out, 10, "NAME", None, &[], 1 << 16 | 10 << 8);
+    variable(&mut out, -1, "", None, &[], 0);
+
+    out.extend(3i32.to_le_bytes());
+    out.extend(2u32.to_le_bytes());
+    for (value, label) in [(1.0f64, "North"), (2.0, "South")] {
+        out.extend(value.to_le_bytes());
+        out.push(label.len() as u8);
+        out.extend(label.as_bytes());
+        out.extend(vec![b' '; (8 - (label.len() + 1) % 8) % 8]);
+    }
+    out.extend(4i32.to_le_bytes());
+    out.extend(1u32.to_le_bytes());
+    out.extend(3u32.to_le_bytes());
+
+    for (subtype, body) in [(13, &b"GDPGRW=gdp_growth"[..]), (20, &b"UTF-8"[..])] {
+        out.extend([7i32, subtype, 1, body.len() as i32].iter().flat_map(|v| v.to_le_bytes()));
+        out.extend(body);
+    }
+    out.extend(999i32.to_le_bytes());
+    out.extend(0i32.to_le_bytes());
+
+    let cases = [
+        [Unit::Number(spss_seconds(2000, 1, 1)), Unit::Number(1.5), Unit::Number(1.0), Unit::Text(b"alpha   "), Unit::Text(b"        ")],
+        [Unit::Number(spss_seconds(2000, 4, 1)), Unit::Number(-99.0), Unit::Number(2.0), Unit::Text(b"beta gam"), Unit::Text(b"ma      ")],
+        [Unit::Number(spss_seconds(2000, 7, 1)), Unit::Sysmis, Unit::Number(1.0), Unit::Text(b"\xc3\xa9t\xc3\xa9   "), Unit::Text(b"        ")],
+    ];
+    let units = cases.iter().flatten();
+    if !compressed {
+        for unit in units {
+            match unit {
+                Unit::Number(v) => out.extend(v.to_le_bytes()),
+                Unit::Text(t) => out.extend(*t),
+                Unit::Sysmis => out.extend((-f64::MAX).to_le_bytes()),
+            }
+        }
+        return out;
+    }
+
+    let mut commands: Vec<(u8, Option<[u8; 8]>)> = units
+        .map(|unit| match unit {
+            Unit::Number(v) if v.fract() == 0.0 && (-99.0..=151.0).contains(v) => ((v + 100.0) as u8, None),
+            Unit::Number(v) => (253, Some(v.to_le_bytes())),
+            Unit::Text(b"        ") => (254, None),
+            Unit::Text(t) => (253, Some(**t)),
+            Unit::Sysmis => (255, None),
+        })
+        .collect();
+    commands.push((252, None));
+    for block in commands.chunks(8) {
+        let mut codes = [0u8; 8];
+        block.iter().enumerate().for_each(|(i, (c, _))| codes[i] = *c);
+        out.extend(codes);
+        block.iter().filter_map(|(_, raw)| *raw).for_each(|raw| out.extend(raw));
+    }
+    out
+}
+
+#[test]
+fn test_sav_compressed() {
+    let (table, meta) = parse_sav(&sav(true)).unwrap();
+
+    assert_eq!(table.columns, vec!["DATE", "gdp_growth", "REGION", "NAME"]);
+    assert_eq!(table.rows[0], vec!["2000Q1", "1.5", "1", "alpha"]);
+    assert_eq!(table.rows[1], vec!["2000Q2", "", "2", "beta gamma"]);
+    assert_eq!(table.rows[2], vec!["2000Q3", "", "1", "été"]);
+
+    assert_eq!(meta[0].format.as_deref(), Some("QYR6"));
+    assert_eq!(meta[1].format.as_deref(), Some("F8.2"));
+    assert_eq!(meta[1].label.as_deref(), Some("GDP growth"));
+    assert_eq!(meta[2].value_labels, labels(&[("1", "North"), ("2", "South")]));
+    assert_eq!(meta[3].format.as_deref(), Some("A10"));
+    assert_eq!(detect_time_index(&table).unwrap().frequency, Frequency::Quarterly);
+}
+
+#[test]
+fn test_sav_uncompressed_matches_compressed() {
+    assert_eq!(parse_sav(&sav(false)).unwrap(), parse_sav(&sav(true)).unwrap());
+
+    match parse_sav(b"$FL3") {
+        Err(FriedmanError::InvalidParams(msg)) => assert!(msg.contains("not supported"), "{msg}"),
+        other => panic!("expected InvalidParams, got {other:?}"),
+    }
+}
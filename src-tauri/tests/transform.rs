@@ -0,0 +1,138 @@
+//! Tests for derived-series transforms.
+//!
+//!   cargo test -p friedman-app --test transform
+
+use app_lib::dataset::delimited::parse_csv;
+use app_lib::dataset::period::Frequency;
+use app_lib::dataset::transform::{apply_transforms, derived_meta, Transform, TransformSpec};
+use app_lib::dataset::{ColumnMeta, Table};
+use app_lib::error::FriedmanError;
+
+fn table() -> Table {
+    parse_csv(b"date,gdp,rate\n2000Q1,100,1\n2000Q2,110,NA\n2000Q3,121,3\n2000Q4,0,4\n2001Q1,133.1,5\n")
+        .unwrap()
+        .0
+}
+
+fn spec(column: &str, transform: Transform) -> TransformSpec {
+    TransformSpec { column: column.into(), transform, name: None }
+}
+
+/// The new column of `table` after one transform, as numbers.
+fn derived(column: &str, transform: Transform, frequency: Option<Frequency>) -> Vec<Option<f64>> {
+    let out = apply_transforms(&table(), &[spec(column, transform)], frequency).unwrap();
+    out.rows.iter().map(|row| row.last().unwrap().parse().ok()).collect()
+}
+
+fn assert_close(actual: &[Option<f64>], expected: &[Option<f64>]) {
+    assert_eq!(actual.len(), expected.len(), "{actual:?}");
+    for (a, e) in actual.iter().zip(expected) {
+        match (a, e) {
+            (Some(a), Some(e)) => assert!((a - e).abs() < 1e-9, "{actual:?} != {expected:?}"),
+            _ => assert_eq!(a, e, "{actual:?} != {expected:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_levels_and_differences() {
+    let ln = f64::ln;
+    assert_close(
+        &derived("gdp", Transform::Log, None),
+        &[Some(ln(100.0)), Some(ln(110.0)), Some(ln(121.0)), None, Some(ln(133.1))],
+    );
+    assert_close(
+        &derived("gdp", Transform::Diff { periods: 1 }, None),
+        &[None, Some(10.0), Some(11.0), Some(-121.0), Some(133.1)],
+    );
+    assert_close(
+        &derived("gdp", Transform::LogDiff { periods: 2 }, None),
+        &[None, None, Some(ln(1.21)), None, Some(ln(1.1))],
+    );
+    assert_close(
+        &derived("rate", Transform::Diff { periods: 1 }, None),
+        &[None, None, None, Some(1.0), Some(1.0)],
+    );
+}
+
+#[test]
+fn test_annualized_lags_and_windows() {
+    assert_close(
+        &derived("gdp", Transform::Annualized, Some(Frequency::Quarterly)),
+        &[None, Some(46.41), Some(46.41), Some(-100.0), None],
+    );
+    assert_close(
+        &derived("rate", Transform::Lag { periods: 1 }, None),
+        &[None, Some(1.0), None, Some(3.0), Some(4.0)],
+    );
+    assert_close(
+        &derived("rate", Transform::Lead { periods: 2 }, None),
+        &[Some(3.0), Some(4.0), Some(5.0), None, None],
+    );
+    assert_close(
+        &derived("rate", Transform::MovingAverage { window: 2, centered: false }, None),
+        &[None, None, None, Some(3.5), Some(4.5)],
+    );
+    assert_close(
+        &derived("rate", Transform::MovingAverage { window: 3, centered: true }, None),
+        &[None, None, None, Some(4.0), None],
+    );
+    let z = derived("rate", Transform::Standardize, None);
+    let sd = (35.0f64 / 12.0).sqrt();
+    assert_close(&z, &[Some(-2.25 / sd), None, Some(-0.25 / sd), Some(0.75 / sd), Some(1.75 / sd)]);
+}
+
+#[test]
+fn test_chained_specs_and_names() {
+    let specs: Vec<TransformSpec> = serde_json::from_str(
+        r#"[
+            {"column": "gdp", "kind": "log"},
+            {"column": "log_gdp", "kind": "diff", "name": "growth"},
+            {"column": "rate", "kind": "moving_average", "window": 4},
+            {"column": "rate", "kind": "lag", "periods": 2}
+        ]"#,
+    )
+    .unwrap();
+    let out = apply_transforms(&table(), &specs, None).unwrap();
+
+    assert_eq!(out.columns, vec!["date", "gdp", "rate", "log_gdp", "growth", "ma4_rate", "rate_lag2"]);
+    assert_eq!(out.rows[0][..3], table().rows[0][..]);
+    assert_eq!(out.rows[1][4].parse::<f64>().unwrap(), 110f64.ln() - 100f64.ln());
+    assert_eq!(spec("gdp", Transform::LogDiff { periods: 4 }).output_name(), "dlog4_gdp");
+}
+
+#[test]
+fn test_derived_meta_follows_columns() {
+    let labelled = |name: &str, label: &str| ColumnMeta {
+        name: name.into(),
+        label: Some(label.into()),
+        ..ColumnMeta::default()
+    };
+    let parent = vec![labelled("date", "Quarter"), labelled("gdp", "Real GDP"), labelled("rate", "Policy rate")];
+    let out = apply_transforms(&table(), &[spec("gdp", Transform::Log)], None).unwrap();
+
+    let meta = derived_meta(&parent, &out.columns);
+    assert_eq!(meta.iter().map(|m| m.name.as_str()).collect::<Vec<_>>(), out.columns);
+    assert_eq!(meta[1].label.as_deref(), Some("Real GDP"));
+    assert_eq!(meta[3], ColumnMeta { name: "log_gdp".into(), ..ColumnMeta::default() });
+    assert!(derived_meta(&[], &out.columns).is_empty());
+
+    // A lead longer than the data is all missing rather than overflowing.
+    assert_close(&derived("rate", Transform::Lead { periods: usize::MAX }, None), &[None; 5]);
+}
+
+#[test]
+fn test_transform_errors() {
+    let message = |specs: &[TransformSpec], frequency| match apply_transforms(&table(), specs, frequency) {
+        Err(FriedmanError::InvalidParams(msg)) => msg,
+        other => panic!("expected InvalidParams, got {other:?}"),
+    };
+
+    assert_eq!(message(&[spec("cpi", Transform::Log)], None), "Unknown column `cpi`");
+    assert_eq!(message(&[spec("date", Transform::Log)], None), "Column `date` is not numeric: found `2000Q1`");
+    assert!(message(&[spec("gdp", Transform::Annualized)], None).contains("need a time index"));
+    assert_eq!(message(&[spec("gdp", Transform::Diff { periods: 0 })], None), "`periods` must be at least 1");
+    let twice = [spec("gdp", Transform::Log), spec("gdp", Transform::Log)];
+    assert_eq!(message(&twice, None), "Column `log_gdp` already exists");
+    assert_eq!(message(&[], None), "No transforms given");
+}
@@ -1,6 +1,14 @@
 import { invoke } from "@tauri-apps/api/core";
 import type {
+  ColumnarFormat,
+  CommandResult,
   DatasetInfo,
+  PreviewPage,
+  PreviewQuery,
+  SheetInfo,
+  TransformSpec,
+  ResultPage,
+  SpilledArray,
   VarEstimateParams,
   VarLagSelectParams,
   VarStabilityParams,
@@ -43,168 +51,227 @@ export async function loadCsv(path: string): Promise<DatasetInfo> {
   return invoke<DatasetInfo>("load_csv", { path });
 }
 
-export async function loadExcel(path: string, sheet: string): Promise<DatasetInfo> {
-  return invoke<DatasetInfo>("load_excel", { path, sheet });
+export async function listSheets(path: string): Promise<SheetInfo[]> {
+  return invoke<SheetInfo[]>("list_sheets", { path });
 }
 
+export async function loadExcel(
+  path: string,
+  sheet?: string,
+  range?: string,
+  headerRow?: number,
+): Promise<DatasetInfo> {
+  return invoke<DatasetInfo>("load_xlsx", { path, sheet, range, headerRow });
+}
+
+export async function loadParquet(path: string): Promise<DatasetInfo> {
+  return invoke<DatasetInfo>("load_parquet", { path });
+}
+
+export async function loadArrow(path: string): Promise<DatasetInfo> {
+  return invoke<DatasetInfo>("load_arrow", { path });
+}
+
+export async function loadStata(path: string): Promise<DatasetInfo> {
+  return invoke<DatasetInfo>("load_stata", { path });
+}
+
+export async function loadSpss(path: string): Promise<DatasetInfo> {
+  return invoke<DatasetInfo>("load_spss", { path });
+}
+
+/** Export a dataset to Parquet or Arrow, by the extension of `path` unless `format` is given. */
+export async function exportDataset(
+  id: string,
+  path: string,
+  format?: ColumnarFormat,
+): Promise<void> {
+  return invoke<void>("export_dataset", { id, path, format });
+}
+
+/** Apply `transforms` in order and register the result as a derived dataset. */
+export async function transformDataset(
+  id: string,
+  transforms: TransformSpec[],
+  name?: string,
+): Promise<DatasetInfo> {
+  return invoke<DatasetInfo>("transform_dataset", { id, transforms, name });
+}
+
+/** A page of a loaded dataset, filtered, sorted and limited by `query`. */
 export async function previewData(
   datasetId: string,
-  limit: number = 100,
-): Promise<{ columns: string[]; rows: Record<string, unknown>[] }> {
-  return invoke<{ columns: string[]; rows: Record<string, unknown>[] }>(
-    "preview_data",
-    { datasetId, limit },
-  );
+  query: PreviewQuery = {},
+): Promise<PreviewPage> {
+  return invoke<PreviewPage>("preview_data", { datasetId, query });
 }
 
 // ── VAR ──────────────────────────────────────────────────────────────────────
 
-export async function varEstimate(params: VarEstimateParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("var_estimate", { params });
+export async function varEstimate(params: VarEstimateParams): Promise<CommandResult> {
+  return invoke<CommandResult>("var_estimate", { params });
 }
 
-export async function varLagSelect(params: VarLagSelectParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("var_lagselect", { params });
+export async function varLagSelect(params: VarLagSelectParams): Promise<CommandResult> {
+  return invoke<CommandResult>("var_lagselect", { params });
 }
 
-export async function varStability(params: VarStabilityParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("var_stability", { params });
+export async function varStability(params: VarStabilityParams): Promise<CommandResult> {
+  return invoke<CommandResult>("var_stability", { params });
 }
 
-export async function varIrf(params: VarIrfParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("var_irf", { params });
+export async function varIrf(params: VarIrfParams): Promise<CommandResult> {
+  return invoke<CommandResult>("var_irf", { params });
 }
 
-export async function varFevd(params: VarFevdParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("var_fevd", { params });
+export async function varFevd(params: VarFevdParams): Promise<CommandResult> {
+  return invoke<CommandResult>("var_fevd", { params });
 }
 
-export async function varHd(params: VarHdParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("var_hd", { params });
+export async function varHd(params: VarHdParams): Promise<CommandResult> {
+  return invoke<CommandResult>("var_hd", { params });
 }
 
-export async function varForecast(params: VarForecastParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("var_forecast", { params });
+export async function varForecast(params: VarForecastParams): Promise<CommandResult> {
+  return invoke<CommandResult>("var_forecast", { params });
 }
 
 // ── BVAR ─────────────────────────────────────────────────────────────────────
 
-export async function bvarEstimate(params: BvarEstimateParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("bvar_estimate", { params });
+export async function bvarEstimate(params: BvarEstimateParams): Promise<CommandResult> {
+  return invoke<CommandResult>("bvar_estimate", { params });
 }
 
-export async function bvarPosterior(params: BvarPosteriorParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("bvar_posterior", { params });
+export async function bvarPosterior(params: BvarPosteriorParams): Promise<CommandResult> {
+  return invoke<CommandResult>("bvar_posterior", { params });
 }
 
-export async function bvarIrf(params: BvarIrfParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("bvar_irf", { params });
+export async function bvarIrf(params: BvarIrfParams): Promise<CommandResult> {
+  return invoke<CommandResult>("bvar_irf", { params });
 }
 
-export async function bvarFevd(params: BvarFevdParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("bvar_fevd", { params });
+export async function bvarFevd(params: BvarFevdParams): Promise<CommandResult> {
+  return invoke<CommandResult>("bvar_fevd", { params });
 }
 
-export async function bvarHd(params: BvarHdParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("bvar_hd", { params });
+export async function bvarHd(params: BvarHdParams): Promise<CommandResult> {
+  return invoke<CommandResult>("bvar_hd", { params });
 }
 
-export async function bvarForecast(params: BvarForecastParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("bvar_forecast", { params });
+export async function bvarForecast(params: BvarForecastParams): Promise<CommandResult> {
+  return invoke<CommandResult>("bvar_forecast", { params });
 }
 
 // ── Local Projections ────────────────────────────────────────────────────────
 
-export async function lpEstimate(params: LpEstimateParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("lp_estimate", { params });
+export async function lpEstimate(params: LpEstimateParams): Promise<CommandResult> {
+  return invoke<CommandResult>("lp_estimate", { params });
 }
 
-export async function lpIrf(params: LpIrfParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("lp_irf", { params });
+export async function lpIrf(params: LpIrfParams): Promise<CommandResult> {
+  return invoke<CommandResult>("lp_irf", { params });
 }
 
-export async function lpFevd(params: LpFevdParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("lp_fevd", { params });
+export async function lpFevd(params: LpFevdParams): Promise<CommandResult> {
+  return invoke<CommandResult>("lp_fevd", { params });
 }
 
-export async function lpHd(params: LpHdParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("lp_hd", { params });
+export async function lpHd(params: LpHdParams): Promise<CommandResult> {
+  return invoke<CommandResult>("lp_hd", { params });
 }
 
-export async function lpForecast(params: LpForecastParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("lp_forecast", { params });
+export async function lpForecast(params: LpForecastParams): Promise<CommandResult> {
+  return invoke<CommandResult>("lp_forecast", { params });
 }
 
 // ── Factor Models ────────────────────────────────────────────────────────────
 
-export async function factorEstimate(params: FactorEstimateParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("factor_estimate", { params });
+export async function factorEstimate(params: FactorEstimateParams): Promise<CommandResult> {
+  return invoke<CommandResult>("factor_estimate", { params });
 }
 
-export async function factorForecast(params: FactorForecastParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("factor_forecast", { params });
+export async function factorForecast(params: FactorForecastParams): Promise<CommandResult> {
+  return invoke<CommandResult>("factor_forecast", { params });
 }
 
 // ── Non-Gaussian SVAR ───────────────────────────────────────────────────────
 
-export async function nongaussianFastica(params: NongaussianFasticaParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("nongaussian_fastica", { params });
+export async function nongaussianFastica(params: NongaussianFasticaParams): Promise<CommandResult> {
+  return invoke<CommandResult>("nongaussian_fastica", { params });
 }
 
-export async function nongaussianMl(params: NongaussianMlParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("nongaussian_ml", { params });
+export async function nongaussianMl(params: NongaussianMlParams): Promise<CommandResult> {
+  return invoke<CommandResult>("nongaussian_ml", { params });
 }
 
-export async function nongaussianHeteroskedasticity(params: NongaussianHeteroskedasticityParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("nongaussian_heteroskedasticity", { params });
+export async function nongaussianHeteroskedasticity(params: NongaussianHeteroskedasticityParams): Promise<CommandResult> {
+  return invoke<CommandResult>("nongaussian_heteroskedasticity", { params });
 }
 
-export async function nongaussianNormality(params: NongaussianNormalityParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("nongaussian_normality", { params });
+export async function nongaussianNormality(params: NongaussianNormalityParams): Promise<CommandResult> {
+  return invoke<CommandResult>("nongaussian_normality", { params });
 }
 
-export async function nongaussianIdentifiability(params: NongaussianIdentifiabilityParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("nongaussian_identifiability", { params });
+export async function nongaussianIdentifiability(params: NongaussianIdentifiabilityParams): Promise<CommandResult> {
+  return invoke<CommandResult>("nongaussian_identifiability", { params });
 }
 
 // ── Unit Root & Cointegration Tests ──────────────────────────────────────────
 
-export async function testAdf(params: TestAdfParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("test_adf", { params });
+export async function testAdf(params: TestAdfParams): Promise<CommandResult> {
+  return invoke<CommandResult>("test_adf", { params });
 }
 
-export async function testKpss(params: TestKpssParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("test_kpss", { params });
+export async function testKpss(params: TestKpssParams): Promise<CommandResult> {
+  return invoke<CommandResult>("test_kpss", { params });
 }
 
-export async function testPp(params: TestPpParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("test_pp", { params });
+export async function testPp(params: TestPpParams): Promise<CommandResult> {
+  return invoke<CommandResult>("test_pp", { params });
 }
 
-export async function testZa(params: TestZaParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("test_za", { params });
+export async function testZa(params: TestZaParams): Promise<CommandResult> {
+  return invoke<CommandResult>("test_za", { params });
 }
 
-export async function testNp(params: TestNpParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("test_np", { params });
+export async function testNp(params: TestNpParams): Promise<CommandResult> {
+  return invoke<CommandResult>("test_np", { params });
 }
 
-export async function testJohansen(params: TestJohansenParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("test_johansen", { params });
+export async function testJohansen(params: TestJohansenParams): Promise<CommandResult> {
+  return invoke<CommandResult>("test_johansen", { params });
 }
 
 // ── GMM ──────────────────────────────────────────────────────────────────────
 
-export async function gmmEstimate(params: GmmEstimateParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("gmm_estimate", { params });
+export async function gmmEstimate(params: GmmEstimateParams): Promise<CommandResult> {
+  return invoke<CommandResult>("gmm_estimate", { params });
 }
 
 // ── ARIMA ────────────────────────────────────────────────────────────────────
 
-export async function arimaEstimate(params: ArimaEstimateParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("arima_estimate", { params });
+export async function arimaEstimate(params: ArimaEstimateParams): Promise<CommandResult> {
+  return invoke<CommandResult>("arima_estimate", { params });
+}
+
+export async function arimaForecast(params: ArimaForecastParams): Promise<CommandResult> {
+  return invoke<CommandResult>("arima_forecast", { params });
+}
+
+// ── Results ──────────────────────────────────────────────────────────────────
+
+export function isSpilled(value: unknown): value is SpilledArray {
+  return typeof value === "object" && value !== null && "$spill" in value;
+}
+
+export async function readResultPage<T = unknown>(
+  handle: string,
+  offset: number,
+  limit: number,
+): Promise<ResultPage<T>> {
+  return invoke<ResultPage<T>>("read_result_page", { handle, offset, limit });
 }
 
-export async function arimaForecast(params: ArimaForecastParams): Promise<Record<string, unknown>> {
-  return invoke<Record<string, unknown>>("arima_forecast", { params });
+export async function releaseResult(handle: string): Promise<void> {
+  return invoke<void>("release_result", { handle });
 }
@@ -1,9 +1,16 @@
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 
+export type ProgressKind = "stage" | "progress" | "iteration" | "warning" | "log";
+
 export interface ProgressPayload {
   job_id: string;
+  kind: ProgressKind;
   message: string;
-  progress?: number;
+  stage?: string | null;
+  /** Fraction in [0, 1]. */
+  progress?: number | null;
+  iteration?: number | null;
+  total?: number | null;
 }
 
 export function onProgress(
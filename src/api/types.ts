@@ -1,32 +1,278 @@
 export interface DatasetInfo {
   id: string;
   name: string;
+  /** File handed to the sidecar (a normalized CSV copy if `source` is set). */
   path: string;
   columns: string[];
   row_count: number;
+  /** The file that was loaded, when `path` is a normalized copy. */
+  source?: string | null;
+  dialect?: CsvDialect | null;
+  /** Sheet, cell range and header row read from a spreadsheet. */
+  sheet?: SheetSelection | null;
+  /** Schema of a Parquet or Arrow file, including the columns left out. */
+  schema?: FieldInfo[] | null;
+  /** Variable labels, display formats and value labels from Stata or SPSS files. */
+  column_meta?: ColumnMeta[];
+  /** Inferred type and summary statistics of each column, in column order. */
+  column_profiles?: ColumnProfile[];
+  /** The date or period column, when one was detected. */
+  time_index?: TimeIndex | null;
+  /** Parent dataset and transforms of a derived dataset. */
+  lineage?: Lineage | null;
+}
+
+export interface CsvDialect {
+  delimiter: string;
+  quote: string;
+  encoding: string;
+  bom: boolean;
+  decimal: string;
+}
+
+export interface SheetInfo {
+  name: string;
+  rows: number;
+  columns: number;
+  /** Used range in A1 notation, e.g. `A1:E81`. */
+  range: string | null;
+}
+
+export interface SheetSelection {
+  sheet: string | null;
+  range: string | null;
+  /** 1-based sheet row holding the column names. */
+  header_row: number | null;
+}
+
+export type ColumnarFormat = "parquet" | "arrow";
+
+export interface FieldInfo {
+  name: string;
+  /** Arrow type, e.g. `Float64`, `Utf8` or `Date32`. */
+  data_type: string;
+  nullable: boolean;
+  /** Whether the column is part of the loaded dataset. */
+  included: boolean;
+}
+
+export interface ValueLabel {
+  value: string;
+  label: string;
+}
+
+export interface ColumnMeta {
+  name: string;
+  label: string | null;
+  /** Display format, e.g. `%tq` in Stata or `F8.2` in SPSS. */
+  format: string | null;
+  value_labels: ValueLabel[];
+}
+
+/** A column transform; rows are taken to be in time order. */
+export type Transform =
+  | { kind: "log" }
+  | { kind: "diff"; periods?: number }
+  | { kind: "log_diff"; periods?: number }
+  /** Growth on the previous period compounded to an annual rate, in percent. */
+  | { kind: "annualized" }
+  | { kind: "lag"; periods?: number }
+  | { kind: "lead"; periods?: number }
+  | { kind: "moving_average"; window: number; centered?: boolean }
+  | { kind: "standardize" };
+
+export type TransformSpec = Transform & {
+  /** Source column; may be one created by an earlier spec in the list. */
+  column: string;
+  /** Name of the new column, e.g. `dlog_gdp` by default. */
+  name?: string | null;
+};
+
+export interface Lineage {
+  parent_id: string;
+  parent_name: string;
+  transforms: TransformSpec[];
+}
+
+/** Rows and columns of a dataset to preview; see `previewData`. */
+export interface PreviewQuery {
+  /** First row, counted after filtering and sorting. */
+  offset?: number;
+  /** Rows per page (default 100, at most 5000). */
+  limit?: number;
+  /** Columns to show, in order; all of them if empty. */
+  columns?: string[];
+  sort?: { column: string; descending?: boolean } | null;
+  /**
+   * Case-insensitive substring match, or a comparison such as `> 2` or
+   * `>= 2000-01`, on one column or any shown column.
+   */
+  filter?: { column?: string | null; text: string } | null;
+}
+
+export interface PreviewPage {
+  columns: string[];
+  rows: string[][];
+  /** Row index (0-based) of each page row in the dataset. */
+  row_numbers: number[];
+  offset: number;
+  /** Rows matching the filter. */
+  total_rows: number;
+  dataset_rows: number;
+}
+
+export type ColumnKind = "numeric" | "date" | "categorical" | "empty";
+
+export interface ColumnProfile {
+  name: string;
+  kind: ColumnKind;
+  count: number;
+  missing: number;
+  min: number | null;
+  max: number | null;
+  mean: number | null;
+  std: number | null;
+  /** Row index of the first and last non-missing cell. */
+  first_valid: number | null;
+  last_valid: number | null;
+}
+
+export type Frequency =
+  | "annual"
+  | "quarterly"
+  | "monthly"
+  | "weekly"
+  | "daily"
+  | "business_daily";
+
+export interface IndexGap {
+  after: string;
+  before: string;
+  periods: number;
+}
+
+export interface TimeIndex {
+  column: string;
+  frequency: Frequency;
+  start: string;
+  end: string;
+  /** Normalized label of each row; empty where the index cell is missing. */
+  labels: string[];
+  gaps: IndexGap[];
+  duplicates: string[];
+  sorted: boolean;
+  missing: number;
+}
+
+/** Returned by every model command. */
+export interface CommandResult<T = Record<string, unknown>> {
+  /** The only result object, or an array of all of them. */
+  payload: T;
+  results: T[];
+  /** The CLI's narrative output, e.g. test conclusions. */
+  text: string[];
+  warnings: string[];
+  /** Rows the command ran on, when known. */
+  sample?: EffectiveSample | null;
+  /** What was done about missing values. */
+  missing?: MissingReport | null;
+}
+
+export interface EffectiveSample {
+  /** Observation numbers from 1. */
+  first: number;
+  last: number;
+  observations: number;
+  start: string | null;
+  end: string | null;
+}
+
+/**
+ * Stands in for an array too large to send at once; page through it with
+ * `readResultPage` and free it with `releaseResult`.
+ */
+export interface SpilledArray {
+  $spill: { handle: string; length: number; bytes: number };
+}
+
+export interface ResultPage<T = unknown> {
+  handle: string;
+  offset: number;
+  /** Total number of elements. */
+  length: number;
+  items: T[];
+}
+
+/** Rejection value of every command (Rust `ErrorReport`). */
+export interface FriedmanError {
+  /** Error variant, e.g. `sidecar_exit`. */
+  kind: string;
+  /** Stable classification, e.g. `julia_not_found`, `singular_matrix`. */
+  code: string;
+  message: string;
+  exit_code: number | null;
+  stderr: string | null;
+  /** friedman-cli args of the failing invocation. */
+  args: string[];
+  hint: string | null;
+  exception: JuliaException | null;
+}
+
+export interface JuliaException {
+  type: string;
+  message: string;
+  frame: { function: string; location: string | null } | null;
+}
+
+/**
+ * The data an analysis runs on: a file path, or a loaded dataset. Either can
+ * be narrowed to an ordered list of columns, which sets the variable order
+ * of Cholesky identification.
+ */
+export interface DataInput {
+  data?: string;
+  dataset_id?: string | null;
+  columns?: string[];
+  /**
+   * Period label such as "2007Q4", or an observation number from 1. Only a
+   * number is an observation number; "5" is read as a label.
+   */
+  sample_start?: string | number | null;
+  sample_end?: string | number | null;
+  missing?: MissingPolicy;
+}
+
+export type MissingTreatment = "none" | "trim" | "listwise" | "linear" | "spline" | "locf";
+
+export interface MissingPolicy {
+  treatment?: MissingTreatment;
+  /** Extra cells to read as missing, e.g. "-999". */
+  tokens?: string[];
+}
+
+export interface MissingReport {
+  treatment: MissingTreatment;
+  columns: { column: string; missing: number; filled: number; remaining: number }[];
+  rows_dropped: number;
 }
 
 // ── VAR ──────────────────────────────────────────────────────────────────────
 
-export interface VarEstimateParams {
-  data: string;
+export interface VarEstimateParams extends DataInput {
   lags?: number;
   trend?: string; // none|constant|trend|both
 }
 
-export interface VarLagSelectParams {
-  data: string;
+export interface VarLagSelectParams extends DataInput {
   max_lags?: number;
   criterion?: string; // aic|bic|hqc
 }
 
-export interface VarStabilityParams {
-  data: string;
+export interface VarStabilityParams extends DataInput {
   lags?: number;
 }
 
-export interface VarIrfParams {
-  data: string;
+export interface VarIrfParams extends DataInput {
   lags?: number;
   shock?: number;
   horizons?: number;
@@ -36,23 +282,20 @@ export interface VarIrfParams {
   config?: string;
 }
 
-export interface VarFevdParams {
-  data: string;
+export interface VarFevdParams extends DataInput {
   lags?: number;
   horizons?: number;
   id?: string;
   config?: string;
 }
 
-export interface VarHdParams {
-  data: string;
+export interface VarHdParams extends DataInput {
   lags?: number;
   id?: string;
   config?: string;
 }
 
-export interface VarForecastParams {
-  data: string;
+export interface VarForecastParams extends DataInput {
   lags?: number;
   horizons?: number;
   confidence?: number;
@@ -60,8 +303,7 @@ export interface VarForecastParams {
 
 // ── BVAR ─────────────────────────────────────────────────────────────────────
 
-export interface BvarEstimateParams {
-  data: string;
+export interface BvarEstimateParams extends DataInput {
   lags?: number;
   prior?: string;
   draws?: number;
@@ -69,8 +311,7 @@ export interface BvarEstimateParams {
   config?: string;
 }
 
-export interface BvarPosteriorParams {
-  data: string;
+export interface BvarPosteriorParams extends DataInput {
   lags?: number;
   draws?: number;
   sampler?: string;
@@ -78,8 +319,7 @@ export interface BvarPosteriorParams {
   config?: string;
 }
 
-export interface BvarIrfParams {
-  data: string;
+export interface BvarIrfParams extends DataInput {
   lags?: number;
   shock?: number;
   horizons?: number;
@@ -89,8 +329,7 @@ export interface BvarIrfParams {
   config?: string;
 }
 
-export interface BvarFevdParams {
-  data: string;
+export interface BvarFevdParams extends DataInput {
   lags?: number;
   horizons?: number;
   id?: string;
@@ -99,8 +338,7 @@ export interface BvarFevdParams {
   config?: string;
 }
 
-export interface BvarHdParams {
-  data: string;
+export interface BvarHdParams extends DataInput {
   lags?: number;
   id?: string;
   draws?: number;
@@ -108,8 +346,7 @@ export interface BvarHdParams {
   config?: string;
 }
 
-export interface BvarForecastParams {
-  data: string;
+export interface BvarForecastParams extends DataInput {
   lags?: number;
   horizons?: number;
   draws?: number;
@@ -119,8 +356,7 @@ export interface BvarForecastParams {
 
 // ── LP ───────────────────────────────────────────────────────────────────────
 
-export interface LpEstimateParams {
-  data: string;
+export interface LpEstimateParams extends DataInput {
   method?: string; // standard|iv|smooth|state|propensity|robust
   shock?: number;
   horizons?: number;
@@ -140,8 +376,7 @@ export interface LpEstimateParams {
   score_method?: string;
 }
 
-export interface LpIrfParams {
-  data: string;
+export interface LpIrfParams extends DataInput {
   shock?: number;
   shocks?: string;
   horizons?: number;
@@ -155,8 +390,7 @@ export interface LpIrfParams {
   config?: string;
 }
 
-export interface LpFevdParams {
-  data: string;
+export interface LpFevdParams extends DataInput {
   horizons?: number;
   lags?: number;
   var_lags?: number;
@@ -165,8 +399,7 @@ export interface LpFevdParams {
   config?: string;
 }
 
-export interface LpHdParams {
-  data: string;
+export interface LpHdParams extends DataInput {
   lags?: number;
   var_lags?: number;
   id?: string;
@@ -174,8 +407,7 @@ export interface LpHdParams {
   config?: string;
 }
 
-export interface LpForecastParams {
-  data: string;
+export interface LpForecastParams extends DataInput {
   shock?: number;
   horizons?: number;
   shock_size?: number;
@@ -188,8 +420,7 @@ export interface LpForecastParams {
 
 // ── Factor ───────────────────────────────────────────────────────────────────
 
-export interface FactorEstimateParams {
-  data: string;
+export interface FactorEstimateParams extends DataInput {
   model_type: string; // static|dynamic|gdfm
   nfactors?: number;
   criterion?: string;
@@ -198,8 +429,7 @@ export interface FactorEstimateParams {
   dynamic_rank?: number;
 }
 
-export interface FactorForecastParams {
-  data: string;
+export interface FactorForecastParams extends DataInput {
   nfactors?: number;
   horizon?: number;
   ci_method?: string;
@@ -212,34 +442,29 @@ export interface FactorForecastParams {
 
 // ── Non-Gaussian SVAR ───────────────────────────────────────────────────────
 
-export interface NongaussianFasticaParams {
-  data: string;
+export interface NongaussianFasticaParams extends DataInput {
   lags?: number;
   method?: string; // fastica|infomax|jade|sobi|dcov|hsic
   contrast?: string; // logcosh|exp|kurtosis
 }
 
-export interface NongaussianMlParams {
-  data: string;
+export interface NongaussianMlParams extends DataInput {
   lags?: number;
   distribution?: string; // student_t|skew_t|ghd|mixture_normal|pml|skew_normal
 }
 
-export interface NongaussianHeteroskedasticityParams {
-  data: string;
+export interface NongaussianHeteroskedasticityParams extends DataInput {
   lags?: number;
   method?: string; // markov|garch|smooth_transition|external
   config?: string;
   regimes?: number;
 }
 
-export interface NongaussianNormalityParams {
-  data: string;
+export interface NongaussianNormalityParams extends DataInput {
   lags?: number;
 }
 
-export interface NongaussianIdentifiabilityParams {
-  data: string;
+export interface NongaussianIdentifiabilityParams extends DataInput {
   lags?: number;
   test?: string; // strength|gaussianity|independence|all|overidentification
   method?: string; // fastica|infomax|jade|sobi|dcov|hsic
@@ -248,56 +473,48 @@ export interface NongaussianIdentifiabilityParams {
 
 // ── Tests ────────────────────────────────────────────────────────────────────
 
-export interface TestAdfParams {
-  data: string;
+export interface TestAdfParams extends DataInput {
   column?: number;
   max_lags?: number;
   trend?: string;
 }
 
-export interface TestKpssParams {
-  data: string;
+export interface TestKpssParams extends DataInput {
   column?: number;
   trend?: string;
 }
 
-export interface TestPpParams {
-  data: string;
+export interface TestPpParams extends DataInput {
   column?: number;
   trend?: string;
 }
 
-export interface TestZaParams {
-  data: string;
+export interface TestZaParams extends DataInput {
   column?: number;
   trend?: string;
   trim?: number;
 }
 
-export interface TestNpParams {
-  data: string;
+export interface TestNpParams extends DataInput {
   column?: number;
   trend?: string;
 }
 
-export interface TestJohansenParams {
-  data: string;
+export interface TestJohansenParams extends DataInput {
   lags?: number;
   trend?: string;
 }
 
 // ── GMM ──────────────────────────────────────────────────────────────────────
 
-export interface GmmEstimateParams {
-  data: string;
+export interface GmmEstimateParams extends DataInput {
   config?: string;
   weighting?: string;
 }
 
 // ── ARIMA ────────────────────────────────────────────────────────────────────
 
-export interface ArimaEstimateParams {
-  data: string;
+export interface ArimaEstimateParams extends DataInput {
   column?: number;
   p?: number; // optional: omit for auto mode
   d?: number;
@@ -310,8 +527,7 @@ export interface ArimaEstimateParams {
   criterion?: string;
 }
 
-export interface ArimaForecastParams {
-  data: string;
+export interface ArimaForecastParams extends DataInput {
   column?: number;
   p?: number;
   d?: number;
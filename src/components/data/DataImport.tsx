@@ -1,11 +1,21 @@
 import { useState, useCallback } from "react";
 import { FileSelector } from "../common/FileSelector";
 import { LoadingSpinner } from "../common/LoadingSpinner";
-import { loadCsv, loadExcel } from "../../api/commands";
+import {
+  listSheets,
+  loadArrow,
+  loadCsv,
+  loadExcel,
+  loadParquet,
+  loadSpss,
+  loadStata,
+} from "../../api/commands";
+import type { DatasetInfo, SheetInfo } from "../../api/types";
+import { errorMessage } from "../../lib/errors";
 import { useProjectStore } from "../../stores/projectStore";
 import { useOutputStore } from "../../stores/outputStore";
 
-type FileFormat = "csv" | "xlsx" | "unknown";
+type FileFormat = "csv" | "xlsx" | "parquet" | "arrow" | "stata" | "spss" | "unknown";
 
 /**
  * Detect file format from file extension.
@@ -13,7 +23,11 @@ type FileFormat = "csv" | "xlsx" | "unknown";
 function detectFormat(path: string): FileFormat {
   const lower = path.toLowerCase();
   if (lower.endsWith(".csv")) return "csv";
-  if (lower.endsWith(".xlsx") || lower.endsWith(".xls")) return "xlsx";
+  if ([".xlsx", ".xls", ".xlsb", ".ods"].some((ext) => lower.endsWith(ext))) return "xlsx";
+  if (lower.endsWith(".parquet") || lower.endsWith(".pq")) return "parquet";
+  if ([".arrow", ".feather", ".ipc"].some((ext) => lower.endsWith(ext))) return "arrow";
+  if (lower.endsWith(".dta")) return "stata";
+  if (lower.endsWith(".sav")) return "spss";
   return "unknown";
 }
 
@@ -31,7 +45,10 @@ interface PreviewRow {
 
 export function DataImport() {
   const [filePath, setFilePath] = useState("");
-  const [sheetName, setSheetName] = useState("Sheet1");
+  const [sheetName, setSheetName] = useState("");
+  const [sheets, setSheets] = useState<SheetInfo[]>([]);
+  const [cellRange, setCellRange] = useState("");
+  const [headerRow, setHeaderRow] = useState("");
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [previewColumns, setPreviewColumns] = useState<string[]>([]);
@@ -54,12 +71,13 @@ export function DataImport() {
       setImported(false);
       setPreviewColumns([]);
       setPreviewRows([]);
+      setSheets([]);
 
       if (!path) return;
 
       const fmt = detectFormat(path);
       if (fmt === "unknown") {
-        setError("Unsupported file format. Please select a CSV or XLSX file.");
+        setError("Unsupported file format. Please select a CSV, spreadsheet, Parquet, Arrow, Stata or SPSS file.");
         return;
       }
 
@@ -67,11 +85,25 @@ export function DataImport() {
       try {
         // Load the full dataset to get a preview
         // The backend returns DatasetInfo; we reload to show preview rows
-        let dsInfo;
+        let dsInfo: DatasetInfo;
         if (fmt === "csv") {
           dsInfo = await loadCsv(path);
+        } else if (fmt === "parquet") {
+          dsInfo = await loadParquet(path);
+        } else if (fmt === "arrow") {
+          dsInfo = await loadArrow(path);
+        } else if (fmt === "stata") {
+          dsInfo = await loadStata(path);
+        } else if (fmt === "spss") {
+          dsInfo = await loadSpss(path);
         } else {
-          dsInfo = await loadExcel(path, sheetName);
+          const sheetList = await listSheets(path);
+          setSheets(sheetList);
+          const sheet = sheetList.some((s) => s.name === sheetName)
+            ? sheetName
+            : sheetList[0]?.name;
+          setSheetName(sheet ?? "");
+          dsInfo = await loadExcel(path, sheet);
         }
         setPreviewColumns(dsInfo.columns);
         // We don't have the raw rows from DatasetInfo, so we store the info
@@ -87,7 +119,7 @@ export function DataImport() {
         setPreviewRows([]); // Will be populated separately if backend supports it
         addLine("info", `Preview loaded: ${getFileName(path)} (${dsInfo.columns.length} columns, ${dsInfo.row_count} rows)`);
       } catch (err) {
-        const msg = err instanceof Error ? err.message : String(err);
+        const msg = errorMessage(err);
         setError(`Failed to preview file: ${msg}`);
         addLine("error", `Preview failed for ${getFileName(path)}: ${msg}`);
       } finally {
@@ -113,11 +145,24 @@ export function DataImport() {
     setError(null);
 
     try {
-      let dsInfo;
+      let dsInfo: DatasetInfo;
       if (fmt === "csv") {
         dsInfo = await loadCsv(filePath);
+      } else if (fmt === "parquet") {
+        dsInfo = await loadParquet(filePath);
+      } else if (fmt === "arrow") {
+        dsInfo = await loadArrow(filePath);
+      } else if (fmt === "stata") {
+        dsInfo = await loadStata(filePath);
+      } else if (fmt === "spss") {
+        dsInfo = await loadSpss(filePath);
       } else {
-        dsInfo = await loadExcel(filePath, sheetName);
+        dsInfo = await loadExcel(
+          filePath,
+          sheetName || undefined,
+          cellRange.trim() || undefined,
+          headerRow ? Number(headerRow) : undefined,
+        );
       }
 
       addDataset(dsInfo);
@@ -128,7 +173,7 @@ export function DataImport() {
         `Imported ${dsInfo.name}: ${dsInfo.row_count} rows, ${dsInfo.columns.length} columns`,
       );
     } catch (err) {
-      const msg = err instanceof Error ? err.message : String(err);
+      const msg = errorMessage(err);
       setError(`Import failed: ${msg}`);
       addLine("error", `Import failed for ${getFileName(filePath)}: ${msg}`);
     } finally {
@@ -148,7 +193,7 @@ export function DataImport() {
           value={filePath}
           onChange={handleFileChange}
           label="Data File"
-          extensions={["csv", "xlsx", "xls"]}
+          extensions={["csv", "xlsx", "xls", "xlsb", "ods", "parquet", "arrow", "feather", "dta", "sav"]}
         />
 
         {/* Detected format */}
@@ -167,19 +212,60 @@ export function DataImport() {
           </div>
         )}
 
-        {/* Sheet name for Excel files */}
+        {/* Sheet, range and header row for spreadsheets */}
         {format === "xlsx" && (
-          <div>
-            <label className="block text-xs text-[var(--text-secondary)] mb-1 font-medium">
-              Sheet Name
-            </label>
-            <input
-              type="text"
-              value={sheetName}
-              onChange={(e) => setSheetName(e.target.value)}
-              placeholder="Sheet1"
-              className="w-full sm:w-48 px-2 py-1.5 text-xs bg-[var(--bg-surface)] border border-[var(--border-color)] rounded text-[var(--text-primary)] placeholder:text-[var(--text-muted)] outline-none focus:border-[var(--accent)] transition-colors"
-            />
+          <div className="flex flex-wrap gap-3">
+            <div>
+              <label className="block text-xs text-[var(--text-secondary)] mb-1 font-medium">
+                Sheet
+              </label>
+              {sheets.length > 0 ? (
+                <select
+                  value={sheetName}
+                  onChange={(e) => setSheetName(e.target.value)}
+                  className="w-full sm:w-48 px-2 py-1.5 text-xs bg-[var(--bg-surface)] border border-[var(--border-color)] rounded text-[var(--text-primary)] outline-none focus:border-[var(--accent)] transition-colors"
+                >
+                  {sheets.map((s) => (
+                    <option key={s.name} value={s.name}>
+                      {s.range ? `${s.name} (${s.range})` : `${s.name} (empty)`}
+                    </option>
+                  ))}
+                </select>
+              ) : (
+                <input
+                  type="text"
+                  value={sheetName}
+                  onChange={(e) => setSheetName(e.target.value)}
+                  placeholder="First sheet"
+                  className="w-full sm:w-48 px-2 py-1.5 text-xs bg-[var(--bg-surface)] border border-[var(--border-color)] rounded text-[var(--text-primary)] placeholder:text-[var(--text-muted)] outline-none focus:border-[var(--accent)] transition-colors"
+                />
+              )}
+            </div>
+            <div>
+              <label className="block text-xs text-[var(--text-secondary)] mb-1 font-medium">
+                Cell Range
+              </label>
+              <input
+                type="text"
+                value={cellRange}
+                onChange={(e) => setCellRange(e.target.value)}
+                placeholder="Used range, e.g. B3:F40"
+                className="w-full sm:w-40 px-2 py-1.5 text-xs bg-[var(--bg-surface)] border border-[var(--border-color)] rounded text-[var(--text-primary)] placeholder:text-[var(--text-muted)] outline-none focus:border-[var(--accent)] transition-colors"
+              />
+            </div>
+            <div>
+              <label className="block text-xs text-[var(--text-secondary)] mb-1 font-medium">
+                Header Row
+              </label>
+              <input
+                type="number"
+                min={1}
+                value={headerRow}
+                onChange={(e) => setHeaderRow(e.target.value)}
+                placeholder="Auto"
+                className="w-full sm:w-24 px-2 py-1.5 text-xs bg-[var(--bg-surface)] border border-[var(--border-color)] rounded text-[var(--text-primary)] placeholder:text-[var(--text-muted)] outline-none focus:border-[var(--accent)] transition-colors"
+              />
+            </div>
           </div>
         )}
 
@@ -1,61 +1,65 @@
-import { useEffect, useState } from "react";
+import { useEffect, useMemo, useState } from "react";
 import { DataGrid } from "./DataGrid";
 import { LoadingSpinner } from "../common/LoadingSpinner";
 import { ErrorBanner } from "../common/ErrorBanner";
 import { previewData } from "../../api/commands";
+import type { PreviewPage } from "../../api/types";
+import { errorMessage } from "../../lib/errors";
+
+const PAGE_SIZE = 100;
 
 interface DataPreviewProps {
   datasetId: string;
+  maxHeight?: string;
 }
 
-export function DataPreview({ datasetId }: DataPreviewProps) {
-  const [columns, setColumns] = useState<string[]>([]);
-  const [rows, setRows] = useState<Record<string, unknown>[]>([]);
+export function DataPreview({ datasetId, maxHeight = "calc(100vh - 260px)" }: DataPreviewProps) {
+  const [page, setPage] = useState<PreviewPage | null>(null);
+  const [offset, setOffset] = useState(0);
+  const [filterText, setFilterText] = useState("");
+  const [filterColumn, setFilterColumn] = useState("");
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
 
   useEffect(() => {
-    let cancelled = false;
+    setOffset(0);
+    setFilterText("");
+    setFilterColumn("");
+  }, [datasetId]);
 
-    async function fetchPreview() {
+  useEffect(() => {
+    let cancelled = false;
+    // Debounce typing in the filter box.
+    const timer = setTimeout(async () => {
       setLoading(true);
       setError(null);
-      setColumns([]);
-      setRows([]);
-
       try {
-        const result = await previewData(datasetId, 100);
-        if (!cancelled) {
-          setColumns(result.columns);
-          setRows(result.rows);
-        }
+        const result = await previewData(datasetId, {
+          offset,
+          limit: PAGE_SIZE,
+          filter: filterText.trim() ? { column: filterColumn || null, text: filterText } : null,
+        });
+        if (!cancelled) setPage(result);
       } catch (err) {
-        if (!cancelled) {
-          const msg = err instanceof Error ? err.message : String(err);
-          setError(msg);
-        }
+        if (!cancelled) setError(errorMessage(err));
       } finally {
-        if (!cancelled) {
-          setLoading(false);
-        }
+        if (!cancelled) setLoading(false);
       }
-    }
-
-    fetchPreview();
+    }, 200);
 
     return () => {
       cancelled = true;
+      clearTimeout(timer);
     };
-  }, [datasetId]);
+  }, [datasetId, offset, filterText, filterColumn]);
 
-  if (loading) {
-    return (
-      <div className="flex items-center justify-center gap-3 py-12 text-[var(--text-muted)] text-sm">
-        <LoadingSpinner size={20} />
-        <span>Loading data preview...</span>
-      </div>
-    );
-  }
+  const rows = useMemo(
+    () =>
+      page
+        ? page.rows.map((row) => Object.fromEntries(page.columns.map((col, i) => [col, row[i]])))
+        : [],
+    [page],
+  );
 
   if (error) {
     return (
@@ -65,29 +69,68 @@ export function DataPreview({ datasetId }: DataPreviewProps) {
     );
   }
 
-  if (columns.length === 0) {
+  if (!page) {
     return (
-      <div className="flex items-center justify-center py-12 text-[var(--text-muted)] text-sm">
-        No data available
+      <div className="flex items-center justify-center gap-3 py-12 text-[var(--text-muted)] text-sm">
+        <LoadingSpinner size={20} />
+        <span>Loading data preview...</span>
       </div>
     );
   }
 
+  const last = Math.min(offset + PAGE_SIZE, page.total_rows);
+
   return (
-    <div className="p-4">
-      <div className="flex items-center justify-between mb-3">
-        <h3 className="text-sm font-semibold text-[var(--text-primary)]">
-          Data Preview
-        </h3>
+    <div>
+      <div className="flex flex-wrap items-center gap-2 mb-2">
+        <select
+          value={filterColumn}
+          onChange={(e) => {
+            setFilterColumn(e.target.value);
+            setOffset(0);
+          }}
+          className="px-2 py-1 text-xs rounded border border-[var(--border-color)] bg-[var(--bg-secondary)] text-[var(--text-primary)]"
+        >
+          <option value="">All columns</option>
+          {page.columns.map((col) => (
+            <option key={col} value={col}>
+              {col}
+            </option>
+          ))}
+        </select>
+        <input
+          type="text"
+          value={filterText}
+          onChange={(e) => {
+            setFilterText(e.target.value);
+            setOffset(0);
+          }}
+          placeholder="Filter, e.g. north or > 2"
+          className="flex-1 min-w-[160px] px-2 py-1 text-xs rounded border border-[var(--border-color)] bg-[var(--bg-secondary)] text-[var(--text-primary)]"
+        />
+        {loading && <LoadingSpinner size={14} />}
         <span className="text-[10px] text-[var(--text-muted)]">
-          Showing up to 100 rows
+          {page.total_rows === 0
+            ? "No matching rows"
+            : `Rows ${(offset + 1).toLocaleString()}–${last.toLocaleString()} of ${page.total_rows.toLocaleString()}`}
+          {page.total_rows !== page.dataset_rows && ` (filtered from ${page.dataset_rows.toLocaleString()})`}
         </span>
+        <button
+          onClick={() => setOffset(Math.max(0, offset - PAGE_SIZE))}
+          disabled={offset === 0}
+          className="px-2 py-1 text-xs rounded border border-[var(--border-color)] text-[var(--text-secondary)] disabled:opacity-40"
+        >
+          Previous
+        </button>
+        <button
+          onClick={() => setOffset(offset + PAGE_SIZE)}
+          disabled={last >= page.total_rows}
+          className="px-2 py-1 text-xs rounded border border-[var(--border-color)] text-[var(--text-secondary)] disabled:opacity-40"
+        >
+          Next
+        </button>
       </div>
-      <DataGrid
-        columns={columns}
-        data={rows}
-        maxHeight="calc(100vh - 260px)"
-      />
+      <DataGrid columns={page.columns} data={rows} maxHeight={maxHeight} />
     </div>
   );
 }
@@ -0,0 +1,152 @@
+import { useState } from "react";
+import { Plus, X } from "lucide-react";
+import { transformDataset } from "../../api/commands";
+import type { DatasetInfo, Transform, TransformSpec } from "../../api/types";
+import { errorMessage } from "../../lib/errors";
+import { useProjectStore } from "../../stores/projectStore";
+import { useOutputStore } from "../../stores/outputStore";
+
+type TransformKind = Transform["kind"];
+
+const KINDS: { kind: TransformKind; label: string }[] = [
+  { kind: "log", label: "Log" },
+  { kind: "diff", label: "Difference" },
+  { kind: "log_diff", label: "Log-difference" },
+  { kind: "annualized", label: "Annualized rate" },
+  { kind: "lag", label: "Lag" },
+  { kind: "lead", label: "Lead" },
+  { kind: "moving_average", label: "Moving average" },
+  { kind: "standardize", label: "Standardize" },
+];
+
+const inputClass =
+  "px-2 py-1.5 text-xs bg-[var(--bg-surface)] border border-[var(--border-color)] rounded text-[var(--text-primary)] outline-none focus:border-[var(--accent)] transition-colors";
+
+function makeTransform(kind: TransformKind, n: number): Transform {
+  switch (kind) {
+    case "diff":
+    case "log_diff":
+    case "lag":
+    case "lead":
+      return { kind, periods: n };
+    case "moving_average":
+      return { kind, window: n };
+    default:
+      return { kind };
+  }
+}
+
+function describe(spec: TransformSpec): string {
+  const label = KINDS.find((k) => k.kind === spec.kind)?.label ?? spec.kind;
+  const n = "periods" in spec ? spec.periods : "window" in spec ? spec.window : undefined;
+  return `${label}${n && n !== 1 ? ` (${n})` : ""} of ${spec.column}`;
+}
+
+/** Build a list of column transforms and create a derived dataset from it. */
+export function TransformPanel({ dataset }: { dataset: DatasetInfo }) {
+  const [column, setColumn] = useState("");
+  const [kind, setKind] = useState<TransformKind>("log_diff");
+  const [n, setN] = useState("1");
+  const [specs, setSpecs] = useState<TransformSpec[]>([]);
+  const [running, setRunning] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const addDataset = useProjectStore((s) => s.addDataset);
+  const setActiveDataset = useProjectStore((s) => s.setActiveDataset);
+  const addLine = useOutputStore((s) => s.addLine);
+
+  const numeric = (dataset.column_profiles ?? [])
+    .filter((p) => p.kind === "numeric")
+    .map((p) => p.name);
+  const columns = numeric.length > 0 ? numeric : dataset.columns;
+  const needsN = ["diff", "log_diff", "lag", "lead", "moving_average"].includes(kind);
+
+  function addSpec() {
+    const source = column || columns[0];
+    if (!source) return;
+    setSpecs([...specs, { ...makeTransform(kind, Math.max(1, Number(n) || 1)), column: source }]);
+  }
+
+  async function create() {
+    setRunning(true);
+    setError(null);
+    try {
+      const derived = await transformDataset(dataset.id, specs);
+      addDataset(derived);
+      setActiveDataset(derived.id);
+      setSpecs([]);
+      addLine("info", `Created ${derived.name} (${derived.columns.length} columns)`);
+    } catch (err) {
+      setError(errorMessage(err));
+    } finally {
+      setRunning(false);
+    }
+  }
+
+  return (
+    <div className="mt-4 p-3 border border-[var(--border-color)] rounded bg-[var(--bg-secondary)]">
+      <h4 className="text-xs font-semibold text-[var(--text-primary)] mb-2">Transform</h4>
+      <div className="flex flex-wrap items-center gap-2">
+        <select value={column || columns[0]} onChange={(e) => setColumn(e.target.value)} className={inputClass}>
+          {columns.map((col) => (
+            <option key={col} value={col}>
+              {col}
+            </option>
+          ))}
+        </select>
+        <select value={kind} onChange={(e) => setKind(e.target.value as TransformKind)} className={inputClass}>
+          {KINDS.map((k) => (
+            <option key={k.kind} value={k.kind}>
+              {k.label}
+            </option>
+          ))}
+        </select>
+        {needsN && (
+          <input
+            type="number"
+            min={1}
+            value={n}
+            onChange={(e) => setN(e.target.value)}
+            title={kind === "moving_average" ? "Window" : "Periods"}
+            className={`${inputClass} w-16`}
+          />
+        )}
+        <button
+          onClick={addSpec}
+          className="flex items-center gap-1 px-2 py-1.5 text-xs rounded border border-[var(--border-color)] text-[var(--text-secondary)] hover:bg-[var(--bg-hover)]"
+        >
+          <Plus size={12} /> Add
+        </button>
+      </div>
+
+      {specs.length > 0 && (
+        <ol className="mt-2 space-y-1 text-xs text-[var(--text-secondary)]">
+          {specs.map((spec, i) => (
+            <li key={i} className="flex items-center gap-2">
+              <span>
+                {i + 1}. {describe(spec)}
+              </span>
+              <button
+                onClick={() => setSpecs(specs.filter((_, j) => j !== i))}
+                className="text-[var(--text-muted)] hover:text-[var(--error)]"
+                title="Remove transform"
+              >
+                <X size={12} />
+              </button>
+            </li>
+          ))}
+        </ol>
+      )}
+
+      {error && <p className="mt-2 text-xs text-[var(--error)]">{error}</p>}
+
+      <button
+        onClick={create}
+        disabled={specs.length === 0 || running}
+        className="mt-3 px-3 py-1.5 text-xs font-medium bg-[var(--accent)] text-[var(--bg-primary)] rounded hover:bg-[var(--accent-hover)] disabled:opacity-50 disabled:cursor-not-allowed transition-colors"
+      >
+        {running ? "Creating..." : "Create derived dataset"}
+      </button>
+    </div>
+  );
+}
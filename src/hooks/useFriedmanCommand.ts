@@ -1,5 +1,6 @@
 import { useState, useCallback } from "react";
 import { useOutputStore } from "../stores/outputStore";
+import { errorMessage } from "../lib/errors";
 
 interface CommandState<T> {
   data: T | null;
@@ -31,7 +32,7 @@ export function useFriedmanCommand<T>(
         addLine("success", `${label} completed.`);
         return result;
       } catch (err) {
-        const msg = err instanceof Error ? err.message : String(err);
+        const msg = errorMessage(err);
         setError(msg);
         addLine("error", `${label} failed: ${msg}`);
         return null;
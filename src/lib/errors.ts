@@ -0,0 +1,13 @@
+import type { FriedmanError } from "../api/types";
+
+export function isFriedmanError(err: unknown): err is FriedmanError {
+  return typeof err === "object" && err !== null && "code" in err && "message" in err;
+}
+
+/** One-line description of a rejected command, with its hint if any. */
+export function errorMessage(err: unknown): string {
+  if (isFriedmanError(err)) {
+    return err.hint ? `${err.message} (${err.hint})` : err.message;
+  }
+  return err instanceof Error ? err.message : String(err);
+}
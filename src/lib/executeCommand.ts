@@ -1,7 +1,8 @@
 import * as commands from "../api/commands";
+import type { CommandResult } from "../api/types";
 
 // eslint-disable-next-line @typescript-eslint/no-explicit-any
-export async function executeCommand(command: string, params: Record<string, any>): Promise<unknown> {
+export async function executeCommand(command: string, params: Record<string, any>): Promise<CommandResult> {
   // eslint-disable-next-line @typescript-eslint/no-explicit-any
   const p = params as any;
   switch (command) {
@@ -1,9 +1,8 @@
-import { useState, useEffect } from "react";
 import { Trash2, Database } from "lucide-react";
 import { DataImport } from "../components/data/DataImport";
-import { DataGrid } from "../components/data/DataGrid";
+import { DataPreview } from "../components/data/DataPreview";
+import { TransformPanel } from "../components/data/TransformPanel";
 import { useProjectStore } from "../stores/projectStore";
-import { previewData } from "../api/commands";
 
 export default function DataPage() {
   const datasets = useProjectStore((s) => s.datasets);
@@ -11,41 +10,8 @@ export default function DataPage() {
   const setActiveDataset = useProjectStore((s) => s.setActiveDataset);
   const removeDataset = useProjectStore((s) => s.removeDataset);
 
-  const [previewColumns, setPreviewColumns] = useState<string[]>([]);
-  const [previewRows, setPreviewRows] = useState<Record<string, unknown>[]>([]);
-  const [loadingPreview, setLoadingPreview] = useState(false);
-
   const activeDataset = datasets.find((d) => d.id === activeDatasetId);
 
-  useEffect(() => {
-    if (!activeDatasetId) {
-      setPreviewColumns([]);
-      setPreviewRows([]);
-      return;
-    }
-
-    let cancelled = false;
-    setLoadingPreview(true);
-    previewData(activeDatasetId, 100)
-      .then((result) => {
-        if (!cancelled) {
-          setPreviewColumns(result.columns);
-          setPreviewRows(result.rows);
-        }
-      })
-      .catch(() => {
-        if (!cancelled) {
-          setPreviewColumns(activeDataset?.columns ?? []);
-          setPreviewRows([]);
-        }
-      })
-      .finally(() => {
-        if (!cancelled) setLoadingPreview(false);
-      });
-
-    return () => { cancelled = true; };
-  }, [activeDatasetId, activeDataset?.columns]);
-
   return (
     <div className="h-full flex flex-col overflow-hidden">
       {/* Scrollable content area */}
@@ -95,6 +61,11 @@ export default function DataPage() {
                     <p className="text-[11px] text-[var(--text-muted)] mt-1">
                       {ds.row_count.toLocaleString()} rows, {ds.columns.length} columns
                     </p>
+                    {ds.lineage && (
+                      <p className="text-[11px] text-[var(--text-muted)] truncate">
+                        Derived from {ds.lineage.parent_name}
+                      </p>
+                    )}
                   </button>
                 );
               })}
@@ -108,20 +79,8 @@ export default function DataPage() {
             <h3 className="text-sm font-semibold text-[var(--text-primary)] mb-2">
               Preview: {activeDataset.name}
             </h3>
-            {loadingPreview ? (
-              <p className="text-xs text-[var(--text-muted)]">Loading preview...</p>
-            ) : previewRows.length > 0 ? (
-              <DataGrid
-                columns={previewColumns}
-                data={previewRows}
-                maxHeight="calc(100vh - 420px)"
-              />
-            ) : (
-              <div className="text-xs text-[var(--text-muted)] bg-[var(--bg-secondary)] border border-[var(--border-color)] rounded p-4">
-                <p>Columns: {activeDataset.columns.join(", ")}</p>
-                <p className="mt-1">{activeDataset.row_count.toLocaleString()} rows</p>
-              </div>
-            )}
+            <DataPreview datasetId={activeDataset.id} maxHeight="calc(100vh - 460px)" />
+            <TransformPanel key={activeDataset.id} dataset={activeDataset} />
           </div>
         )}
       </div>
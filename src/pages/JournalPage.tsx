@@ -9,6 +9,7 @@ import { useActiveDataset } from "../hooks/useDataset";
 import { getInitialOptions, getSubMenu, getNextSteps, COMMAND_LABELS } from "../lib/journalFlow";
 import { LoadingSpinner } from "../components/common/LoadingSpinner";
 import { executeCommand } from "../lib/executeCommand";
+import { errorMessage } from "../lib/errors";
 import { getChartForCommand } from "../lib/resultCharts";
 import { IRFChart } from "../components/charts/IRFChart";
 import { FEVDChart } from "../components/charts/FEVDChart";
@@ -28,6 +29,9 @@ interface CommandFormProps {
 function CommandForm({ command, entryId, onComplete, onError }: CommandFormProps) {
   const activeDataset = useActiveDataset();
   const dataPath = activeDataset?.path ?? "";
+  const numericColumns = (activeDataset?.column_profiles ?? [])
+    .filter((p) => p.kind === "numeric")
+    .map((p) => p.name);
   const addLine = useOutputStore((s) => s.addLine);
   const updateFormStatus = useJournalStore((s) => s.updateFormStatus);
   const [loading, setLoading] = useState(false);
@@ -57,6 +61,24 @@ function CommandForm({ command, entryId, onComplete, onError }: CommandFormProps
     // Build params from fields, converting numbers
     // eslint-disable-next-line @typescript-eslint/no-explicit-any
     const params: Record<string, any> = { data: fields.data };
+    if (activeDataset && fields.data === activeDataset.path) {
+      params.dataset_id = activeDataset.id;
+      const columns = (fields.columns ?? "").split(",").map((c) => c.trim()).filter(Boolean);
+      if (columns.length > 0) params.columns = columns;
+    }
+    // "#12" is an observation number; anything else a period label
+    const addBound = (key: string, field: string) => {
+      const raw = fields[field]?.trim();
+      if (!raw) return;
+      const n = Number(raw.slice(1));
+      params[key] = raw.startsWith("#") && Number.isInteger(n) ? n : raw;
+    };
+    addBound("sample_start", "sample_start");
+    addBound("sample_end", "sample_end");
+    const tokens = (fields.missing_tokens ?? "").split(",").map((t) => t.trim()).filter(Boolean);
+    if (fields.missing_treatment || tokens.length > 0) {
+      params.missing = { treatment: fields.missing_treatment || "none", tokens };
+    }
 
     // Add optional fields based on command
     const addNum = (key: string, field: string) => {
@@ -295,11 +317,25 @@ function CommandForm({ command, entryId, onComplete, onError }: CommandFormProps
 
     try {
       const result = await executeCommand(command, params);
+      // The CLI's own interpretation (headers, test conclusions) and warnings
+      for (const line of result.text) addLine("info", line);
+      for (const warning of result.warnings) addLine("warn", warning);
+      if (result.sample) {
+        const { start, end, first, last, observations } = result.sample;
+        const span = start && end ? `${start} to ${end}` : `obs ${first} to ${last}`;
+        addLine("info", `Sample: ${span} (${observations} obs)`);
+      }
+      if (result.missing) {
+        const { columns, rows_dropped } = result.missing;
+        const filled = columns.filter((c) => c.filled > 0).map((c) => `${c.column} (${c.filled})`);
+        if (rows_dropped > 0) addLine("info", `Missing values: dropped ${rows_dropped} rows`);
+        if (filled.length > 0) addLine("info", `Missing values filled: ${filled.join(", ")}`);
+      }
       addLine("success", `${label} completed.`);
       updateFormStatus(entryId, "done", params);
-      onComplete(command, params, result);
+      onComplete(command, params, result.payload);
     } catch (err) {
-      const msg = err instanceof Error ? err.message : String(err);
+      const msg = errorMessage(err);
       addLine("error", `${label} failed: ${msg}`);
       updateFormStatus(entryId, "done");
       onError(msg);
@@ -849,6 +885,73 @@ function CommandForm({ command, entryId, onComplete, onError }: CommandFormProps
         </p>
       )}
 
+      {activeDataset && (
+        <div className="mb-3">
+          <label className="block text-xs text-[var(--text-secondary)] mb-1 font-medium">
+            Variables (in order)
+          </label>
+          <input
+            type="text"
+            value={fields.columns ?? ""}
+            onChange={(e) => setField("columns", e.target.value)}
+            placeholder={numericColumns.join(", ") || "all columns"}
+            className="w-full px-2 py-1.5 text-xs bg-[var(--bg-surface)] border border-[var(--border-color)] rounded text-[var(--text-primary)] placeholder:text-[var(--text-muted)] outline-none focus:border-[var(--accent)] transition-colors"
+          />
+          <p className="text-[10px] text-[var(--text-muted)] mt-1">
+            Comma-separated; the order sets the Cholesky ordering. Leave empty to use the whole file.
+          </p>
+        </div>
+      )}
+
+      <div className="mb-3 grid grid-cols-2 gap-2">
+        {(["sample_start", "sample_end"] as const).map((key) => (
+          <div key={key}>
+            <label className="block text-xs text-[var(--text-secondary)] mb-1 font-medium">
+              {key === "sample_start" ? "Sample start" : "Sample end"}
+            </label>
+            <input
+              type="text"
+              value={fields[key] ?? ""}
+              onChange={(e) => setField(key, e.target.value)}
+              placeholder={key === "sample_start" ? "e.g. 1985Q1 or #5" : "e.g. 2019Q4"}
+              className="w-full px-2 py-1.5 text-xs bg-[var(--bg-surface)] border border-[var(--border-color)] rounded text-[var(--text-primary)] placeholder:text-[var(--text-muted)] outline-none focus:border-[var(--accent)] transition-colors"
+            />
+          </div>
+        ))}
+      </div>
+
+      <div className="mb-3 grid grid-cols-2 gap-2">
+        <div>
+          <label className="block text-xs text-[var(--text-secondary)] mb-1 font-medium">
+            Missing values
+          </label>
+          <select
+            value={fields.missing_treatment ?? ""}
+            onChange={(e) => setField("missing_treatment", e.target.value)}
+            className="w-full px-2 py-1.5 text-xs bg-[var(--bg-surface)] border border-[var(--border-color)] rounded text-[var(--text-primary)] outline-none focus:border-[var(--accent)] transition-colors"
+          >
+            <option value="">Leave as is</option>
+            <option value="trim">Drop leading/trailing</option>
+            <option value="listwise">Listwise deletion</option>
+            <option value="linear">Linear interpolation</option>
+            <option value="spline">Spline interpolation</option>
+            <option value="locf">Carry last value forward</option>
+          </select>
+        </div>
+        <div>
+          <label className="block text-xs text-[var(--text-secondary)] mb-1 font-medium">
+            Also missing
+          </label>
+          <input
+            type="text"
+            value={fields.missing_tokens ?? ""}
+            onChange={(e) => setField("missing_tokens", e.target.value)}
+            placeholder="e.g. -999"
+            className="w-full px-2 py-1.5 text-xs bg-[var(--bg-surface)] border border-[var(--border-color)] rounded text-[var(--text-primary)] placeholder:text-[var(--text-muted)] outline-none focus:border-[var(--accent)] transition-colors"
+          />
+        </div>
+      </div>
+
       {renderFields()}
 
       <button